@@ -0,0 +1,172 @@
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use cosmic_comp_config::input::{
+    AccelConfig, AccelProfile, ClickMethod, DeviceState, InputConfig, ScrollConfig, ScrollMethod,
+    TapConfig,
+};
+use cosmolith::compositor::{Compositor, CompositorResult};
+use cosmolith::event::Event;
+use cosmolith::event::input::{MouseEvent, TouchpadEvent};
+use criterion::{Criterion, criterion_group, criterion_main};
+
+// Number of intermediate values cosmic-settings writes while a slider is dragged.
+const SLIDER_STEPS: usize = 100;
+
+/// Backend that only counts how many events reach it.
+#[derive(Default)]
+struct NoopCompositor {
+    applied: AtomicUsize,
+}
+
+impl Compositor for NoopCompositor {
+    fn init(&mut self) -> CompositorResult {
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "noop"
+    }
+
+    fn is_running(&self) -> bool {
+        true
+    }
+
+    fn capabilities(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn supports(&self, _event: &Event) -> bool {
+        true
+    }
+
+    fn apply_event(&self, _event: Event) -> CompositorResult {
+        self.applied.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn reload(&self) -> CompositorResult {
+        Ok(())
+    }
+
+    fn shutdown(&self) -> CompositorResult {
+        Ok(())
+    }
+}
+
+fn touchpad_config(scroll_factor: f64) -> InputConfig {
+    InputConfig {
+        state: DeviceState::Enabled,
+        acceleration: Some(AccelConfig {
+            profile: Some(AccelProfile::Adaptive),
+            speed: 0.0,
+        }),
+        calibration: None,
+        click_method: Some(ClickMethod::Clickfinger),
+        disable_while_typing: Some(true),
+        left_handed: Some(false),
+        middle_button_emulation: Some(false),
+        rotation_angle: None,
+        scroll_config: Some(ScrollConfig {
+            method: Some(ScrollMethod::TwoFinger),
+            natural_scroll: Some(true),
+            scroll_button: None,
+            scroll_factor: Some(scroll_factor),
+        }),
+        tap_config: Some(TapConfig {
+            enabled: true,
+            button_map: None,
+            drag: true,
+            drag_lock: false,
+        }),
+        map_to_output: None,
+    }
+}
+
+/// A config that differs from `touchpad_config(1.0)` in most user-facing fields.
+fn changed_config() -> InputConfig {
+    let mut config = touchpad_config(1.5);
+    config.acceleration = Some(AccelConfig {
+        profile: Some(AccelProfile::Flat),
+        speed: 0.4,
+    });
+    config.click_method = Some(ClickMethod::ButtonAreas);
+    config.disable_while_typing = Some(false);
+    config.left_handed = Some(true);
+    if let Some(scroll) = config.scroll_config.as_mut() {
+        scroll.natural_scroll = Some(false);
+    }
+    if let Some(tap) = config.tap_config.as_mut() {
+        tap.enabled = false;
+    }
+    config
+}
+
+/// Consecutive configs produced by dragging the scroll speed slider.
+fn slider_drag() -> Vec<InputConfig> {
+    (0..=SLIDER_STEPS)
+        .map(|step| touchpad_config(1.0 + step as f64 / SLIDER_STEPS as f64))
+        .collect()
+}
+
+fn bench_diff(c: &mut Criterion) {
+    let old = touchpad_config(1.0);
+    let new = changed_config();
+
+    c.bench_function("diff/touchpad", |b| {
+        b.iter(|| TouchpadEvent::from(black_box(old.clone()), black_box(new.clone())))
+    });
+    c.bench_function("diff/mouse", |b| {
+        b.iter(|| MouseEvent::from(black_box(old.clone()), black_box(new.clone())))
+    });
+    c.bench_function("diff/unchanged", |b| {
+        b.iter(|| TouchpadEvent::from(black_box(old.clone()), black_box(old.clone())))
+    });
+}
+
+fn bench_dispatch(c: &mut Criterion) {
+    let drag = slider_drag();
+
+    // Every intermediate value is diffed and dispatched, as the daemon does today.
+    let uncoalesced = |compositor: &NoopCompositor| {
+        for pair in drag.windows(2) {
+            for event in TouchpadEvent::from(pair[0].clone(), pair[1].clone()) {
+                if compositor.supports(&event) {
+                    let _ = compositor.apply_event(event);
+                }
+            }
+        }
+    };
+    // Only the first and last value of the burst are diffed, which is the best any
+    // coalescing stage can do.
+    let coalesced = |compositor: &NoopCompositor| {
+        let (first, last) = (drag.first().unwrap(), drag.last().unwrap());
+        for event in TouchpadEvent::from(first.clone(), last.clone()) {
+            if compositor.supports(&event) {
+                let _ = compositor.apply_event(event);
+            }
+        }
+    };
+
+    let before = NoopCompositor::default();
+    uncoalesced(&before);
+    let after = NoopCompositor::default();
+    coalesced(&after);
+    println!(
+        "slider drag ({SLIDER_STEPS} steps): {} commands without coalescing, {} with",
+        before.applied.load(Ordering::Relaxed),
+        after.applied.load(Ordering::Relaxed)
+    );
+
+    c.bench_function("dispatch/slider_drag", |b| {
+        let compositor = NoopCompositor::default();
+        b.iter(|| uncoalesced(black_box(&compositor)))
+    });
+    c.bench_function("dispatch/slider_drag_coalesced", |b| {
+        let compositor = NoopCompositor::default();
+        b.iter(|| coalesced(black_box(&compositor)))
+    });
+}
+
+criterion_group!(benches, bench_diff, bench_dispatch);
+criterion_main!(benches);
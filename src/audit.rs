@@ -0,0 +1,92 @@
+// Append-only record of the settings cosmolith changed, for users and support.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, SecondsFormat};
+
+use crate::event::SourcedEvent;
+
+/// Human-readable log of successfully applied settings, one line per setting.
+///
+/// The file is opened in append mode for every entry rather than held open, so
+/// logrotate's default move-and-create rotation works without signalling cosmolith.
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    /// `$XDG_STATE_HOME/cosmolith/audit.log`, falling back to `~/.local/state`.
+    pub fn default_path() -> Option<PathBuf> {
+        Some(dirs::state_dir()?.join("cosmolith").join("audit.log"))
+    }
+
+    /// Create the parent directory so the first write does not fail.
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+        Ok(Self { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append an entry for a setting that `backend` applied without error.
+    pub fn record(&self, backend: &str, sourced: &SourcedEvent) -> io::Result<()> {
+        let line = entry(Local::now(), backend, sourced);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{line}")
+    }
+}
+
+fn entry(at: DateTime<Local>, backend: &str, sourced: &SourcedEvent) -> String {
+    format!(
+        "{} {backend}: {} = {:?} (from {})",
+        at.to_rfc3339_opts(SecondsFormat::Secs, false),
+        sourced.event.name(),
+        sourced.event,
+        sourced.source,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::event::input::{InputEvent, TouchpadEvent};
+    use crate::event::{Event, Source};
+
+    #[test]
+    fn entry_has_time_backend_setting_and_source() {
+        let at = Local.with_ymd_and_hms(2025, 3, 1, 9, 30, 0).unwrap();
+        let sourced = SourcedEvent::new(
+            Event::Input(InputEvent::touchpad(TouchpadEvent::AccelSpeed(0.5))),
+            Source {
+                namespace: "com.system76.CosmicComp",
+                key: Some("input_touchpad"),
+            },
+        );
+
+        let line = entry(at, "Sway", &sourced);
+        assert!(line.starts_with("2025-03-01T09:30:00"), "{line}");
+        assert!(
+            line.ends_with(
+                " Sway: touchpad_accel_speed = Input(TouchPad(AccelSpeed(0.5))) \
+                 (from com.system76.CosmicComp/input_touchpad)"
+            ),
+            "{line}"
+        );
+    }
+}
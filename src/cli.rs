@@ -0,0 +1,289 @@
+use std::error::Error as StdError;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::{Parser, Subcommand};
+
+use cosmolith::Session;
+use cosmolith::compositor::BACKENDS;
+use cosmolith::debounce::{Category, DebounceConfig};
+use cosmolith::error::Error;
+use cosmolith::event::catalog::{EVENTS, describe};
+
+/// Sync COSMIC settings to the running compositor.
+#[derive(Debug, Parser)]
+#[command(name = "cosmolith", version, about)]
+pub struct Cli {
+    /// Print every event cosmolith can emit, with its payload and a short description, then exit.
+    #[arg(long)]
+    pub list_events: bool,
+
+    /// Use this backend, as listed by --matrix, instead of detecting the desktop. Exits
+    /// with an error when it cannot connect rather than falling back. Also picks the
+    /// backend --list-events reports on.
+    #[arg(long, value_name = "NAME", value_parser = parse_backend_name)]
+    pub compositor: Option<String>,
+
+    /// Print which events each backend supports, then exit.
+    #[arg(long)]
+    pub matrix: bool,
+
+    /// Debounce window, in milliseconds, for every event category that is debounced at
+    /// all (scroll and pointer by default); categories applied immediately stay so.
+    /// `--debounce` still overrides single categories.
+    #[arg(long, value_name = "MS")]
+    pub debounce_ms: Option<u64>,
+
+    /// Debounce window for an event category, as `<category>=<ms>`. Repeatable.
+    /// Categories: keyboard (default 0), scroll (150), pointer (150), device (0), shortcut (0).
+    #[arg(long = "debounce", value_name = "CATEGORY=MS", value_parser = parse_debounce)]
+    pub debounce: Vec<(Category, Duration)>,
+
+    /// Events that skip debouncing, comma separated. Defaults to layout, variant, options,
+    /// numlock and device enable/disable. Pass the flag with no events to debounce everything.
+    #[arg(
+        long,
+        value_name = "EVENTS",
+        num_args = 0..,
+        value_delimiter = ',',
+        value_parser = parse_event_name
+    )]
+    pub immediate: Option<Vec<&'static str>>,
+
+    /// Put every setting the detected backend supports back to its libinput/XKB default,
+    /// undoing cosmolith's overrides, then exit. Needs --yes.
+    #[arg(long)]
+    pub reset_backend: bool,
+
+    /// Confirm a destructive command such as --reset-backend.
+    #[arg(long)]
+    pub yes: bool,
+
+    /// Also apply pointer settings to X11 devices with `xinput` when DISPLAY is set, for
+    /// X11 apps running under Xwayland.
+    #[arg(long)]
+    pub also_x11: bool,
+
+    /// Append every applied setting, with time and backend, to a log file. Defaults to
+    /// `$XDG_STATE_HOME/cosmolith/audit.log` when no path is given.
+    #[arg(long, value_name = "PATH", num_args = 0..=1)]
+    pub audit_log: Option<Option<PathBuf>>,
+
+    /// Append every event sent to a backend, with the time and result, to FILE as JSON
+    /// lines that `cosmolith replay` can apply again.
+    #[arg(long, value_name = "FILE")]
+    pub record: Option<PathBuf>,
+
+    /// Path of the control socket used to pause and resume syncing at runtime.
+    /// Defaults to `$XDG_RUNTIME_DIR/cosmolith.sock`.
+    #[arg(long, value_name = "PATH")]
+    pub control_socket: Option<PathBuf>,
+
+    /// Broadcast every event as a JSON line to clients of a Unix socket. Defaults to
+    /// `$XDG_RUNTIME_DIR/cosmolith-events.sock` when no path is given.
+    #[cfg(feature = "emit")]
+    #[arg(long, value_name = "PATH", num_args = 0..=1)]
+    pub emit_socket: Option<Option<PathBuf>>,
+
+    /// Wait this many milliseconds before detecting the compositor and syncing, for
+    /// sessions where cosmolith starts before the compositor is up.
+    #[arg(long, value_name = "MS", default_value_t = 0)]
+    pub startup_delay: u64,
+
+    /// Skip applying the current COSMIC input settings at startup; only changes made
+    /// while cosmolith runs are synced. The startup sync is on by default: without it a
+    /// new session keeps the compositor's own settings until something changes in COSMIC.
+    #[arg(long)]
+    pub no_sync_on_start: bool,
+
+    /// Reconnects after a failed Sway or KDE IPC call before giving up. Overrides
+    /// `[reconnect] max_attempts` from the config file.
+    #[arg(long, value_name = "N")]
+    pub reconnect_attempts: Option<u32>,
+
+    /// Log at debug level, including every event the watchers send. `RUST_LOG` picks the
+    /// levels otherwise, e.g. `RUST_LOG=cosmolith=warn`.
+    #[arg(long)]
+    pub debug: bool,
+
+    /// Log every backend IPC call (keyword, command or key) with how long it took.
+    #[arg(long)]
+    pub trace_ipc: bool,
+
+    /// Print every command, keyword or config write the backend would make instead of
+    /// making it. Devices and settings are still read from the running compositor.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Apply the current COSMIC input settings to the running compositor once and exit.
+    /// Exits with an error when any setting could not be applied.
+    Apply,
+    /// Apply and revert a probe value for every supported setting on the running
+    /// compositor, and report which handlers took effect.
+    #[command(hide = true)]
+    SelfTest,
+    /// Apply the events of a --record FILE again, in order, to the running compositor or
+    /// the one given with --compositor.
+    Replay { file: PathBuf },
+    /// Copy the COSMIC settings cosmolith reads into DIR, for `replay-config`.
+    CaptureConfig { dir: PathBuf },
+    /// Run a `capture-config` snapshot through the pipeline and print what BACKEND would
+    /// be sent, without touching the session's config or any compositor.
+    ReplayConfig {
+        dir: PathBuf,
+        /// Backend whose capabilities to use, as listed by --matrix.
+        #[arg(long, default_value = "Sway", value_parser = parse_backend_name)]
+        backend: String,
+    },
+}
+
+impl Cli {
+    /// `config`, from the `[debounce]` section of the cosmolith config, with the debounce
+    /// flags applied over it.
+    pub fn debounce_config(&self, mut config: DebounceConfig) -> DebounceConfig {
+        if let Some(ms) = self.debounce_ms {
+            // Keyboard, device and shortcut changes are not slider-driven; delaying them
+            // would only make them feel slow.
+            for category in Category::ALL {
+                if !config.window(category).is_zero() {
+                    config.set_window(category, Duration::from_millis(ms));
+                }
+            }
+        }
+        for (category, window) in &self.debounce {
+            config.set_window(*category, *window);
+        }
+        if let Some(immediate) = &self.immediate {
+            config.immediate = immediate.clone();
+        }
+        config
+    }
+}
+
+/// The session for the backend forced with `--compositor`, or the detected one.
+pub fn open_session(forced: Option<&str>) -> Result<Session, Box<dyn StdError + Send + Sync>> {
+    match forced {
+        Some(backend) => Session::for_backend(backend),
+        None => Session::detect(),
+    }
+}
+
+fn parse_debounce(value: &str) -> Result<(Category, Duration), String> {
+    let (category, ms) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected <category>=<ms>, got `{value}`"))?;
+    let category = Category::from_name(category.trim()).ok_or_else(|| {
+        let known: Vec<_> = Category::ALL.iter().map(|category| category.name()).collect();
+        format!("unknown category `{category}`, expected one of {}", known.join(", "))
+    })?;
+    let ms = ms
+        .trim()
+        .parse::<u64>()
+        .map_err(|err| format!("invalid milliseconds `{ms}`: {err}"))?;
+    Ok((category, Duration::from_millis(ms)))
+}
+
+fn parse_backend_name(value: &str) -> Result<String, String> {
+    BACKENDS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(value))
+        .map(|(name, _)| name.to_string())
+        .ok_or_else(|| {
+            let known: Vec<_> = BACKENDS.iter().map(|(name, _)| *name).collect();
+            format!("unknown backend `{value}`, expected one of {}", known.join(", "))
+        })
+}
+
+fn parse_event_name(value: &str) -> Result<&'static str, String> {
+    describe(value.trim())
+        .map(|info| info.name)
+        .ok_or_else(|| format!("unknown event `{value}`; see --list-events"))
+}
+
+/// Print the event catalog. With a backend, each event is prefixed by whether that
+/// backend applies it.
+pub fn print_event_list(backend: Option<&str>) {
+    let name_width = EVENTS.iter().map(|info| info.name.len()).max().unwrap_or(0);
+    let payload_width = EVENTS.iter().map(|info| info.payload.len()).max().unwrap_or(0);
+    let capabilities = backend.and_then(|backend| {
+        BACKENDS
+            .iter()
+            .find(|(name, _)| *name == backend)
+            .map(|(_, capabilities)| *capabilities)
+    });
+
+    if let Some(backend) = backend {
+        println!("Support on {backend}:");
+    }
+    for info in EVENTS {
+        if let Some(capabilities) = capabilities {
+            let mark = if capabilities.contains(&info.name) { "supported" } else { "-" };
+            print!("{mark:9}  ");
+        }
+        println!(
+            "{:name_width$}  {:payload_width$}  {} ({})",
+            info.name, info.payload, info.description, info.variant
+        );
+    }
+}
+
+pub fn print_support_matrix() {
+    let name_width = EVENTS.iter().map(|info| info.name.len()).max().unwrap_or(0);
+
+    print!("{:name_width$}", "event");
+    for (backend, _) in BACKENDS {
+        print!("  {backend}");
+    }
+    println!();
+
+    for info in EVENTS {
+        print!("{:name_width$}", info.name);
+        for (backend, capabilities) in BACKENDS {
+            let mark = if capabilities.contains(&info.name) { "yes" } else { "-" };
+            print!("  {mark:^width$}", width = backend.len());
+        }
+        println!();
+    }
+}
+
+/// Actionable guidance for common backend initialization failures.
+pub fn init_hint(err: &(dyn StdError + Send + Sync + 'static)) -> Option<&'static str> {
+    match err.downcast_ref::<Error>()? {
+        Error::MissingEnvVar {
+            var: "HYPRLAND_INSTANCE_SIGNATURE",
+        } => Some("Hyprland not detected; are you running inside a Hyprland session?"),
+        Error::MissingEnvVar { var: "SWAYSOCK" } => {
+            Some("Sway not detected; is SWAYSOCK exported to this process?")
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn debounce_config(args: &[&str]) -> DebounceConfig {
+        let cli = Cli::parse_from(std::iter::once("cosmolith").chain(args.iter().copied()));
+        cli.debounce_config(DebounceConfig::default())
+    }
+
+    #[test]
+    fn debounce_ms_leaves_immediate_categories_alone() {
+        let config = debounce_config(&["--debounce-ms", "300"]);
+        assert_eq!(config.scroll, Duration::from_millis(300));
+        assert_eq!(config.pointer, Duration::from_millis(300));
+        assert_eq!(config.keyboard, Duration::ZERO);
+        assert_eq!(config.device, Duration::ZERO);
+        assert_eq!(config.shortcut, Duration::ZERO);
+
+        let config = debounce_config(&["--debounce-ms", "300", "--debounce", "device=50"]);
+        assert_eq!(config.device, Duration::from_millis(50));
+    }
+}
@@ -0,0 +1,62 @@
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+
+use crate::compositor::CompositorResult;
+use crate::compositor::trace;
+use crate::error::Error;
+use crate::event::color_temp::ColorTempEvent;
+
+/// Compositor night light interface. Like `Workspace`, the default returns
+/// `Error::NotImplemented` so a desktop without night light support is reported.
+pub trait ColorTemp {
+    fn apply_color_temp_event(&self, _event: ColorTempEvent) -> CompositorResult {
+        Err(Error::NotImplemented {
+            handler: "color_temp",
+        })
+    }
+}
+
+/// A `gammastep -O` process holding the night light temperature, for wlroots compositors
+/// without a night light of their own.
+///
+/// wlroots restores the gamma ramps when the client that set them exits, so the process
+/// keeps running until the temperature changes, night light is turned off, or this is
+/// dropped.
+#[derive(Debug, Default)]
+pub struct Gammastep(Mutex<Option<Child>>);
+
+impl Gammastep {
+    pub fn apply(&self, backend: &'static str, event: ColorTempEvent) -> CompositorResult {
+        let mut child = self.0.lock().unwrap_or_else(|err| err.into_inner());
+        if let Some(mut previous) = child.take() {
+            let _ = previous.kill();
+            let _ = previous.wait();
+        }
+        if !event.enabled {
+            return Ok(());
+        }
+        let temperature = event.temperature.to_string();
+        *child = trace::write(
+            backend,
+            || format!("gammastep -P -O {temperature}"),
+            || {
+                Command::new("gammastep")
+                    .args(["-P", "-O", &temperature])
+                    .stdin(Stdio::null())
+                    .spawn()
+                    .map(Some)
+            },
+        )?;
+        Ok(())
+    }
+}
+
+impl Drop for Gammastep {
+    fn drop(&mut self) {
+        let child = self.0.get_mut().unwrap_or_else(|err| err.into_inner());
+        if let Some(mut child) = child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
@@ -0,0 +1,150 @@
+// Helpers shared by backends that are configured through files on disk.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+/// Read a config file, or return `default` when it does not exist yet.
+pub fn read_or(path: &Path, default: &str) -> io::Result<String> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(contents),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(default.to_string()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Replace `path` with `contents` without ever exposing a half-written file: write a
+/// temporary sibling, fsync it, then rename it over the original.
+pub fn atomic_write(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+    let tmp = path.with_file_name(format!(
+        ".{}.cosmolith-{}",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+
+    let mut file = fs::File::create(&tmp)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    drop(file);
+
+    fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })
+}
+
+/// Set `KEY=value` in a shell-style environment file, replacing an existing assignment
+/// or appending a new one. Other lines are preserved as-is.
+pub fn set_env_line(contents: &str, key: &str, value: &str) -> String {
+    let assignment = format!("{key}={value}");
+    let mut found = false;
+    let mut lines: Vec<String> = contents
+        .lines()
+        .map(|line| {
+            let is_key = line
+                .trim_start()
+                .split_once('=')
+                .is_some_and(|(name, _)| name.trim() == key);
+            if is_key && !found {
+                found = true;
+                assignment.clone()
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    if !found {
+        lines.push(assignment);
+    }
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+/// Set `key=value` in `[section]` of an INI file, replacing an existing entry or adding
+/// one at the end of the section. The section is appended if the file has none. Other
+/// sections, keys and comments are preserved as-is.
+///
+/// No spaces around `=`: Weston's parser keeps them as part of the key.
+pub fn set_ini_key(contents: &str, section: &str, key: &str, value: &str) -> String {
+    let assignment = format!("{key}={value}");
+    let header = format!("[{section}]");
+    let mut lines: Vec<String> = contents.lines().map(str::to_string).collect();
+
+    let Some(start) = lines.iter().position(|line| line.trim() == header) else {
+        if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+            lines.push(String::new());
+        }
+        lines.push(header);
+        lines.push(assignment);
+        return join_lines(lines);
+    };
+    let end = lines[start + 1..]
+        .iter()
+        .position(|line| line.trim_start().starts_with('['))
+        .map_or(lines.len(), |offset| start + 1 + offset);
+
+    let existing = (start + 1..end).find(|&i| {
+        lines[i]
+            .split_once('=')
+            .is_some_and(|(name, _)| !name.trim_start().starts_with('#') && name.trim() == key)
+    });
+    match existing {
+        Some(i) => lines[i] = assignment,
+        None => {
+            // Keep blank lines that separate this section from the next one.
+            let last = (start..end)
+                .rev()
+                .find(|&i| !lines[i].trim().is_empty())
+                .unwrap_or(start);
+            lines.insert(last + 1, assignment);
+        }
+    }
+    join_lines(lines)
+}
+
+fn join_lines(lines: Vec<String>) -> String {
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ini_key_is_replaced_within_its_section() {
+        let ini = "[core]\nxkb_layout = fr\n\n[input]\n# comment\nxkb_layout = us\n\n[idle]\n";
+        assert_eq!(
+            set_ini_key(ini, "input", "xkb_layout", "de"),
+            "[core]\nxkb_layout = fr\n\n[input]\n# comment\nxkb_layout=de\n\n[idle]\n"
+        );
+    }
+
+    #[test]
+    fn ini_key_is_added_to_the_end_of_its_section() {
+        let ini = "[input]\nnatural_scroll = true\n\n[idle]\ntimeout = 60\n";
+        assert_eq!(
+            set_ini_key(ini, "input", "tap_to_click", "false"),
+            "[input]\nnatural_scroll = true\ntap_to_click=false\n\n[idle]\ntimeout = 60\n"
+        );
+    }
+
+    #[test]
+    fn missing_ini_section_is_appended() {
+        assert_eq!(set_ini_key("", "input", "xkb_layout", "us"), "[input]\nxkb_layout=us\n");
+        assert_eq!(
+            set_ini_key("[core]\nplugins = ipc\n", "input", "xkb_layout", "us"),
+            "[core]\nplugins = ipc\n\n[input]\nxkb_layout=us\n"
+        );
+    }
+}
@@ -0,0 +1,59 @@
+// A backend that prints events instead of sending them, for `replay-config`.
+
+use crate::compositor::{BACKENDS, Compositor, CompositorResult};
+use crate::event::Event;
+
+/// Reports what another backend would be asked to do, without talking to any compositor.
+pub struct DryRun {
+    backend: &'static str,
+    capabilities: &'static [&'static str],
+}
+
+impl DryRun {
+    /// A dry run of the `BACKENDS` entry called `backend`, using its capabilities.
+    pub fn emulating(backend: &str) -> Option<DryRun> {
+        BACKENDS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(backend))
+            .map(|&(backend, capabilities)| DryRun {
+                backend,
+                capabilities,
+            })
+    }
+
+    /// Name of the emulated backend.
+    pub fn backend(&self) -> &'static str {
+        self.backend
+    }
+}
+
+impl Compositor for DryRun {
+    fn init(&mut self) -> CompositorResult {
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "dry run"
+    }
+
+    fn is_running(&self) -> bool {
+        true
+    }
+
+    fn capabilities(&self) -> &'static [&'static str] {
+        self.capabilities
+    }
+
+    fn apply_event(&self, event: Event) -> CompositorResult {
+        println!("  [{}] {event:?}", self.backend);
+        Ok(())
+    }
+
+    fn reload(&self) -> CompositorResult {
+        Ok(())
+    }
+
+    fn shutdown(&self) -> CompositorResult {
+        Ok(())
+    }
+}
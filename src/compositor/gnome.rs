@@ -1,33 +1,155 @@
+use crate::compositor::color_temp::ColorTemp;
 use crate::compositor::input::{Input, InputResult};
+use crate::compositor::libinput;
+use crate::compositor::trace;
+use crate::compositor::theme::{self, Theme};
+use crate::compositor::workspace::Workspace;
 use crate::compositor::{Compositor, CompositorResult};
+use crate::error::Error;
 use crate::event::input::InputEvent;
+use std::sync::Mutex;
 use crate::event::Event;
+use crate::event::color_temp::ColorTempEvent;
+use crate::event::cursor::CursorEvent;
+use crate::event::theme::ColorScheme;
 use gio::prelude::*;
-use gio::Settings;
+use gio::{Settings, SettingsSchemaSource};
+use cosmic_comp_config::input::{AccelConfig, AccelProfile, ClickMethod};
+
+/// Events with a real handler in this backend.
+pub const CAPABILITIES: &[&str] = &[
+    "keyboard_layout",
+    "keyboard_variant",
+    "keyboard_repeat_delay",
+    "keyboard_repeat_rate",
+    "touchpad_acceleration",
+    "touchpad_accel_speed",
+    "touchpad_accel_profile",
+    "touchpad_click_method",
+    "touchpad_tap_enabled",
+    "touchpad_natural_scroll",
+    "touchpad_disable_while_typing",
+    "touchpad_left_handed",
+    "mouse_acceleration",
+    "mouse_accel_speed",
+    "mouse_accel_profile",
+    "mouse_left_handed",
+    "mouse_natural_scroll",
+    "theme_mode",
+    "cursor_theme",
+    "cursor_size",
+    "color_temp",
+];
+
+const TOUCHPAD_SCHEMA: &str = "org.gnome.desktop.peripherals.touchpad";
+const MOUSE_SCHEMA: &str = "org.gnome.desktop.peripherals.mouse";
+const KEYBOARD_SCHEMA: &str = "org.gnome.desktop.peripherals.keyboard";
+const INPUT_SOURCES_SCHEMA: &str = "org.gnome.desktop.input-sources";
+const COLOR_SCHEMA: &str = "org.gnome.settings-daemon.plugins.color";
 
 pub struct Gnome {
-    touchpad_settings: Settings,
-    mouse_settings: Settings,
+    // Created in `init` once the schemas are known to exist: `Settings::new` aborts the
+    // process on a missing schema, which happens when a non-GNOME session merely reports
+    // GNOME in XDG_CURRENT_DESKTOP.
+    touchpad_settings: Option<Settings>,
+    mouse_settings: Option<Settings>,
+    keyboard_settings: Option<Settings>,
+    input_source_settings: Option<Settings>,
+    // `sources` pairs each layout with its variant, which COSMIC sends separately.
+    xkb: Mutex<Xkb>,
+}
+
+/// Last layout and variant lists, comma-separated as in XKB.
+#[derive(Debug, Default)]
+struct Xkb {
+    layout: Option<String>,
+    variant: String,
 }
 
 impl Gnome {
     pub fn new() -> Self {
         Self {
-            touchpad_settings: Settings::new("org.gnome.desktop.peripherals.touchpad"),
-            mouse_settings: Settings::new("org.gnome.desktop.peripherals.mouse"),
+            touchpad_settings: None,
+            mouse_settings: None,
+            keyboard_settings: None,
+            input_source_settings: None,
+            xkb: Mutex::default(),
         }
     }
 
+    fn touchpad(&self) -> crate::error::Result<&Settings> {
+        self.touchpad_settings
+            .as_ref()
+            .ok_or(Error::NotInitialized { backend: "GNOME" })
+    }
+
+    fn mouse(&self) -> crate::error::Result<&Settings> {
+        self.mouse_settings
+            .as_ref()
+            .ok_or(Error::NotInitialized { backend: "GNOME" })
+    }
+
+    fn keyboard(&self) -> crate::error::Result<&Settings> {
+        self.keyboard_settings
+            .as_ref()
+            .ok_or(Error::NotInitialized { backend: "GNOME" })
+    }
+
+    fn input_sources(&self) -> crate::error::Result<&Settings> {
+        self.input_source_settings
+            .as_ref()
+            .ok_or(Error::NotInitialized { backend: "GNOME" })
+    }
+
+    fn set_uint(&self, settings: &Settings, key: &str, value: u32) -> InputResult {
+        trace::write(
+            "GNOME",
+            || format!("{key}={value}"),
+            || settings.set_uint(key, value),
+        )?;
+        Ok(())
+    }
+
+    /// Apply `update` to the last layout and variant and rewrite the `xkb` entries of
+    /// `sources` from them, keeping input methods where they are. Before COSMIC sent a
+    /// layout, a variant goes with the layouts `sources` already has; nothing is written
+    /// when it has none, so a variant alone does not wipe them.
+    fn update_sources(&self, update: impl FnOnce(&mut Xkb)) -> InputResult {
+        let settings = self.input_sources()?;
+        let mut xkb = self.xkb.lock().unwrap_or_else(|err| err.into_inner());
+        update(&mut xkb);
+        let current: Vec<(String, String)> = settings.value("sources").get().unwrap_or_default();
+        if xkb.layout.is_none() {
+            xkb.layout = Some(xkb_layouts(&current)).filter(|layout| !layout.is_empty());
+        }
+        let Some(layout) = xkb.layout.as_deref() else {
+            return Ok(());
+        };
+        let sources = replace_xkb_sources(&current, xkb_sources(layout, &xkb.variant));
+        trace::write(
+            "GNOME",
+            || format!("sources={sources:?}"),
+            || settings.set_value("sources", &sources.to_variant()),
+        )?;
+        Ok(())
+    }
+
     fn set_str(&self, settings: &Settings, key: &str, value: &str) -> InputResult {
-        settings
-            .set_string(key, value)
-            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+        trace::write(
+            "GNOME",
+            || format!("{key}={value:?}"),
+            || settings.set_string(key, value),
+        )?;
+        Ok(())
     }
 
     fn set_bool(&self, settings: &Settings, key: &str, value: bool) -> InputResult {
-        settings
-            .set_boolean(key, value)
-            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+        trace::write(
+            "GNOME",
+            || format!("{key}={value}"),
+            || settings.set_boolean(key, value),
+        )?;
+        Ok(())
     }
 
     fn set_opt_bool(&self, settings: &Settings, key: &str, value: Option<bool>) -> InputResult {
@@ -38,13 +160,56 @@ impl Gnome {
     }
 
     fn set_double(&self, settings: &Settings, key: &str, val: f64) -> InputResult {
-        settings.set_double(key, val)?;
+        trace::write("GNOME", || format!("{key}={val}"), || settings.set_double(key, val))?;
+        Ok(())
+    }
+
+    fn set_accel_speed(&self, settings: &Settings, speed: f64) -> InputResult {
+        self.set_double(settings, "speed", speed.clamp(-1.0, 1.0))
+    }
+
+    /// `accel-profile` is an enum; an unset profile goes back to the device default.
+    fn set_accel_profile(&self, settings: &Settings, profile: Option<AccelProfile>) -> InputResult {
+        let value = match profile {
+            Some(profile) => match libinput::AccelProfile::try_from(&profile)? {
+                libinput::AccelProfile::Flat => "flat",
+                libinput::AccelProfile::Adaptive => "adaptive",
+            },
+            None => "default",
+        };
+        self.set_str(settings, "accel-profile", value)
+    }
+
+    fn acceleration(&self, settings: &Settings, accel: Option<AccelConfig>) -> InputResult {
+        if let Some(accel) = accel {
+            self.set_accel_speed(settings, accel.speed)?;
+            self.set_accel_profile(settings, accel.profile)?;
+        }
         Ok(())
     }
 }
 
 impl Compositor for Gnome {
     fn init(&mut self) -> CompositorResult {
+        let source = SettingsSchemaSource::default().ok_or_else(|| {
+            Error::DetectionFailed("no GSettings schemas are installed".to_string())
+        })?;
+        for schema in [
+            TOUCHPAD_SCHEMA,
+            MOUSE_SCHEMA,
+            KEYBOARD_SCHEMA,
+            INPUT_SOURCES_SCHEMA,
+        ] {
+            if source.lookup(schema, true).is_none() {
+                let reason = format!("GSettings schema {schema} is not installed");
+                return Err(Error::DetectionFailed(reason));
+            }
+        }
+
+        self.touchpad_settings = Some(Settings::new(TOUCHPAD_SCHEMA));
+        self.mouse_settings = Some(Settings::new(MOUSE_SCHEMA));
+        self.keyboard_settings = Some(Settings::new(KEYBOARD_SCHEMA));
+        self.input_source_settings = Some(Settings::new(INPUT_SOURCES_SCHEMA));
         Ok(())
     }
 
@@ -58,16 +223,23 @@ impl Compositor for Gnome {
             .unwrap_or(false)
     }
 
-    fn supports(&self, event: &Event) -> bool {
-        matches!(event, Event::Input(_))
+    fn capabilities(&self) -> &'static [&'static str] {
+        CAPABILITIES
     }
 
     fn apply_event(&self, event: Event) -> CompositorResult {
         match event {
-            Event::Input(InputEvent::Mouse(ev)) => self.apply_mouse_event(ev)?,
-            Event::Input(InputEvent::TouchPad(ev)) => self.apply_touchpad_event(ev)?,
+            Event::Input(InputEvent::Mouse { event: ev, .. }) => self.apply_mouse_event(ev)?,
+            Event::Input(InputEvent::TouchPad { event: ev, .. }) => self.apply_touchpad_event(ev)?,
+            Event::Input(InputEvent::Keyboard(ev)) => self.apply_keyboard_event(ev)?,
+            Event::Workspace(ev) => self.apply_workspace_event(ev)?,
+            Event::Theme(ev) => self.apply_theme_event(ev)?,
+            Event::Cursor(ev) => self.apply_cursor_event(ev)?,
+            Event::ColorTemp(ev) => self.apply_color_temp_event(ev)?,
             _ => (),
         }
+        // Flush every key written for this event to dconf at once.
+        Settings::sync();
         Ok(())
     }
 
@@ -79,36 +251,246 @@ impl Compositor for Gnome {
     }
 }
 
+// GNOME manages workspaces itself (dynamic by default), so nothing is synced.
+impl Workspace for Gnome {}
+
+impl Theme for Gnome {
+    fn theme_mode(&self, scheme: ColorScheme) -> CompositorResult {
+        theme::set_gtk_color_scheme("GNOME", scheme, false)
+    }
+
+    fn apply_cursor_event(&self, event: CursorEvent) -> CompositorResult {
+        theme::set_gtk_cursor("GNOME", event)
+    }
+}
+
+// gsd-color only tints the screen during its own night light schedule, which is left
+// as the user configured it in GNOME.
+impl ColorTemp for Gnome {
+    fn apply_color_temp_event(&self, event: ColorTempEvent) -> CompositorResult {
+        let settings = theme::gsettings(COLOR_SCHEMA)?;
+        let ColorTempEvent {
+            temperature,
+            enabled,
+        } = event;
+        self.set_bool(&settings, "night-light-enabled", enabled)?;
+        if enabled {
+            trace::write(
+                "GNOME",
+                || format!("night-light-temperature={temperature}"),
+                || settings.set_uint("night-light-temperature", temperature),
+            )?;
+        }
+        Ok(())
+    }
+}
+
 impl Input for Gnome {
+    /* Keyboard */
+
+    fn keyboard_layout(&self, layout: String) -> InputResult {
+        self.update_sources(|xkb| xkb.layout = Some(layout))
+    }
+
+    fn keyboard_variant(&self, variant: String) -> InputResult {
+        self.update_sources(|xkb| xkb.variant = variant)
+    }
+
+    fn keyboard_repeat_delay(&self, delay: u32) -> InputResult {
+        self.set_uint(self.keyboard()?, "delay", delay)
+    }
+
+    fn keyboard_repeat_rate(&self, rate: u32) -> InputResult {
+        let settings = self.keyboard()?;
+        // A rate of 0 turns repeat off in XKB; GNOME has a separate switch for that.
+        self.set_bool(settings, "repeat", rate > 0)?;
+        match repeat_interval(rate) {
+            Some(interval) => self.set_uint(settings, "repeat-interval", interval),
+            None => Ok(()),
+        }
+    }
+
     /* Touchpad */
 
+    fn touchpad_acceleration(&self, accel: Option<AccelConfig>) -> InputResult {
+        self.acceleration(self.touchpad()?, accel)
+    }
+
+    fn touchpad_accel_speed(&self, speed: f64) -> InputResult {
+        self.set_accel_speed(self.touchpad()?, speed)
+    }
+
+    fn touchpad_accel_profile(&self, profile: Option<AccelProfile>) -> InputResult {
+        self.set_accel_profile(self.touchpad()?, profile)
+    }
+
+    fn touchpad_click_method(&self, method: Option<ClickMethod>) -> InputResult {
+        let value = match method {
+            Some(method) => match libinput::ClickMethod::try_from(&method)? {
+                libinput::ClickMethod::ButtonAreas => "areas",
+                libinput::ClickMethod::Clickfinger => "fingers",
+            },
+            None => "default",
+        };
+        self.set_str(self.touchpad()?, "click-method", value)
+    }
+
     fn touchpad_tap_enabled(&self, enabled: bool) -> InputResult {
-        self.set_bool(&self.touchpad_settings, "tap-to-click", enabled)
+        self.set_bool(self.touchpad()?, "tap-to-click", enabled)
     }
 
     fn touchpad_natural_scroll(&self, enabled: Option<bool>) -> InputResult {
-        self.set_opt_bool(&self.touchpad_settings, "natural-scroll", enabled)
+        self.set_opt_bool(self.touchpad()?, "natural-scroll", enabled)
     }
 
     fn touchpad_disable_while_typing(&self, enabled: Option<bool>) -> InputResult {
-        self.set_opt_bool(&self.touchpad_settings, "disable-while-typing", enabled)
+        self.set_opt_bool(self.touchpad()?, "disable-while-typing", enabled)
     }
 
     fn touchpad_left_handed(&self, enabled: Option<bool>) -> InputResult {
         if let Some(v) = enabled {
             let val = if v { "left" } else { "mouse" }; // "mouse" is right-handed
-            self.set_str(&self.touchpad_settings, "haptic-output-mode", val)?;
+            self.set_str(self.touchpad()?, "haptic-output-mode", val)?;
         }
         Ok(())
     }
 
     /* Mouse */
 
+    fn mouse_acceleration(&self, accel: Option<AccelConfig>) -> InputResult {
+        self.acceleration(self.mouse()?, accel)
+    }
+
+    fn mouse_accel_speed(&self, speed: f64) -> InputResult {
+        self.set_accel_speed(self.mouse()?, speed)
+    }
+
+    fn mouse_accel_profile(&self, profile: Option<AccelProfile>) -> InputResult {
+        self.set_accel_profile(self.mouse()?, profile)
+    }
+
     fn mouse_left_handed(&self, enabled: Option<bool>) -> InputResult {
-        self.set_opt_bool(&self.mouse_settings, "left-handed", enabled)
+        self.set_opt_bool(self.mouse()?, "left-handed", enabled)
     }
 
     fn mouse_natural_scroll(&self, enabled: Option<bool>) -> InputResult {
-        self.set_opt_bool(&self.mouse_settings, "natural-scroll", enabled)
+        self.set_opt_bool(self.mouse()?, "natural-scroll", enabled)
+    }
+}
+
+/// `org.gnome.desktop.input-sources sources` for comma-separated XKB `layout` and
+/// `variant` lists: one `('xkb', 'layout+variant')` per layout, `('xkb', 'layout')`
+/// without a variant.
+fn xkb_sources(layout: &str, variant: &str) -> Vec<(String, String)> {
+    let mut variants = variant.split(',').map(str::trim);
+    layout
+        .split(',')
+        .map(|layout| (layout.trim(), variants.next().unwrap_or_default()))
+        .filter(|(layout, _)| !layout.is_empty())
+        .map(|(layout, variant)| {
+            let id = if variant.is_empty() {
+                layout.to_string()
+            } else {
+                format!("{layout}+{variant}")
+            };
+            ("xkb".to_string(), id)
+        })
+        .collect()
+}
+
+/// `sources` with its `xkb` entries replaced by `xkb`, in order. Other sources, such as
+/// input methods, keep their place; extra layouts go after the last replaced one, or
+/// first when `sources` had none.
+fn replace_xkb_sources(
+    sources: &[(String, String)],
+    xkb: Vec<(String, String)>,
+) -> Vec<(String, String)> {
+    let mut xkb = xkb.into_iter();
+    let mut replaced = Vec::new();
+    let mut after_xkb = 0;
+    for source in sources {
+        if source.0 != "xkb" {
+            replaced.push(source.clone());
+        } else if let Some(layout) = xkb.next() {
+            replaced.push(layout);
+            after_xkb = replaced.len();
+        }
+    }
+    replaced.splice(after_xkb..after_xkb, xkb);
+    replaced
+}
+
+/// The comma-separated XKB layout list in `sources`, without variants. Sources other
+/// than `xkb` ones, such as input methods, have no layout and are skipped.
+fn xkb_layouts(sources: &[(String, String)]) -> String {
+    sources
+        .iter()
+        .filter(|(kind, _)| kind == "xkb")
+        .map(|(_, id)| id.split_once('+').map_or(id.as_str(), |(layout, _)| layout))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// `repeat-interval` for a repeat `rate`: GNOME takes the time between repeats in ms,
+/// not repeats per second. `None` for 0, which has no interval.
+fn repeat_interval(rate: u32) -> Option<u32> {
+    (rate > 0).then(|| (1000 / rate).max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sources_pair_layouts_with_their_variant() {
+        let source = |id: &str| ("xkb".to_string(), id.to_string());
+        assert_eq!(xkb_sources("us", ""), vec![source("us")]);
+        assert_eq!(
+            xkb_sources("us,de", ",nodeadkeys"),
+            vec![source("us"), source("de+nodeadkeys")]
+        );
+        assert_eq!(
+            xkb_sources("us,de,", "dvorak"),
+            vec![source("us+dvorak"), source("de")]
+        );
+    }
+
+    #[test]
+    fn layouts_are_read_back_from_sources() {
+        let sources = [
+            ("xkb".to_string(), "us".to_string()),
+            ("ibus".to_string(), "anthy".to_string()),
+            ("xkb".to_string(), "de+nodeadkeys".to_string()),
+        ];
+        assert_eq!(xkb_layouts(&sources), "us,de");
+        assert_eq!(xkb_layouts(&[]), "");
+        // A variant sent on its own pairs up with them.
+        let source = |id: &str| ("xkb".to_string(), id.to_string());
+        assert_eq!(
+            xkb_sources(&xkb_layouts(&sources), ",dvorak"),
+            vec![source("us"), source("de+dvorak")]
+        );
+        // Only the layouts are rewritten; the input method stays between them.
+        let anthy = ("ibus".to_string(), "anthy".to_string());
+        assert_eq!(
+            replace_xkb_sources(&sources, xkb_sources("fr,it,es", "")),
+            vec![source("fr"), anthy.clone(), source("it"), source("es")]
+        );
+        assert_eq!(
+            replace_xkb_sources(&sources, xkb_sources("fr", "")),
+            vec![source("fr"), anthy.clone()]
+        );
+        assert_eq!(
+            replace_xkb_sources(&[anthy.clone()], xkb_sources("fr", "")),
+            vec![source("fr"), anthy]
+        );
+    }
+
+    #[test]
+    fn repeat_rate_becomes_an_interval() {
+        assert_eq!(repeat_interval(25), Some(40));
+        assert_eq!(repeat_interval(33), Some(30));
+        assert_eq!(repeat_interval(2000), Some(1));
+        assert_eq!(repeat_interval(0), None);
     }
 }
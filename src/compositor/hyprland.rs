@@ -1,18 +1,118 @@
+use crate::compositor::color_temp::ColorTemp;
 use crate::compositor::input::{Input, InputResult};
+use crate::compositor::libinput;
+use crate::compositor::numlock;
+use crate::compositor::shortcut::{self, Shortcut};
+use crate::compositor::trace;
+use crate::compositor::theme::{self, Cursor, Theme};
+use crate::compositor::workspace::Workspace;
 use crate::compositor::{Compositor, CompositorResult};
+use crate::error::Error;
 use crate::event::Event;
+use crate::event::color_temp::ColorTempEvent;
+use crate::event::cursor::CursorEvent;
+use crate::event::theme::ColorScheme;
 use crate::event::input::InputEvent;
+use crate::event::shortcuts::{Direction, FocusDirection, Shortcut as ShortcutAction, SystemAction};
+use crate::units::scroll;
+use cosmic_settings_config::shortcuts::Binding;
+use hyprland::data::Devices;
 use hyprland::keyword::Keyword;
+use hyprland::shared::HyprData;
 use std::env;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::sync::Mutex;
 
 use cosmic_comp_config::input::{
-    AccelConfig, AccelProfile, ClickMethod, ScrollConfig, ScrollMethod, TapButtonMap, TapConfig,
+    AccelConfig, AccelProfile, ClickMethod, DeviceState, ScrollConfig, ScrollMethod, TapButtonMap,
+    TapConfig,
 };
 use cosmic_comp_config::NumlockState;
 
+/// Events with a real handler in this backend.
+pub const CAPABILITIES: &[&str] = &[
+    "keyboard_rules",
+    "keyboard_model",
+    "keyboard_layout",
+    "keyboard_variant",
+    "keyboard_options",
+    "keyboard_repeat_delay",
+    "keyboard_repeat_rate",
+    "numslock_state",
+    "touchpad_state",
+    "touchpad_acceleration",
+    "touchpad_accel_speed",
+    "touchpad_accel_profile",
+    "touchpad_click_method",
+    "touchpad_disable_while_typing",
+    "touchpad_left_handed",
+    "touchpad_middle_button_emulation",
+    "touchpad_scroll_config",
+    "touchpad_scroll_method",
+    "touchpad_natural_scroll",
+    "touchpad_scroll_factor",
+    "touchpad_tap_config",
+    "touchpad_tap_enabled",
+    "touchpad_tap_button_map",
+    "touchpad_tap_drag",
+    "touchpad_tap_drag_lock",
+    "mouse_acceleration",
+    "mouse_accel_speed",
+    "mouse_accel_profile",
+    "mouse_left_handed",
+    "mouse_scroll_method",
+    "mouse_natural_scroll",
+    "mouse_scroll_factor",
+    "mouse_scroll_button",
+    "workspace_count",
+    "workspace_names",
+    "add_shortcut",
+    "remove_shortcut",
+    "theme_mode",
+    "cursor_theme",
+    "cursor_size",
+    "color_temp",
+];
+
+/// Keywords that can be read back with `hyprctl getoption` for `self-test`.
+const READ_BACK_KEYWORDS: &[(&str, &str)] = &[
+    ("keyboard_repeat_delay", "input:repeat_delay"),
+    ("keyboard_repeat_rate", "input:repeat_rate"),
+    ("touchpad_disable_while_typing", "input:touchpad:disable_while_typing"),
+    ("touchpad_middle_button_emulation", "input:touchpad:middle_button_emulation"),
+    ("touchpad_natural_scroll", "input:touchpad:natural_scroll"),
+    ("touchpad_tap_enabled", "input:touchpad:tap-to-click"),
+    ("touchpad_tap_drag", "input:touchpad:tap-and-drag"),
+    ("touchpad_tap_drag_lock", "input:touchpad:drag_lock"),
+    ("mouse_accel_speed", "input:sensitivity"),
+    ("mouse_left_handed", "input:left_handed"),
+    ("mouse_natural_scroll", "input:natural_scroll"),
+];
+
+/// `input:` keys that apply to every pointer. COSMIC's mouse settings own them; touchpad
+/// values go to per-device `device[<name>]:` keys so the two never overwrite each other.
+const SHARED_INPUT_KEYS: &[&str] = &[
+    "sensitivity",
+    "accel_profile",
+    "left_handed",
+    "scroll_method",
+];
+
 #[derive(Debug, Default)]
 pub struct Hyprland {
     pub instance_signature: Option<String>,
+    // Keywords of the event or batch being applied, collected by `batched`.
+    batch: Mutex<Option<Vec<(String, String)>>>,
+    // `setcursor` takes theme and size together, so both are remembered.
+    cursor: Mutex<Cursor>,
+    // Test seam: fixed touchpad names instead of `hyprctl devices`, and keywords
+    // recorded instead of sent, one entry per IPC request.
+    #[cfg(test)]
+    touchpad_names: Option<Vec<String>>,
+    #[cfg(test)]
+    recorded: Option<Mutex<Vec<Vec<(String, String)>>>>,
 }
 
 // #todo: Restructure:
@@ -20,48 +120,143 @@ pub struct Hyprland {
 // 2. Trace Error Location: Which Line should be looked at just by looking at the logs.
 impl Hyprland {
     pub fn new() -> Self {
-        Self {
-            instance_signature: None,
-        }
+        Self::default()
     }
 
-    fn set_keyword(&self, key: &str, value: impl ToString) -> InputResult {
-        Keyword::set(key, value.to_string())
-            .map_err(|err| Box::new(err) as Box<dyn std::error::Error + Send + Sync>)
+    /// The input handlers for an event limited to `device`, or for every device.
+    fn input<'a>(&'a self, device: Option<&'a str>) -> DeviceInput<'a> {
+        DeviceInput {
+            hyprland: self,
+            device,
+        }
     }
 
-    fn set_bool(&self, key: &str, value: Option<bool>) -> InputResult {
-        match value {
-            Some(true) => self.set_keyword(key, "true"),
-            Some(false) => self.set_keyword(key, "false"),
-            None => Ok(()),
+    /// Run `apply` with its keywords collected and sent in one request, so an event that
+    /// sets several keywords (a scroll or tap config) is a single IPC round trip.
+    ///
+    /// Inside an outer batch (see `apply_events`) the keywords join that batch instead.
+    fn batched(&self, apply: impl FnOnce() -> InputResult) -> InputResult {
+        {
+            let mut batch = self.batch.lock().unwrap_or_else(|err| err.into_inner());
+            if batch.is_some() {
+                drop(batch);
+                return apply();
+            }
+            *batch = Some(Vec::new());
         }
+        let result = apply();
+        let keywords = self
+            .batch
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .take()
+            .unwrap_or_default();
+        // Keywords set before a failing handler still go out, as they would unbatched.
+        let sent = if keywords.is_empty() {
+            Ok(())
+        } else {
+            self.send(keywords)
+        };
+        result.and(sent)
     }
 
-    fn map_scroll_method(method: &ScrollMethod) -> &'static str {
-        match method {
-            ScrollMethod::TwoFinger => "2fg",
-            ScrollMethod::Edge => "edge",
-            ScrollMethod::OnButtonDown => "on_button",
-            ScrollMethod::NoScroll => "none",
-            _ => "none",
+    fn set_keyword(&self, key: &str, value: impl ToString) -> InputResult {
+        let (key, value) = (key.to_string(), value.to_string());
+        if let Some(batch) = self.batch.lock().unwrap_or_else(|err| err.into_inner()).as_mut() {
+            batch.push((key, value));
+            return Ok(());
         }
+        self.send(vec![(key, value)])
     }
 
-    fn map_click_method(method: &ClickMethod) -> bool {
-        match method {
-            ClickMethod::Clickfinger => true,
-            ClickMethod::ButtonAreas => false,
-            _ => false,
+    /// Send `keywords` in one request.
+    fn send(&self, keywords: Vec<(String, String)>) -> InputResult {
+        #[cfg(test)]
+        if let Some(recorded) = &self.recorded {
+            recorded.lock().unwrap().push(keywords);
+            return Ok(());
         }
+        // `;` separates batched commands, so such a value has to go on its own.
+        if keywords.len() > 1 && keywords.iter().all(|(_, value)| !value.contains(';')) {
+            return self.send_batch(&keywords);
+        }
+        for (key, value) in keywords {
+            trace::write("Hyprland", || format!("keyword {key} {value}"), || {
+                Keyword::set(key.as_str(), value.clone())
+            })?;
+        }
+        Ok(())
     }
 
-    fn map_tap_button_map(map: &TapButtonMap) -> &'static str {
-        match map {
-            TapButtonMap::LeftRightMiddle => "lrm",
-            TapButtonMap::LeftMiddleRight => "lmr",
-            _ => "lrm",
+    /// Send `keywords` as one `[[BATCH]]` request on Hyprland's control socket, the same
+    /// request `hyprctl --batch` makes.
+    fn send_batch(&self, keywords: &[(String, String)]) -> InputResult {
+        let request = batch_request(keywords);
+        let reply = trace::write("Hyprland", || request.clone(), || control_request(&request))?;
+        // One reply per command: `ok`, or the error message.
+        let failures: Vec<&str> = reply
+            .split("\n\n")
+            .map(str::trim)
+            .filter(|reply| !reply.is_empty() && *reply != "ok")
+            .collect();
+        if !failures.is_empty() {
+            return Err(Error::IpcCommand {
+                backend: "Hyprland",
+                command: request,
+                reason: failures.join("; "),
+            });
         }
+        Ok(())
+    }
+
+    fn normalize_kb_options(options: Option<&str>) -> String {
+        // Hyprland expects a clean comma-separated list with no leading/trailing commas
+        // and no empty segments. Normalize by trimming edge commas/whitespace, dropping
+        // empty segments, and re-joining with commas.
+        options
+            .unwrap_or_default()
+            .trim_matches(|c: char| c == ',' || c.is_whitespace())
+            .split(',')
+            .filter_map(|part| {
+                let trimmed = part.trim();
+                if trimmed.is_empty() {
+                    None
+                } else {
+                    Some(trimmed)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    fn map_scroll_method(method: &ScrollMethod) -> Result<&'static str, Error> {
+        Ok(match libinput::ScrollMethod::try_from(method)? {
+            libinput::ScrollMethod::TwoFinger => "2fg",
+            libinput::ScrollMethod::Edge => "edge",
+            libinput::ScrollMethod::OnButtonDown => "on_button",
+            libinput::ScrollMethod::NoScroll => "none",
+        })
+    }
+
+    fn map_click_method(method: &ClickMethod) -> Result<bool, Error> {
+        Ok(match libinput::ClickMethod::try_from(method)? {
+            libinput::ClickMethod::Clickfinger => true,
+            libinput::ClickMethod::ButtonAreas => false,
+        })
+    }
+
+    fn map_accel_profile(profile: &AccelProfile) -> Result<&'static str, Error> {
+        Ok(match libinput::AccelProfile::try_from(profile)? {
+            libinput::AccelProfile::Flat => "flat",
+            libinput::AccelProfile::Adaptive => "adaptive",
+        })
+    }
+
+    fn map_tap_button_map(map: &TapButtonMap) -> Result<&'static str, Error> {
+        Ok(match libinput::TapButtonMap::try_from(map)? {
+            libinput::TapButtonMap::LeftRightMiddle => "lrm",
+            libinput::TapButtonMap::LeftMiddleRight => "lmr",
+        })
     }
 }
 
@@ -69,11 +264,9 @@ impl Compositor for Hyprland {
     fn init(&mut self) -> CompositorResult {
         self.instance_signature = env::var("HYPRLAND_INSTANCE_SIGNATURE").ok();
         if self.instance_signature.is_none() {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::NotFound,
-                "HYPRLAND_INSTANCE_SIGNATURE not set",
-            )
-            .into());
+            return Err(Error::MissingEnvVar {
+                var: "HYPRLAND_INSTANCE_SIGNATURE",
+            });
         }
         Ok(())
     }
@@ -86,21 +279,67 @@ impl Compositor for Hyprland {
         env::var("HYPRLAND_INSTANCE_SIGNATURE").is_ok()
     }
 
-    fn supports(&self, event: &Event) -> bool {
-        matches!(event, Event::Input(_))
+    fn capabilities(&self) -> &'static [&'static str] {
+        CAPABILITIES
+    }
+
+    // `hyprctl keyword` only changes the running Hyprland, not hyprland.conf.
+    fn keeps_settings(&self) -> bool {
+        false
+    }
+
+    fn read_back(&self, event_name: &str) -> Option<String> {
+        let (_, key) = READ_BACK_KEYWORDS
+            .iter()
+            .find(|(name, _)| *name == event_name)?;
+        Keyword::get(key).ok().map(|keyword| format!("{:?}", keyword.value))
     }
 
     fn apply_event(&self, event: Event) -> CompositorResult {
         match event {
-            Event::Input(InputEvent::TouchPad(ev)) => self.apply_touchpad_event(ev),
-            Event::Input(InputEvent::Mouse(ev)) => self.apply_mouse_event(ev),
-            Event::Input(InputEvent::Keyboard(ev)) => self.apply_keyboard_event(ev),
-            Event::Shortcut(_) => Ok(()),
+            Event::Input(InputEvent::TouchPad { event, device }) => {
+                let input = self.input(device.as_deref());
+                self.batched(|| input.apply_touchpad_event(event))
+            }
+            Event::Input(InputEvent::Mouse { event, device }) => {
+                let input = self.input(device.as_deref());
+                self.batched(|| input.apply_mouse_event(event))
+            }
+            Event::Input(InputEvent::Keyboard(ev)) => self.input(None).apply_keyboard_event(ev),
+            Event::Shortcut(ev) => self.apply_shortcut_event(ev),
+            Event::Workspace(ev) => self.apply_workspace_event(ev),
+            Event::Theme(ev) => self.apply_theme_event(ev),
+            Event::Cursor(ev) => self.apply_cursor_event(ev),
+            Event::ColorTemp(ev) => self.apply_color_temp_event(ev),
         }
     }
 
+    /// Every keyword of the batch goes out in one `[[BATCH]]` request.
+    fn apply_events(&self, events: Vec<Event>) -> CompositorResult {
+        self.batched(|| {
+            let mut result = Ok(());
+            for event in events {
+                let applied = self.apply_event(event);
+                if result.is_ok() {
+                    result = applied;
+                }
+            }
+            result
+        })
+    }
+
+    /// Re-read hyprland.conf, as `hyprctl reload` does.
+    ///
+    /// Not called after applying events: a reload drops every runtime `keyword`, so it
+    /// would undo the settings that were just applied.
     fn reload(&self) -> CompositorResult {
-        Ok(())
+        if self.instance_signature.is_none() {
+            return Err(Error::CompositorNotRunning {
+                backend: "Hyprland".to_string(),
+                reason: "HYPRLAND_INSTANCE_SIGNATURE is not set".to_string(),
+            });
+        }
+        send_request("reload")
     }
 
     fn shutdown(&self) -> CompositorResult {
@@ -109,26 +348,186 @@ impl Compositor for Hyprland {
 }
 
 // #todo: For all the todos -> Find equivalent functions in documentation and update
-impl Input for Hyprland {
+impl Workspace for Hyprland {
+    fn workspace_count(&self, count: u32) -> CompositorResult {
+        // Workspace rules only add: lowering the count does not remove workspaces that
+        // were made persistent earlier until Hyprland reloads its config.
+        for id in 1..=count {
+            self.set_keyword("workspace", format!("{id}, persistent:true"))?;
+        }
+        Ok(())
+    }
 
-    // fn touchpad_state(&self, _state: DeviceState) -> InputResult {
-    //     // TODO: Hyprland does not expose a direct enable/disable for touchpad.
-    //     dbg!("Hyprland: touchpad enable/disable not supported");
-    //     Ok(())
-    // }
+    fn workspace_names(&self, names: Vec<String>) -> CompositorResult {
+        // A `,` would start another workspace rule and a `;` another batched command,
+        // and Hyprland has no way to escape either.
+        if let Some(name) = names.iter().find(|name| name.contains([';', ','])) {
+            return Err(format!("workspace name {name:?} contains `;` or `,`").into());
+        }
+        for (id, name) in (1..).zip(names) {
+            if !name.is_empty() {
+                self.set_keyword("workspace", format!("{id}, defaultName:{name}"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Shortcut for Hyprland {
+    /// Replaces any bind Hyprland already has on the same keys, as COSMIC would.
+    fn add_shortcut(&self, shortcut: ShortcutAction, binding: Binding) -> CompositorResult {
+        let Some(keys) = format_binding(&binding) else {
+            return Ok(());
+        };
+        let dispatcher = dispatcher(&shortcut).ok_or_else(|| Error::UnsupportedValue {
+            setting: "shortcut",
+            value: format!("{shortcut:?}"),
+        })?;
+        self.set_keyword("unbind", &keys)?;
+        self.set_keyword("bind", format!("{keys}, {dispatcher}"))
+    }
+
+    fn remove_shortcut(&self, _shortcut: ShortcutAction, binding: Binding) -> CompositorResult {
+        match format_binding(&binding) {
+            Some(keys) => self.set_keyword("unbind", keys),
+            None => Ok(()),
+        }
+    }
+}
+
+// Through hyprsunset's IPC, which needs hyprsunset (0.2 or later) to be running.
+impl ColorTemp for Hyprland {
+    fn apply_color_temp_event(&self, event: ColorTempEvent) -> CompositorResult {
+        let temperature = event.temperature.to_string();
+        let args: &[&str] = if event.enabled {
+            &["hyprsunset", "temperature", &temperature]
+        } else {
+            &["hyprsunset", "identity"]
+        };
+        let command = format!("hyprctl {}", args.join(" "));
+        let output = trace::write(
+            "Hyprland",
+            || command.clone(),
+            || std::process::Command::new("hyprctl").args(args).output().map(Some),
+        )?;
+        if let Some(output) = output.filter(|output| !output.status.success()) {
+            let reply = String::from_utf8_lossy(&output.stdout);
+            return Err(format!("{command} exited with {}: {}", output.status, reply.trim()).into());
+        }
+        Ok(())
+    }
+}
+
+// No appearance settings of its own; GTK apps follow the GSettings interface keys.
+impl Theme for Hyprland {
+    fn theme_mode(&self, scheme: ColorScheme) -> CompositorResult {
+        theme::set_gtk_color_scheme("Hyprland", scheme, true)
+    }
+
+    fn apply_cursor_event(&self, event: CursorEvent) -> CompositorResult {
+        let (theme, size) = self.cursor.lock().unwrap_or_else(|err| err.into_inner()).update(event);
+        send_request(&setcursor_request(&theme, size)?)
+    }
+}
+
+/// Hyprland's input handlers for one event. A touchpad or mouse event can name one
+/// device; its `input:` keywords then go to that device's `device[<name>]:` section.
+struct DeviceInput<'a> {
+    hyprland: &'a Hyprland,
+    device: Option<&'a str>,
+}
+
+impl DeviceInput<'_> {
+    fn set_keyword(&self, key: &str, value: impl ToString) -> InputResult {
+        match self.device {
+            Some(device) => self.hyprland.set_keyword(&device_key(key, device), value),
+            None => self.hyprland.set_keyword(key, value),
+        }
+    }
+
+    /// Set a touchpad keyword, moving keys shared with mice onto each touchpad device.
+    fn set_touchpad_keyword(&self, key: &str, value: impl ToString) -> InputResult {
+        let value = value.to_string();
+        let keys = touchpad_keys(key, &self.touchpads()?);
+        if keys.is_empty() {
+            // Writing the shared key instead would change the mouse.
+            return Err(Error::RoutingFailed {
+                reason: format!("no touchpad found for {key}"),
+            });
+        }
+        for key in keys {
+            self.set_keyword(&key, &value)?;
+        }
+        Ok(())
+    }
+
+    /// Hyprland device names of the connected touchpads, re-read on every call so
+    /// hotplugged devices are picked up.
+    fn touchpads(&self) -> crate::error::Result<Vec<String>> {
+        if let Some(device) = self.device {
+            return Ok(vec![device.to_string()]);
+        }
+        #[cfg(test)]
+        if let Some(names) = &self.hyprland.touchpad_names {
+            return Ok(names.clone());
+        }
+        let devices = trace::timed("Hyprland", || "devices".to_string(), Devices::get)?;
+        Ok(devices
+            .mice
+            .into_iter()
+            .map(|mouse| mouse.name)
+            .filter(|name| name.contains("touchpad") || name.contains("trackpad"))
+            .collect())
+    }
+
+    fn set_bool(&self, key: &str, value: Option<bool>) -> InputResult {
+        match value {
+            Some(true) => self.set_keyword(key, "true"),
+            Some(false) => self.set_keyword(key, "false"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Input for DeviceInput<'_> {
+
+    fn touchpad_state(&self, state: DeviceState) -> InputResult {
+        let enabled = match state {
+            DeviceState::Enabled => true,
+            DeviceState::Disabled => false,
+            // Resolved by the device monitor before it gets here; see
+            // `Compositor::disables_touchpad_on_external_mouse`.
+            DeviceState::DisabledOnExternalMouse => {
+                return Err(Error::UnsupportedValue {
+                    setting: "touchpad_state",
+                    value: format!("{state:?}"),
+                });
+            }
+        };
+        // Only per-device sections have `enabled`; without a touchpad there is nothing
+        // to turn off.
+        for name in self.touchpads()? {
+            self.set_keyword(&format!("device[{name}]:enabled"), enabled)?;
+        }
+        Ok(())
+    }
 
     fn touchpad_acceleration(&self, accel: Option<AccelConfig>) -> InputResult {
-        // Mapped to general input sensitivity + accel_profile
         if let Some(accel) = accel {
-            self.set_keyword("input:sensitivity", accel.speed)?;
-            if let Some(profile) = accel.profile {
-                let value = match profile {
-                    AccelProfile::Flat => "flat",
-                    AccelProfile::Adaptive => "adaptive",
-                    _ => "adaptive",
-                };
-                self.set_keyword("input:accel_profile", value)?;
-            }
+            self.touchpad_accel_speed(accel.speed)?;
+            self.touchpad_accel_profile(accel.profile)?;
+        }
+        Ok(())
+    }
+
+    fn touchpad_accel_speed(&self, speed: f64) -> InputResult {
+        self.set_touchpad_keyword("input:sensitivity", speed)
+    }
+
+    fn touchpad_accel_profile(&self, profile: Option<AccelProfile>) -> InputResult {
+        if let Some(profile) = profile {
+            let value = Hyprland::map_accel_profile(&profile)?;
+            return self.set_touchpad_keyword("input:accel_profile", value);
         }
         Ok(())
     }
@@ -141,7 +540,7 @@ impl Input for Hyprland {
 
     fn touchpad_click_method(&self, method: Option<ClickMethod>) -> InputResult {
         if let Some(method) = method {
-            let enabled = Self::map_click_method(&method);
+            let enabled = Hyprland::map_click_method(&method)?;
             return self.set_keyword("input:touchpad:clickfinger_behavior", enabled);
         }
         Ok(())
@@ -152,8 +551,10 @@ impl Input for Hyprland {
     }
 
     fn touchpad_left_handed(&self, enabled: Option<bool>) -> InputResult {
-        // Mapped to general input left_handed
-        self.set_bool("input:left_handed", enabled)
+        if let Some(enabled) = enabled {
+            return self.set_touchpad_keyword("input:left_handed", enabled);
+        }
+        Ok(())
     }
 
     fn touchpad_middle_button_emulation(&self, enabled: Option<bool>) -> InputResult {
@@ -170,7 +571,7 @@ impl Input for Hyprland {
         // Split into scroll_factor + natural_scroll
         if let Some(config) = config {
             if let Some(factor) = config.scroll_factor {
-                self.set_keyword("input:touchpad:scroll_factor", factor)?;
+                self.set_keyword("input:touchpad:scroll_factor", scroll::multiplier(factor))?;
             }
             self.set_bool("input:touchpad:natural_scroll", config.natural_scroll)?;
         }
@@ -179,8 +580,8 @@ impl Input for Hyprland {
 
     fn touchpad_scroll_method(&self, method: Option<ScrollMethod>) -> InputResult {
         if let Some(method) = method {
-            let value = Self::map_scroll_method(&method);
-            return self.set_keyword("input:scroll_method", value);
+            let value = Hyprland::map_scroll_method(&method)?;
+            return self.set_touchpad_keyword("input:scroll_method", value);
         }
         Ok(())
     }
@@ -191,7 +592,7 @@ impl Input for Hyprland {
 
     fn touchpad_scroll_factor(&self, factor: Option<f64>) -> InputResult {
         if let Some(factor) = factor {
-            return self.set_keyword("input:touchpad:scroll_factor", factor);
+            return self.set_keyword("input:touchpad:scroll_factor", scroll::multiplier(factor));
         }
         Ok(())
     }
@@ -218,7 +619,7 @@ impl Input for Hyprland {
 
     fn touchpad_tap_button_map(&self, map: Option<TapButtonMap>) -> InputResult {
         if let Some(map) = map {
-            let value = Self::map_tap_button_map(&map);
+            let value = Hyprland::map_tap_button_map(&map)?;
             return self.set_keyword("input:touchpad:tap_button_map", value);
         }
         Ok(())
@@ -246,15 +647,20 @@ impl Input for Hyprland {
 
     fn mouse_acceleration(&self, accel: Option<AccelConfig>) -> InputResult {
         if let Some(accel) = accel {
-            self.set_keyword("input:sensitivity", accel.speed)?;
-            if let Some(profile) = accel.profile {
-                let value = match profile {
-                    AccelProfile::Flat => "flat",
-                    AccelProfile::Adaptive => "adaptive",
-                    _ => "adaptive",
-                };
-                self.set_keyword("input:accel_profile", value)?;
-            }
+            self.mouse_accel_speed(accel.speed)?;
+            self.mouse_accel_profile(accel.profile)?;
+        }
+        Ok(())
+    }
+
+    fn mouse_accel_speed(&self, speed: f64) -> InputResult {
+        self.set_keyword("input:sensitivity", speed)
+    }
+
+    fn mouse_accel_profile(&self, profile: Option<AccelProfile>) -> InputResult {
+        if let Some(profile) = profile {
+            let value = Hyprland::map_accel_profile(&profile)?;
+            return self.set_keyword("input:accel_profile", value);
         }
         Ok(())
     }
@@ -301,7 +707,7 @@ impl Input for Hyprland {
 
     fn mouse_scroll_method(&self, method: Option<ScrollMethod>) -> InputResult {
         if let Some(method) = method {
-            let value = Self::map_scroll_method(&method);
+            let value = Hyprland::map_scroll_method(&method)?;
             return self.set_keyword("input:scroll_method", value);
         }
         Ok(())
@@ -311,11 +717,13 @@ impl Input for Hyprland {
         self.set_bool("input:natural_scroll", enabled)
     }
 
-    // fn mouse_scroll_factor(&self, _factor: Option<f64>) -> InputResult {
-    //     // TODO: No mouse scroll_factor keyword in Hyprland.
-    //     dbg!("Hyprland: mouse scroll_factor not supported");
-    //     Ok(())
-    // }
+    // `input:scroll_factor` only applies to mice; touchpads have their own keyword.
+    fn mouse_scroll_factor(&self, factor: Option<f64>) -> InputResult {
+        if let Some(factor) = factor {
+            return self.set_keyword("input:scroll_factor", scroll::multiplier(factor));
+        }
+        Ok(())
+    }
 
     fn mouse_scroll_button(&self, button: Option<u32>) -> InputResult {
         if let Some(button) = button {
@@ -349,27 +757,10 @@ impl Input for Hyprland {
     }
 
     fn keyboard_options(&self, options: Option<String>) -> InputResult {
-        if let Some(options) = options {
-            // Hyprland expects a clean comma-separated list with no leading/trailing commas
-            // and no empty segments. Normalize by trimming edge commas/whitespace, dropping
-            let cleaned = options
-                .trim_matches(|c: char| c == ',' || c.is_whitespace())
-                .split(',')
-                .filter_map(|part| {
-                    let trimmed = part.trim();
-                    if trimmed.is_empty() {
-                        None
-                    } else {
-                        Some(trimmed)
-                    }
-                })
-                .collect::<Vec<_>>()
-                // empty segments, and re-joining with commas.
-                .join(",");
-
-            return self.set_keyword("input:kb_options", cleaned);
-        }
-        Ok(())
+        // `input:kb_options` replaces the whole option set, which matches how cosmolith
+        // treats COSMIC's options: always the authoritative full set. `None`/empty clears.
+        let options = Hyprland::normalize_kb_options(options.as_deref());
+        self.set_keyword("input:kb_options", options)
     }
 
     fn keyboard_variant(&self, variant: String) -> InputResult {
@@ -385,10 +776,409 @@ impl Input for Hyprland {
     }
 
     fn numslock_state(&self, state: NumlockState) -> InputResult {
-        match state {
-            NumlockState::BootOn => self.set_keyword("input:numlock_by_default", "true"),
-            NumlockState::BootOff => self.set_keyword("input:numlock_by_default", "false"),
-            NumlockState::LastBoot => Ok(()), // Don't change
+        match numlock::target(state) {
+            Some(enabled) => self.set_keyword("input:numlock_by_default", enabled),
+            None => Ok(()),
+        }
+    }
+}
+
+/// `MODS, key` as `bind` and `unbind` take it, e.g. `SUPER SHIFT, q`. `None` for a
+/// binding without a key.
+fn format_binding(binding: &Binding) -> Option<String> {
+    let mods = &binding.modifiers;
+    let mods: Vec<&str> = [
+        (mods.logo, "SUPER"),
+        (mods.alt, "ALT"),
+        (mods.shift, "SHIFT"),
+        (mods.ctrl, "CTRL"),
+    ]
+    .into_iter()
+    .filter_map(|(set, name)| set.then_some(name))
+    .collect();
+    let key = if let Some(key) = binding.key {
+        xkbcommon::xkb::keysym_get_name(key)
+    } else {
+        format!("code:{}", binding.keycode?)
+    };
+    Some(format!("{}, {key}", mods.join(" ")))
+}
+
+/// `dispatcher, args` for a COSMIC shortcut action, `None` when Hyprland has no
+/// equivalent.
+fn dispatcher(action: &ShortcutAction) -> Option<String> {
+    Some(match action {
+        ShortcutAction::Close => "killactive,".to_string(),
+        ShortcutAction::Focus(dir) => {
+            let dir = match dir {
+                FocusDirection::Left => "l",
+                FocusDirection::Right => "r",
+                FocusDirection::Up => "u",
+                FocusDirection::Down => "d",
+            };
+            format!("movefocus, {dir}")
+        }
+        ShortcutAction::Move(dir) => {
+            let dir = match dir {
+                Direction::Left => "l",
+                Direction::Right => "r",
+                Direction::Up => "u",
+                Direction::Down => "d",
+            };
+            format!("movewindow, {dir}")
+        }
+        ShortcutAction::Workspace(id) => format!("workspace, {id}"),
+        ShortcutAction::MoveToWorkspace(id) => format!("movetoworkspace, {id}"),
+        ShortcutAction::Terminate | ShortcutAction::System(SystemAction::LogOut) => {
+            "exit,".to_string()
         }
+        ShortcutAction::Custom(cmd) => format!("exec, {cmd}"),
+        ShortcutAction::System(SystemAction::LockScreen) => "exec, hyprlock".to_string(),
+        ShortcutAction::System(system) => format!("exec, {}", shortcut::system_command(system)?),
+        ShortcutAction::Unknown => return None,
+    })
+}
+
+/// `[[BATCH]]keyword <key> <value>;...` for the control socket.
+fn batch_request(keywords: &[(String, String)]) -> String {
+    let commands: Vec<String> = keywords
+        .iter()
+        .map(|(key, value)| format!("keyword {key} {value}"))
+        .collect();
+    format!("[[BATCH]]{}", commands.join(";"))
+}
+
+/// Send a request that Hyprland answers with `ok` on success.
+fn send_request(request: &str) -> CompositorResult {
+    let reply = trace::write("Hyprland", || request.to_string(), || control_request(request))?;
+    // Empty under a dry run.
+    let reply = reply.trim();
+    if !reply.is_empty() && reply != "ok" {
+        return Err(Error::IpcCommand {
+            backend: "Hyprland",
+            command: request.to_string(),
+            reason: reply.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// `setcursor` takes everything before the size as the theme, so spaces need no quoting.
+/// It has no escaping either, so a theme with a `;` or control character is refused.
+fn setcursor_request(theme: &str, size: u32) -> Result<String, Error> {
+    if theme.contains(|c: char| c == ';' || c.is_control()) {
+        return Err(format!("cursor theme {theme:?} cannot be sent to setcursor").into());
+    }
+    Ok(format!("setcursor {theme} {size}"))
+}
+
+/// Send one request on Hyprland's control socket and return its reply, as `hyprctl` does.
+fn control_request(request: &str) -> crate::error::Result<String> {
+    let mut stream = UnixStream::connect(control_socket()?)?;
+    stream.write_all(request.as_bytes())?;
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply)?;
+    Ok(reply)
+}
+
+/// Hyprland's control socket: under `$XDG_RUNTIME_DIR/hypr` since 0.40, `/tmp/hypr`
+/// before that.
+fn control_socket() -> Result<PathBuf, Error> {
+    let signature = env::var("HYPRLAND_INSTANCE_SIGNATURE").map_err(|_| {
+        Error::MissingEnvVar {
+            var: "HYPRLAND_INSTANCE_SIGNATURE",
+        }
+    })?;
+    let runtime = env::var_os("XDG_RUNTIME_DIR").map(|dir| PathBuf::from(dir).join("hypr"));
+    let socket = runtime
+        .into_iter()
+        .chain([PathBuf::from("/tmp/hypr")])
+        .map(|dir| dir.join(&signature).join(".socket.sock"))
+        .find(|path| path.exists());
+    socket.ok_or_else(|| Error::DetectionFailed("Hyprland control socket not found".to_string()))
+}
+
+/// Keys to write for a touchpad setting: one `device[<name>]:` key per touchpad for keys
+/// shared with mice, the key itself for `input:touchpad:` keys.
+fn touchpad_keys(key: &str, touchpads: &[String]) -> Vec<String> {
+    match key.strip_prefix("input:") {
+        Some(option) if SHARED_INPUT_KEYS.contains(&option) => touchpads
+            .iter()
+            .map(|name| format!("device[{name}]:{option}"))
+            .collect(),
+        _ => vec![key.to_string()],
+    }
+}
+
+/// `device[<name>]:<option>` for an `input:` or `input:touchpad:` key; device sections
+/// take both kinds of options without the prefix. Other keys are returned unchanged.
+fn device_key(key: &str, device: &str) -> String {
+    key.strip_prefix("input:touchpad:")
+        .or_else(|| key.strip_prefix("input:"))
+        .map_or_else(|| key.to_string(), |option| format!("device[{device}]:{option}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmic_comp_config::XkbConfig;
+
+    use super::*;
+    use crate::debounce::order_by_device;
+    use crate::event::input::{KeyboardEvent, MouseEvent, TouchpadEvent};
+    use crate::event::{Source, SourcedEvent};
+
+    fn recording(touchpads: &[&str]) -> Hyprland {
+        Hyprland {
+            touchpad_names: Some(touchpads.iter().map(|name| name.to_string()).collect()),
+            recorded: Some(Mutex::new(Vec::new())),
+            ..Hyprland::default()
+        }
+    }
+
+    /// Final value of every keyword after the recorded writes.
+    fn final_values(hyprland: &Hyprland) -> std::collections::HashMap<String, String> {
+        let recorded = hyprland.recorded.as_ref().unwrap().lock().unwrap();
+        recorded.iter().flatten().cloned().collect()
+    }
+
+    fn requests(hyprland: &Hyprland) -> usize {
+        hyprland.recorded.as_ref().unwrap().lock().unwrap().len()
+    }
+
+    #[test]
+    fn one_request_per_event() {
+        let hyprland = recording(&["touchpad-a"]);
+        let tap = TapConfig {
+            enabled: true,
+            button_map: None,
+            drag: true,
+            drag_lock: false,
+        };
+        let scroll = ScrollConfig {
+            method: None,
+            natural_scroll: Some(true),
+            scroll_button: None,
+            scroll_factor: Some(1.0),
+        };
+        let events = [
+            TouchpadEvent::TapConfig(Some(tap)),
+            TouchpadEvent::ScrollConfig(Some(scroll)),
+        ];
+        for event in events {
+            hyprland.apply_event(Event::Input(InputEvent::touchpad(event))).unwrap();
+        }
+
+        // Five keywords, previously five round trips.
+        assert_eq!(final_values(&hyprland).len(), 5);
+        assert_eq!(requests(&hyprland), 2);
+        // Handlers called outside `apply_event` are sent straight away.
+        hyprland.input(None).touchpad_tap_enabled(false).unwrap();
+        assert_eq!(requests(&hyprland), 3);
+    }
+
+    #[test]
+    fn touchpad_state_toggles_each_touchpad() {
+        let hyprland = recording(&["touchpad-a", "touchpad-b"]);
+        let disable = TouchpadEvent::State(DeviceState::Disabled);
+        hyprland.apply_event(Event::Input(InputEvent::touchpad(disable))).unwrap();
+
+        let values = final_values(&hyprland);
+        assert_eq!(values["device[touchpad-a]:enabled"], "false");
+        assert_eq!(values["device[touchpad-b]:enabled"], "false");
+        let state = DeviceState::DisabledOnExternalMouse;
+        assert!(hyprland.input(None).touchpad_state(state).is_err());
+    }
+
+    #[test]
+    fn apply_events_is_one_request() {
+        let hyprland = recording(&["touchpad-a"]);
+        let events = vec![
+            Event::Input(InputEvent::touchpad(TouchpadEvent::NaturalScroll(Some(true)))),
+            Event::Input(InputEvent::touchpad(TouchpadEvent::TapEnabled(false))),
+            Event::Input(InputEvent::mouse(MouseEvent::AccelSpeed(0.2))),
+        ];
+        hyprland.apply_events(events).unwrap();
+
+        assert_eq!(final_values(&hyprland).len(), 3);
+        assert_eq!(requests(&hyprland), 1);
+    }
+
+    #[test]
+    fn workspace_names_cannot_inject_rules() {
+        let hyprland = recording(&[]);
+        let batched = vec!["mail".to_string(), "web;keyword misc:vfr 0".to_string()];
+        let rule = vec!["a, persistent:true".to_string()];
+        assert!(hyprland.workspace_names(batched).is_err());
+        assert!(hyprland.workspace_names(rule).is_err());
+        assert_eq!(requests(&hyprland), 0);
+    }
+
+    #[test]
+    fn cursor_themes_cannot_inject_requests() {
+        assert_eq!(
+            setcursor_request("Bibata Modern Ice", 24).unwrap(),
+            "setcursor Bibata Modern Ice 24"
+        );
+        assert!(setcursor_request("default 24\nreload", 24).is_err());
+        assert!(setcursor_request("default;reload", 24).is_err());
+    }
+
+    #[test]
+    fn batch_request_joins_keywords() {
+        let keywords = [
+            ("input:touchpad:tap-to-click".to_string(), "true".to_string()),
+            ("input:touchpad:drag_lock".to_string(), "false".to_string()),
+        ];
+        assert_eq!(
+            batch_request(&keywords),
+            "[[BATCH]]keyword input:touchpad:tap-to-click true;\
+             keyword input:touchpad:drag_lock false"
+        );
+    }
+
+    #[test]
+    fn touchpad_and_mouse_acceleration_in_one_batch_do_not_clobber() {
+        let source = Source {
+            namespace: "com.system76.CosmicComp",
+            key: None,
+        };
+        let sourced = |event| SourcedEvent::new(Event::Input(event), source);
+        let mut batch = vec![
+            sourced(InputEvent::mouse(MouseEvent::AccelSpeed(-0.4))),
+            sourced(InputEvent::touchpad(TouchpadEvent::AccelSpeed(0.3))),
+            sourced(InputEvent::mouse(MouseEvent::AccelProfile(Some(AccelProfile::Flat)))),
+            sourced(InputEvent::touchpad(TouchpadEvent::AccelProfile(Some(
+                AccelProfile::Adaptive,
+            )))),
+        ];
+        order_by_device(&mut batch);
+        assert!(matches!(batch[0].event, Event::Input(InputEvent::TouchPad { .. })));
+        assert!(matches!(batch[3].event, Event::Input(InputEvent::Mouse { .. })));
+
+        let hyprland = recording(&["elan0001:00-04f3:3140-touchpad"]);
+        for sourced in batch {
+            hyprland.apply_event(sourced.event).unwrap();
+        }
+
+        let values = final_values(&hyprland);
+        assert_eq!(values["input:sensitivity"], "-0.4");
+        assert_eq!(values["input:accel_profile"], "flat");
+        assert_eq!(values["device[elan0001:00-04f3:3140-touchpad]:sensitivity"], "0.3");
+        assert_eq!(values["device[elan0001:00-04f3:3140-touchpad]:accel_profile"], "adaptive");
+    }
+
+    #[test]
+    fn shared_touchpad_keys_fail_without_a_touchpad() {
+        let hyprland = recording(&[]);
+        let input = hyprland.input(None);
+        let err = input.touchpad_accel_speed(0.3).unwrap_err();
+        assert!(matches!(err, Error::RoutingFailed { .. }));
+        assert!(final_values(&hyprland).is_empty());
+        // Touchpad-only keys need no device.
+        input.touchpad_tap_enabled(true).unwrap();
+    }
+
+    #[test]
+    fn events_for_one_device_use_its_section() {
+        let hyprland = recording(&["touchpad-a", "touchpad-b"]);
+        let device = Some("touchpad-b".to_string());
+        let events = [
+            InputEvent::TouchPad {
+                event: TouchpadEvent::AccelSpeed(0.3),
+                device: device.clone(),
+            },
+            InputEvent::TouchPad {
+                event: TouchpadEvent::TapEnabled(true),
+                device: device.clone(),
+            },
+            InputEvent::Mouse {
+                event: MouseEvent::LeftHanded(Some(true)),
+                device: Some("mx-master".to_string()),
+            },
+        ];
+        for event in events {
+            hyprland.apply_event(Event::Input(event)).unwrap();
+        }
+
+        let mut keys: Vec<_> = final_values(&hyprland).into_keys().collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            [
+                "device[mx-master]:left_handed",
+                "device[touchpad-b]:sensitivity",
+                "device[touchpad-b]:tap-to-click",
+            ]
+        );
+    }
+
+    #[test]
+    fn touchpad_only_keys_stay_global() {
+        let touchpads = vec!["touchpad-a".to_string()];
+        assert_eq!(
+            touchpad_keys("input:touchpad:tap-to-click", &touchpads),
+            ["input:touchpad:tap-to-click"]
+        );
+        assert_eq!(
+            touchpad_keys("input:left_handed", &touchpads),
+            ["device[touchpad-a]:left_handed"]
+        );
+        assert!(touchpad_keys("input:sensitivity", &[]).is_empty());
+    }
+
+    #[test]
+    fn clearing_options_replaces_with_empty_set() {
+        let old = XkbConfig {
+            options: Some("ctrl:nocaps".to_string()),
+            ..Default::default()
+        };
+        let new = XkbConfig {
+            options: None,
+            ..Default::default()
+        };
+
+        let events = KeyboardEvent::from(old, new);
+        assert_eq!(events.len(), 1);
+        assert!(matches!(
+            events[0],
+            Event::Input(InputEvent::Keyboard(KeyboardEvent::Options(None)))
+        ));
+        assert_eq!(Hyprland::normalize_kb_options(None), "");
+        assert_eq!(Hyprland::normalize_kb_options(Some("")), "");
+    }
+
+    #[test]
+    fn options_are_normalized() {
+        assert_eq!(
+            Hyprland::normalize_kb_options(Some(",ctrl:nocaps, ,compose:ralt,")),
+            "ctrl:nocaps,compose:ralt"
+        );
+    }
+
+    #[test]
+    fn shortcuts_map_to_dispatchers() {
+        let workspace = ShortcutAction::Workspace("3".to_string());
+        assert_eq!(dispatcher(&workspace).as_deref(), Some("workspace, 3"));
+        assert_eq!(dispatcher(&ShortcutAction::Close).as_deref(), Some("killactive,"));
+        let launcher = ShortcutAction::System(SystemAction::Launcher);
+        assert_eq!(dispatcher(&launcher).as_deref(), Some("exec, /usr/bin/cosmic-launcher"));
+        assert_eq!(dispatcher(&ShortcutAction::System(SystemAction::Unknown)), None);
+        assert_eq!(dispatcher(&ShortcutAction::Unknown), None);
+    }
+
+    #[test]
+    fn reload_needs_a_running_instance() {
+        let err = Hyprland::default().reload().unwrap_err();
+        assert!(matches!(err, Error::CompositorNotRunning { .. }));
+    }
+
+    #[test]
+    fn mouse_scroll_factor_leaves_touchpads_alone() {
+        let hyprland = recording(&["elan-touchpad"]);
+        let event = Event::Input(InputEvent::mouse(MouseEvent::ScrollFactor(Some(2.0))));
+        hyprland.apply_event(event).unwrap();
+        let values = final_values(&hyprland);
+        assert_eq!(values.get("input:scroll_factor").map(String::as_str), Some("2"));
+        assert!(!values.contains_key("input:touchpad:scroll_factor"));
     }
 }
@@ -1,14 +1,13 @@
-use std::error::Error;
-
 use crate::event::input::{KeyboardEvent, MouseEvent, TouchpadEvent};
 
 use cosmic_comp_config::input::{
-    AccelConfig, ClickMethod, DeviceState, ScrollConfig, ScrollMethod, TapButtonMap, TapConfig,
+    AccelConfig, AccelProfile, ClickMethod, DeviceState, ScrollConfig, ScrollMethod, TapButtonMap, TapConfig,
 };
 
 use cosmic_comp_config::NumlockState;
+use tracing::warn;
 
-pub type InputResult = Result<(), Box<dyn Error + Send + Sync>>;
+pub type InputResult = crate::error::Result<()>;
 
 /// Compositor input interface. Implement this for each compositor backend.
 pub trait Input {
@@ -29,6 +28,8 @@ pub trait Input {
         match event {
             TouchpadEvent::State(v) => self.touchpad_state(v),
             TouchpadEvent::Acceleration(v) => self.touchpad_acceleration(v),
+            TouchpadEvent::AccelSpeed(v) => self.touchpad_accel_speed(v),
+            TouchpadEvent::AccelProfile(v) => self.touchpad_accel_profile(v),
             TouchpadEvent::Calibration(v) => self.touchpad_calibration(v),
             TouchpadEvent::ClickMethod(v) => self.touchpad_click_method(v),
             TouchpadEvent::DisableWhileTyping(v) => self.touchpad_disable_while_typing(v),
@@ -53,6 +54,8 @@ pub trait Input {
         match event {
             MouseEvent::State(v) => self.mouse_state(v),
             MouseEvent::Acceleration(v) => self.mouse_acceleration(v),
+            MouseEvent::AccelSpeed(v) => self.mouse_accel_speed(v),
+            MouseEvent::AccelProfile(v) => self.mouse_accel_profile(v),
             MouseEvent::Calibration(v) => self.mouse_calibration(v),
             MouseEvent::ClickMethod(v) => self.mouse_click_method(v),
             MouseEvent::DisableWhileTyping(v) => self.mouse_disable_while_typing(v),
@@ -70,183 +73,199 @@ pub trait Input {
     }
 
     fn keyboard_rules(&self, rules: String) -> InputResult {
-        eprintln!("keyboard_rules not implemented: {:?}", rules);
+        warn!("keyboard_rules not implemented: {:?}", rules);
         Ok(())
     }
     fn keyboard_model(&self, model: String) -> InputResult {
-        eprintln!("keyboard_model not implemented: {:?}", model);
+        warn!("keyboard_model not implemented: {:?}", model);
         Ok(())
     }
     fn keyboard_layout(&self, layout: String) -> InputResult {
-        eprintln!("keyboard_layout not implemented: {:?}", layout);
+        warn!("keyboard_layout not implemented: {:?}", layout);
         Ok(())
     }
     fn keyboard_variant(&self, variant: String) -> InputResult {
-        eprintln!("keyboard_variant not implemented: {:?}", variant);
+        warn!("keyboard_variant not implemented: {:?}", variant);
         Ok(())
     }
     fn keyboard_options(&self, options: Option<String>) -> InputResult {
-        eprintln!("keyboard_options not implemented: {:?}", options);
+        warn!("keyboard_options not implemented: {:?}", options);
         Ok(())
     }
     fn keyboard_repeat_delay(&self, delay: u32) -> InputResult {
-        eprintln!("keyboard_repeat_delay not implemented: {:?}", delay);
+        warn!("keyboard_repeat_delay not implemented: {:?}", delay);
         Ok(())
     }
     fn keyboard_repeat_rate(&self, rate: u32) -> InputResult {
-        eprintln!("keyboard_repeat_rate not implemented: {:?}", rate);
+        warn!("keyboard_repeat_rate not implemented: {:?}", rate);
         Ok(())
     }
 
     fn numslock_state(&self, state: NumlockState) -> InputResult {
-        eprintln!("numslock_state not implemented: {:?}", state);
+        warn!("numslock_state not implemented: {:?}", state);
         Ok(())
     }
 
     fn touchpad_state(&self, state: DeviceState) -> InputResult {
-        eprintln!("touchpad_state not implemented: {:?}", state);
+        warn!("touchpad_state not implemented: {:?}", state);
         Ok(())
     }
     fn touchpad_acceleration(&self, accel: Option<AccelConfig>) -> InputResult {
-        eprintln!("touchpad_acceleration not implemented: {:?}", accel);
+        warn!("touchpad_acceleration not implemented: {:?}", accel);
+        Ok(())
+    }
+    fn touchpad_accel_speed(&self, speed: f64) -> InputResult {
+        warn!("touchpad_accel_speed not implemented: {:?}", speed);
+        Ok(())
+    }
+    fn touchpad_accel_profile(&self, profile: Option<AccelProfile>) -> InputResult {
+        warn!("touchpad_accel_profile not implemented: {:?}", profile);
         Ok(())
     }
     fn touchpad_calibration(&self, cal: Option<[f32; 6]>) -> InputResult {
-        eprintln!("touchpad_calibration not implemented: {:?}", cal);
+        warn!("touchpad_calibration not implemented: {:?}", cal);
         Ok(())
     }
     fn touchpad_click_method(&self, method: Option<ClickMethod>) -> InputResult {
-        eprintln!("touchpad_click_method not implemented: {:?}", method);
+        warn!("touchpad_click_method not implemented: {:?}", method);
         Ok(())
     }
     fn touchpad_disable_while_typing(&self, enabled: Option<bool>) -> InputResult {
-        eprintln!(
+        warn!(
             "touchpad_disable_while_typing not implemented: {:?}",
             enabled
         );
         Ok(())
     }
     fn touchpad_left_handed(&self, enabled: Option<bool>) -> InputResult {
-        eprintln!("touchpad_left_handed not implemented: {:?}", enabled);
+        warn!("touchpad_left_handed not implemented: {:?}", enabled);
         Ok(())
     }
     fn touchpad_middle_button_emulation(&self, enabled: Option<bool>) -> InputResult {
-        eprintln!(
+        warn!(
             "touchpad_middle_button_emulation not implemented: {:?}",
             enabled
         );
         Ok(())
     }
     fn touchpad_rotation_angle(&self, angle: Option<u32>) -> InputResult {
-        eprintln!("touchpad_rotation_angle not implemented: {:?}", angle);
+        warn!("touchpad_rotation_angle not implemented: {:?}", angle);
         Ok(())
     }
     fn touchpad_scroll_config(&self, config: Option<ScrollConfig>) -> InputResult {
-        eprintln!("touchpad_scroll_config not implemented: {:?}", config);
+        warn!("touchpad_scroll_config not implemented: {:?}", config);
         Ok(())
     }
     fn touchpad_scroll_method(&self, method: Option<ScrollMethod>) -> InputResult {
-        eprintln!("touchpad_scroll_method not implemented: {:?}", method);
+        warn!("touchpad_scroll_method not implemented: {:?}", method);
         Ok(())
     }
     fn touchpad_natural_scroll(&self, enabled: Option<bool>) -> InputResult {
-        eprintln!("touchpad_natural_scroll not implemented: {:?}", enabled);
+        warn!("touchpad_natural_scroll not implemented: {:?}", enabled);
         Ok(())
     }
     fn touchpad_scroll_factor(&self, factor: Option<f64>) -> InputResult {
-        eprintln!("touchpad_scroll_factor not implemented: {:?}", factor);
+        warn!("touchpad_scroll_factor not implemented: {:?}", factor);
         Ok(())
     }
     fn touchpad_scroll_button(&self, button: Option<u32>) -> InputResult {
-        eprintln!("touchpad_scroll_button not implemented: {:?}", button);
+        warn!("touchpad_scroll_button not implemented: {:?}", button);
         Ok(())
     }
     fn touchpad_tap_config(&self, config: Option<TapConfig>) -> InputResult {
-        eprintln!("touchpad_tap_config not implemented: {:?}", config);
+        warn!("touchpad_tap_config not implemented: {:?}", config);
         Ok(())
     }
     fn touchpad_tap_enabled(&self, enabled: bool) -> InputResult {
-        eprintln!("touchpad_tap_enabled not implemented: {:?}", enabled);
+        warn!("touchpad_tap_enabled not implemented: {:?}", enabled);
         Ok(())
     }
     fn touchpad_tap_button_map(&self, map: Option<TapButtonMap>) -> InputResult {
-        eprintln!("touchpad_tap_button_map not implemented: {:?}", map);
+        warn!("touchpad_tap_button_map not implemented: {:?}", map);
         Ok(())
     }
     fn touchpad_tap_drag(&self, enabled: bool) -> InputResult {
-        eprintln!("touchpad_tap_drag not implemented: {:?}", enabled);
+        warn!("touchpad_tap_drag not implemented: {:?}", enabled);
         Ok(())
     }
     fn touchpad_tap_drag_lock(&self, enabled: bool) -> InputResult {
-        eprintln!("touchpad_tap_drag_lock not implemented: {:?}", enabled);
+        warn!("touchpad_tap_drag_lock not implemented: {:?}", enabled);
         Ok(())
     }
     fn touchpad_map_to_output(&self, output: Option<String>) -> InputResult {
-        eprintln!("touchpad_map_to_output not implemented: {:?}", output);
+        warn!("touchpad_map_to_output not implemented: {:?}", output);
         Ok(())
     }
 
     fn mouse_state(&self, state: DeviceState) -> InputResult {
-        eprintln!("mouse_state not implemented: {:?}", state);
+        warn!("mouse_state not implemented: {:?}", state);
         Ok(())
     }
     fn mouse_acceleration(&self, accel: Option<AccelConfig>) -> InputResult {
-        eprintln!("mouse_acceleration not implemented: {:?}", accel);
+        warn!("mouse_acceleration not implemented: {:?}", accel);
+        Ok(())
+    }
+    fn mouse_accel_speed(&self, speed: f64) -> InputResult {
+        warn!("mouse_accel_speed not implemented: {:?}", speed);
+        Ok(())
+    }
+    fn mouse_accel_profile(&self, profile: Option<AccelProfile>) -> InputResult {
+        warn!("mouse_accel_profile not implemented: {:?}", profile);
         Ok(())
     }
     fn mouse_calibration(&self, cal: Option<[f32; 6]>) -> InputResult {
-        eprintln!("mouse_calibration not implemented: {:?}", cal);
+        warn!("mouse_calibration not implemented: {:?}", cal);
         Ok(())
     }
     fn mouse_click_method(&self, method: Option<ClickMethod>) -> InputResult {
-        eprintln!("mouse_click_method not implemented: {:?}", method);
+        warn!("mouse_click_method not implemented: {:?}", method);
         Ok(())
     }
     fn mouse_disable_while_typing(&self, enabled: Option<bool>) -> InputResult {
-        eprintln!("mouse_disable_while_typing not implemented: {:?}", enabled);
+        warn!("mouse_disable_while_typing not implemented: {:?}", enabled);
         Ok(())
     }
     fn mouse_left_handed(&self, enabled: Option<bool>) -> InputResult {
-        eprintln!("mouse_left_handed not implemented: {:?}", enabled);
+        warn!("mouse_left_handed not implemented: {:?}", enabled);
         Ok(())
     }
     fn mouse_middle_button_emulation(&self, enabled: Option<bool>) -> InputResult {
-        eprintln!(
+        warn!(
             "mouse_middle_button_emulation not implemented: {:?}",
             enabled
         );
         Ok(())
     }
     fn mouse_rotation_angle(&self, angle: Option<u32>) -> InputResult {
-        eprintln!("mouse_rotation_angle not implemented: {:?}", angle);
+        warn!("mouse_rotation_angle not implemented: {:?}", angle);
         Ok(())
     }
     fn mouse_scroll_config(&self, config: Option<ScrollConfig>) -> InputResult {
-        eprintln!("mouse_scroll_config not implemented: {:?}", config);
+        warn!("mouse_scroll_config not implemented: {:?}", config);
         Ok(())
     }
     fn mouse_scroll_method(&self, method: Option<ScrollMethod>) -> InputResult {
-        eprintln!("mouse_scroll_method not implemented: {:?}", method);
+        warn!("mouse_scroll_method not implemented: {:?}", method);
         Ok(())
     }
     fn mouse_natural_scroll(&self, enabled: Option<bool>) -> InputResult {
-        eprintln!("mouse_natural_scroll not implemented: {:?}", enabled);
+        warn!("mouse_natural_scroll not implemented: {:?}", enabled);
         Ok(())
     }
     fn mouse_scroll_factor(&self, factor: Option<f64>) -> InputResult {
-        eprintln!("mouse_scroll_factor not implemented: {:?}", factor);
+        warn!("mouse_scroll_factor not implemented: {:?}", factor);
         Ok(())
     }
     fn mouse_scroll_button(&self, button: Option<u32>) -> InputResult {
-        eprintln!("mouse_scroll_button not implemented: {:?}", button);
+        warn!("mouse_scroll_button not implemented: {:?}", button);
         Ok(())
     }
     fn mouse_tap_config(&self, config: Option<TapConfig>) -> InputResult {
-        eprintln!("mouse_tap_config not implemented: {:?}", config);
+        warn!("mouse_tap_config not implemented: {:?}", config);
         Ok(())
     }
     fn mouse_map_to_output(&self, output: Option<String>) -> InputResult {
-        eprintln!("mouse_map_to_output not implemented: {:?}", output);
+        warn!("mouse_map_to_output not implemented: {:?}", output);
         Ok(())
     }
 }
@@ -1,51 +1,329 @@
+use crate::compositor::color_temp::ColorTemp;
+use crate::compositor::config_file::{atomic_write, read_or, set_ini_key};
 use crate::compositor::input::{Input, InputResult};
+use crate::compositor::libinput;
+use crate::compositor::numlock;
+use crate::compositor::reconnect;
+use crate::compositor::trace;
+use crate::compositor::theme::Theme;
+use crate::compositor::workspace::Workspace;
 use crate::compositor::{Compositor, CompositorResult};
+use crate::error::Error;
+use crate::event::color_temp::ColorTempEvent;
+use crate::event::theme::ColorScheme;
 use crate::event::{Event, InputEvent};
+use crate::units::scroll;
+use std::path::PathBuf;
 use std::sync::Mutex;
-use zbus::blocking::Connection;
+use std::sync::atomic::{AtomicBool, Ordering};
+use cosmic_comp_config::input::{AccelConfig, AccelProfile, ClickMethod};
+use cosmic_comp_config::NumlockState;
+use zbus::blocking::{Connection, Proxy};
+use tracing::warn;
+
+/// Events with a real handler in this backend.
+pub const CAPABILITIES: &[&str] = &[
+    "keyboard_layout",
+    "keyboard_variant",
+    "keyboard_options",
+    "keyboard_repeat_delay",
+    "keyboard_repeat_rate",
+    "numslock_state",
+    "touchpad_acceleration",
+    "touchpad_accel_speed",
+    "touchpad_accel_profile",
+    "touchpad_click_method",
+    "touchpad_disable_while_typing",
+    "touchpad_natural_scroll",
+    "touchpad_scroll_factor",
+    "touchpad_tap_enabled",
+    "mouse_acceleration",
+    "mouse_accel_speed",
+    "mouse_accel_profile",
+    "mouse_left_handed",
+    "mouse_scroll_factor",
+    "theme_mode",
+    "cursor_theme",
+    "cursor_size",
+    "color_temp",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DeviceKind {
+    Touchpad,
+    Mouse,
+}
+
+impl DeviceKind {
+    /// KWin reports touchpads as pointers too.
+    fn classify(touchpad: bool, pointer: bool) -> Option<DeviceKind> {
+        match (touchpad, pointer) {
+            (true, _) => Some(DeviceKind::Touchpad),
+            (false, true) => Some(DeviceKind::Mouse),
+            (false, false) => None,
+        }
+    }
+
+    /// Group written when the devices cannot be listed.
+    fn fallback_group(self) -> &'static str {
+        match self {
+            DeviceKind::Touchpad => "Libinput",
+            DeviceKind::Mouse => "Mouse",
+        }
+    }
+}
+
+/// A device as KWin keys it in kcminputrc.
+struct Device {
+    name: String,
+    vendor: u32,
+    product: u32,
+}
+
+impl Device {
+    fn groups(&self) -> [String; 4] {
+        [
+            "Libinput".to_string(),
+            self.vendor.to_string(),
+            self.product.to_string(),
+            self.name.clone(),
+        ]
+    }
+}
+
+/// KConfig writes nested groups as one header, `[Libinput][1133][16495][Name]`; this is
+/// the text between the outer brackets.
+fn group_header(groups: &[&str]) -> String {
+    groups.join("][")
+}
 
 pub struct Kde {
+    // Where KWin reads kcminputrc (input settings), kxkbrc (layouts) and kwinrc from.
+    config_dir: Option<PathBuf>,
     connection: Mutex<Option<Connection>>,
+    // Set by `init`; a `None` connection afterwards means it was dropped and is reopened
+    // on the next reload.
+    initialized: AtomicBool,
+    // Set when kcminputrc or kwinrc was written since the last reconfigure.
+    pending_reload: AtomicBool,
+    // Set when kxkbrc was written since the layouts were last reloaded.
+    pending_layout_reload: AtomicBool,
 }
 
 impl Kde {
     pub fn new() -> Self {
         Self {
+            config_dir: dirs::config_dir(),
             connection: Mutex::new(None),
+            initialized: AtomicBool::new(false),
+            pending_reload: AtomicBool::new(false),
+            pending_layout_reload: AtomicBool::new(false),
         }
     }
 
     fn run_kde_cmd(&self, group: &str, key: &str, value: &str) -> InputResult {
-        std::process::Command::new("kwriteconfig6")
-            .args([
-                "--file",
-                "kcminputrc",
-                "--group",
-                group,
-                "--key",
-                key,
-                value,
-            ])
-            .status()
-            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?;
+        self.write_config(&[group], key, value)
+    }
 
-        self.reload()
+    /// Write `key=value` to the nested `groups` of kcminputrc. KWin is not reconfigured
+    /// here: `apply_event` does that once, after all the writes for the event.
+    fn write_config(&self, groups: &[&str], key: &str, value: &str) -> InputResult {
+        self.pending_reload.store(true, Ordering::Relaxed);
+        self.write_file("kcminputrc", groups, key, value)
     }
 
-    fn set_bool(&self, group: &str, key: &str, value: bool) -> InputResult {
-        self.run_kde_cmd(group, key, &value.to_string())
+    /// Write `key=value` to the nested `groups` of kwinrc, reconfigured as kcminputrc is.
+    fn write_kwin(&self, groups: &[&str], key: &str, value: &str) -> InputResult {
+        self.pending_reload.store(true, Ordering::Relaxed);
+        self.write_file("kwinrc", groups, key, value)
     }
 
-    fn set_opt_bool(&self, group: &str, key: &str, value: Option<bool>) -> InputResult {
-        if let Some(v) = value {
-            return self.set_bool(group, key, v);
+    /// Write `key=value` to the nested `groups` of `file` in the config directory. The
+    /// callers mark what has to be reloaded for `file`.
+    ///
+    /// The file is patched in place; `kwriteconfig6` is only used when it cannot be.
+    fn write_file(&self, file: &str, groups: &[&str], key: &str, value: &str) -> InputResult {
+        let Some(path) = self.config_dir.as_deref().map(|dir| dir.join(file)) else {
+            return Self::kwriteconfig(file, groups, key, value);
+        };
+        let group = group_header(groups);
+        let command = || format!("{file} [{group}] {key}={value}");
+        let written = trace::write("KDE Plasma", command, || {
+            let contents = read_or(&path, "")?;
+            atomic_write(&path, &set_ini_key(&contents, &group, key, value))
+        });
+        if let Err(err) = written {
+            warn!("Could not write {} ({err}); using kwriteconfig6", path.display());
+            return Self::kwriteconfig(file, groups, key, value);
+        }
+        Ok(())
+    }
+
+    /// Write `key=value` to `[Layout]` of kxkbrc. `apply_event` reloads the layouts once
+    /// after all the writes for the event.
+    fn write_layout(&self, key: &str, value: &str) -> InputResult {
+        self.pending_layout_reload.store(true, Ordering::Relaxed);
+        // Without `Use`, Plasma ignores the list and keeps the system layout.
+        self.write_file("kxkbrc", &["Layout"], "Use", "true")?;
+        self.write_file("kxkbrc", &["Layout"], key, value)
+    }
+
+    fn kwriteconfig(file: &str, groups: &[&str], key: &str, value: &str) -> InputResult {
+        let mut args = vec!["--file", file];
+        for group in groups {
+            args.extend(["--group", *group]);
+        }
+        args.extend(["--key", key, value]);
+        let status = trace::write(
+            "KDE Plasma",
+            || format!("kwriteconfig6 {file} [{}] {key}={value}", group_header(groups)),
+            || std::process::Command::new("kwriteconfig6").args(&args).status(),
+        )?;
+        if !status.success() {
+            let groups = group_header(groups);
+            let command = format!("kwriteconfig6 {file} [{groups}] {key}");
+            return Err(format!("{command} exited with {status}").into());
+        }
+        Ok(())
+    }
+
+    /// Write `key=value` to the `[Libinput][vendor][product][name]` group of every
+    /// connected device of `kind`, which is where KWin reads libinput settings from.
+    /// Falls back to the generic group when KWin cannot list its devices.
+    fn set_device(&self, kind: DeviceKind, key: &str, value: &str) -> InputResult {
+        match self.devices(kind) {
+            Ok(devices) if !devices.is_empty() => {
+                // One failed device should not keep the others unchanged.
+                let mut result = Ok(());
+                for device in &devices {
+                    let groups = device.groups();
+                    let groups: Vec<&str> = groups.iter().map(String::as_str).collect();
+                    let written = self.write_config(&groups, key, value);
+                    if result.is_ok() {
+                        result = written;
+                    }
+                }
+                result
+            }
+            Ok(_) => self.write_config(&[kind.fallback_group()], key, value),
+            Err(err) => {
+                warn!("Could not list KWin input devices, writing the generic group: {err}");
+                self.write_config(&[kind.fallback_group()], key, value)
+            }
+        }
+    }
+
+    /// Input devices of `kind`, from `org.kde.KWin.InputDeviceManager`.
+    fn devices(&self, kind: DeviceKind) -> crate::error::Result<Vec<Device>> {
+        let guard = reconnect::lock_connection("KDE Plasma", &self.connection);
+        let conn = guard.as_ref().ok_or(Error::NotInitialized {
+            backend: "KDE Plasma",
+        })?;
+        let manager = Proxy::new(
+            conn,
+            "org.kde.KWin",
+            "/org/kde/KWin/InputDevice",
+            "org.kde.KWin.InputDeviceManager",
+        )?;
+        let sys_names: Vec<String> = trace::timed(
+            "KDE Plasma",
+            || "org.kde.KWin.InputDeviceManager.devicesSysNames".to_string(),
+            || manager.get_property("devicesSysNames"),
+        )?;
+
+        let mut devices = Vec::new();
+        for sys_name in sys_names {
+            let path = format!("/org/kde/KWin/InputDevice/{sys_name}");
+            let device = Proxy::new(conn, "org.kde.KWin", path, "org.kde.KWin.InputDevice")?;
+            let touchpad: bool = device.get_property("touchpad")?;
+            let pointer: bool = device.get_property("pointer")?;
+            if DeviceKind::classify(touchpad, pointer) != Some(kind) {
+                continue;
+            }
+            devices.push(Device {
+                name: device.get_property("name")?,
+                vendor: device.get_property("vendor")?,
+                product: device.get_property("product")?,
+            });
+        }
+        Ok(devices)
+    }
+
+    /// Run `call` on the session bus, reconnecting with the `[reconnect]` policy. Does
+    /// nothing before `init`: there is no session bus to talk to.
+    fn on_session_bus(&self, call: fn(&Connection) -> zbus::Result<()>) -> CompositorResult {
+        if !self.initialized.load(Ordering::Relaxed) {
+            return Ok(());
         }
+        let mut guard = reconnect::lock_connection("KDE Plasma", &self.connection);
+        reconnect::with_reconnect(
+            &reconnect::policy(),
+            "KDE Plasma",
+            &mut *guard,
+            Connection::session,
+            |conn| call(conn),
+        )?;
+        Ok(())
+    }
+
+    /// The signal the keyboard settings module sends after writing kxkbrc; KWin re-reads
+    /// the layouts and options on it.
+    fn reload_layouts(conn: &Connection) -> zbus::Result<()> {
+        trace::write("KDE Plasma", || "org.kde.keyboard.reloadConfig".to_string(), || {
+            conn.emit_signal(None::<&str>, "/Layouts", "org.kde.keyboard", "reloadConfig", &())
+        })
+    }
+
+    fn reconfigure(conn: &Connection) -> zbus::Result<()> {
+        trace::write("KDE Plasma", || "org.kde.KWin.reconfigure".to_string(), || {
+            conn.call_method(
+                Some("org.kde.KWin"),
+                "/KWin",
+                Some("org.kde.KWin"),
+                "reconfigure",
+                &(),
+            )
+            .map(|_| ())
+        })?;
         Ok(())
     }
 
-    fn set_opt_double(&self, group: &str, key: &str, value: Option<f64>) -> InputResult {
+    fn set_bool(&self, kind: DeviceKind, key: &str, value: bool) -> InputResult {
+        self.set_device(kind, key, &value.to_string())
+    }
+
+    fn set_opt_bool(&self, kind: DeviceKind, key: &str, value: Option<bool>) -> InputResult {
         if let Some(v) = value {
-            return self.run_kde_cmd(group, key, &v.to_string());
+            return self.set_bool(kind, key, v);
+        }
+        Ok(())
+    }
+
+    // kcminputrc PointerAccelerationProfile holds libinput's enum: 1 = flat, 2 = adaptive.
+    fn map_accel_profile(profile: &AccelProfile) -> Result<&'static str, Error> {
+        Ok(match libinput::AccelProfile::try_from(profile)? {
+            libinput::AccelProfile::Flat => "1",
+            libinput::AccelProfile::Adaptive => "2",
+        })
+    }
+
+    fn set_accel_speed(&self, kind: DeviceKind, speed: f64) -> InputResult {
+        let speed = speed.clamp(-1.0, 1.0).to_string();
+        self.set_device(kind, "PointerAcceleration", &speed)
+    }
+
+    fn set_accel_profile(&self, kind: DeviceKind, profile: Option<AccelProfile>) -> InputResult {
+        if let Some(profile) = profile {
+            let value = Self::map_accel_profile(&profile)?;
+            return self.set_device(kind, "PointerAccelerationProfile", value);
+        }
+        Ok(())
+    }
+
+    fn acceleration(&self, kind: DeviceKind, accel: Option<AccelConfig>) -> InputResult {
+        if let Some(accel) = accel {
+            self.set_accel_speed(kind, accel.speed)?;
+            self.set_accel_profile(kind, accel.profile)?;
         }
         Ok(())
     }
@@ -54,10 +332,8 @@ impl Kde {
 impl Compositor for Kde {
     fn init(&mut self) -> CompositorResult {
         let conn = Connection::session()?;
-        let mut guard = self.connection.lock().map_err(|_| {
-            std::io::Error::new(std::io::ErrorKind::Other, "KDE connection lock poisoned")
-        })?;
-        *guard = Some(conn);
+        *reconnect::lock_connection("KDE Plasma", &self.connection) = Some(conn);
+        self.initialized.store(true, Ordering::Relaxed);
         Ok(())
     }
 
@@ -72,51 +348,295 @@ impl Compositor for Kde {
     }
 
     fn reload(&self) -> CompositorResult {
-        let guard = self.connection.lock().unwrap();
-        if let Some(conn) = guard.as_ref() {
-            conn.call_method(
-                Some("org.kde.KWin"),
-                "/KWin",
-                Some("org.kde.KWin"),
-                "reconfigure",
-                &(),
-            )?;
-        }
-        Ok(())
+        self.on_session_bus(Self::reconfigure)
     }
 
-    fn supports(&self, event: &Event) -> bool {
-        matches!(event, Event::Input(_))
+    fn capabilities(&self) -> &'static [&'static str] {
+        CAPABILITIES
     }
     fn apply_event(&self, event: Event) -> CompositorResult {
-        match event {
-            Event::Input(InputEvent::Mouse(ev)) => self.apply_mouse_event(ev)?,
-            Event::Input(InputEvent::TouchPad(ev)) => self.apply_touchpad_event(ev)?,
-            _ => (),
+        let result = match event {
+            Event::Input(InputEvent::Mouse { event: ev, .. }) => self.apply_mouse_event(ev),
+            Event::Input(InputEvent::TouchPad { event: ev, .. }) => self.apply_touchpad_event(ev),
+            Event::Input(InputEvent::Keyboard(ev)) => self.apply_keyboard_event(ev),
+            Event::Workspace(ev) => self.apply_workspace_event(ev),
+            Event::Theme(ev) => self.apply_theme_event(ev),
+            Event::Cursor(ev) => self.apply_cursor_event(ev),
+            Event::ColorTemp(ev) => self.apply_color_temp_event(ev),
+            _ => Ok(()),
+        };
+        // One reconfigure for all the writes of this event, including the ones that went
+        // through before a failed write. Both reloads run even when one fails.
+        let mut reloaded = Ok(());
+        if self.pending_reload.swap(false, Ordering::Relaxed) {
+            reloaded = self.reload();
         }
-        Ok(())
+        let mut layouts_reloaded = Ok(());
+        if self.pending_layout_reload.swap(false, Ordering::Relaxed) {
+            layouts_reloaded = self.on_session_bus(Self::reload_layouts);
+        }
+        result.and(reloaded).and(layouts_reloaded)
     }
     fn shutdown(&self) -> CompositorResult {
         Ok(())
     }
 }
 
+// KWin virtual desktops are not synced yet.
+impl Workspace for Kde {}
+
+impl Theme for Kde {
+    /// Switches the global color scheme, which Qt and (through the KDE GTK config
+    /// module) GTK apps follow.
+    fn theme_mode(&self, scheme: ColorScheme) -> CompositorResult {
+        let name = match scheme {
+            ColorScheme::Dark => "BreezeDark",
+            ColorScheme::Light => "BreezeLight",
+        };
+        let status = trace::write(
+            "KDE Plasma",
+            || format!("plasma-apply-colorscheme {name}"),
+            || std::process::Command::new("plasma-apply-colorscheme").arg(name).status(),
+        )?;
+        if !status.success() {
+            return Err(format!("plasma-apply-colorscheme {name} exited with {status}").into());
+        }
+        Ok(())
+    }
+
+    // KWin picks the cursor up on the reconfigure after the write; apps started
+    // afterwards inherit it.
+    fn cursor_theme(&self, theme: String) -> CompositorResult {
+        self.run_kde_cmd("Mouse", "cursorTheme", &theme)
+    }
+
+    fn cursor_size(&self, size: u32) -> CompositorResult {
+        self.run_kde_cmd("Mouse", "cursorSize", &size.to_string())
+    }
+}
+
+// Night Color's D-Bus interface only previews a temperature for a few seconds, so the
+// setting is written to kwinrc, which KWin picks up on the reconfigure after the write.
+impl ColorTemp for Kde {
+    fn apply_color_temp_event(&self, event: ColorTempEvent) -> CompositorResult {
+        self.write_kwin(&["NightColor"], "Active", &event.enabled.to_string())?;
+        if event.enabled {
+            // Constant mode tints all day, like COSMIC does while night light is on.
+            self.write_kwin(&["NightColor"], "Mode", "Constant")?;
+            let temperature = event.temperature.to_string();
+            self.write_kwin(&["NightColor"], "NightTemperature", &temperature)?;
+        }
+        Ok(())
+    }
+}
+
 impl Input for Kde {
+    /* Keyboard */
+    // kxkbrc takes the same comma-separated lists as XKB.
+    fn keyboard_layout(&self, layout: String) -> InputResult {
+        self.write_layout("LayoutList", &layout)
+    }
+
+    fn keyboard_variant(&self, variant: String) -> InputResult {
+        self.write_layout("VariantList", &variant)
+    }
+
+    fn keyboard_options(&self, options: Option<String>) -> InputResult {
+        // Without `ResetOldOptions`, the options are added to the ones already active.
+        self.write_layout("ResetOldOptions", "true")?;
+        self.write_layout("Options", &kb_options(options.as_deref()))
+    }
+
+    fn keyboard_repeat_delay(&self, delay: u32) -> InputResult {
+        self.run_kde_cmd("Keyboard", "KeyRepeat", "repeat")?;
+        self.run_kde_cmd("Keyboard", "RepeatDelay", &delay.to_string())
+    }
+
+    fn keyboard_repeat_rate(&self, rate: u32) -> InputResult {
+        self.run_kde_cmd("Keyboard", "KeyRepeat", "repeat")?;
+        self.run_kde_cmd("Keyboard", "RepeatRate", &rate.to_string())
+    }
+
+    fn numslock_state(&self, state: NumlockState) -> InputResult {
+        // kcminputrc NumLock: 0 = on, 1 = off, 2 = leave unchanged.
+        match numlock::target(state) {
+            Some(true) => self.run_kde_cmd("Keyboard", "NumLock", "0"),
+            Some(false) => self.run_kde_cmd("Keyboard", "NumLock", "1"),
+            None => Ok(()),
+        }
+    }
+
     /* Touchpad */
+    fn touchpad_acceleration(&self, accel: Option<AccelConfig>) -> InputResult {
+        self.acceleration(DeviceKind::Touchpad, accel)
+    }
+
+    fn touchpad_accel_speed(&self, speed: f64) -> InputResult {
+        self.set_accel_speed(DeviceKind::Touchpad, speed)
+    }
+
+    fn touchpad_accel_profile(&self, profile: Option<AccelProfile>) -> InputResult {
+        self.set_accel_profile(DeviceKind::Touchpad, profile)
+    }
+
+    fn touchpad_click_method(&self, method: Option<ClickMethod>) -> InputResult {
+        // kcminputrc ClickMethod: 1 = button areas, 2 = clickfinger.
+        if let Some(method) = method {
+            let value = match libinput::ClickMethod::try_from(&method)? {
+                libinput::ClickMethod::ButtonAreas => "1",
+                libinput::ClickMethod::Clickfinger => "2",
+            };
+            return self.set_device(DeviceKind::Touchpad, "ClickMethod", value);
+        }
+        Ok(())
+    }
+
+    fn touchpad_disable_while_typing(&self, enabled: Option<bool>) -> InputResult {
+        self.set_opt_bool(DeviceKind::Touchpad, "DisableWhileTyping", enabled)
+    }
+
     fn touchpad_natural_scroll(&self, enabled: Option<bool>) -> InputResult {
-        self.set_opt_bool("Libinput", "NaturalScroll", enabled)
+        self.set_opt_bool(DeviceKind::Touchpad, "NaturalScroll", enabled)
+    }
+
+    fn touchpad_scroll_factor(&self, factor: Option<f64>) -> InputResult {
+        if let Some(factor) = factor {
+            let factor = scroll::multiplier(factor).to_string();
+            return self.set_device(DeviceKind::Touchpad, "ScrollFactor", &factor);
+        }
+        Ok(())
     }
 
     fn touchpad_tap_enabled(&self, enabled: bool) -> InputResult {
-        self.set_bool("Libinput", "TapToClick", enabled)
+        self.set_bool(DeviceKind::Touchpad, "TapToClick", enabled)
     }
 
     /* Mouse */
+    fn mouse_acceleration(&self, accel: Option<AccelConfig>) -> InputResult {
+        self.acceleration(DeviceKind::Mouse, accel)
+    }
+
+    fn mouse_accel_speed(&self, speed: f64) -> InputResult {
+        self.set_accel_speed(DeviceKind::Mouse, speed)
+    }
+
+    fn mouse_accel_profile(&self, profile: Option<AccelProfile>) -> InputResult {
+        self.set_accel_profile(DeviceKind::Mouse, profile)
+    }
+
     fn mouse_left_handed(&self, enabled: Option<bool>) -> InputResult {
-        self.set_opt_bool("Mouse", "LeftHanded", enabled)
+        self.set_opt_bool(DeviceKind::Mouse, "LeftHanded", enabled)
     }
 
+    // Per-device `ScrollFactor` is what the "Scrolling speed" slider in Plasma's mouse
+    // settings writes. `WheelScrollLines` would only change how far Qt apps scroll.
     fn mouse_scroll_factor(&self, factor: Option<f64>) -> InputResult {
-        self.set_opt_double("Mouse", "WheelScrollLines", factor)
+        if let Some(factor) = factor {
+            let factor = scroll::multiplier(factor).to_string();
+            return self.set_device(DeviceKind::Mouse, "ScrollFactor", &factor);
+        }
+        Ok(())
+    }
+}
+
+/// `options` as kxkbrc's `Options`: comma-separated, without empty entries. `None`
+/// clears them.
+fn kb_options(options: Option<&str>) -> String {
+    options
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|option| !option.is_empty())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::input::KeyboardEvent;
+
+    #[test]
+    fn touchpads_are_not_mice() {
+        assert_eq!(DeviceKind::classify(true, true), Some(DeviceKind::Touchpad));
+        assert_eq!(DeviceKind::classify(false, true), Some(DeviceKind::Mouse));
+        assert_eq!(DeviceKind::classify(false, false), None);
+    }
+
+    #[test]
+    fn device_group_matches_kcminputrc() {
+        let device = Device {
+            name: "Logitech USB Receiver".to_string(),
+            vendor: 1133,
+            product: 16495,
+        };
+        assert_eq!(device.groups(), ["Libinput", "1133", "16495", "Logitech USB Receiver"]);
+    }
+
+    #[test]
+    fn nested_groups_are_one_kconfig_header() {
+        let groups = ["Libinput", "1133", "16495", "Logitech USB Receiver"];
+        let contents = "[Libinput][1133][16495][Logitech USB Receiver]\n\
+                        NaturalScroll=false\n\
+                        \n\
+                        [Mouse]\n\
+                        PointerAcceleration=0\n";
+        let patched = set_ini_key(contents, &group_header(&groups), "NaturalScroll", "true");
+        assert_eq!(
+            patched,
+            "[Libinput][1133][16495][Logitech USB Receiver]\n\
+             NaturalScroll=true\n\
+             \n\
+             [Mouse]\n\
+             PointerAcceleration=0\n"
+        );
+    }
+
+    #[test]
+    fn keyboard_layouts_go_to_kxkbrc() {
+        let dir = std::env::temp_dir().join(format!("cosmolith-kde-{}", std::process::id()));
+        let kde = Kde {
+            config_dir: Some(dir.clone()),
+            ..Kde::new()
+        };
+        let events = [
+            KeyboardEvent::Layout("us,de".to_string()),
+            KeyboardEvent::Variant(",nodeadkeys".to_string()),
+            KeyboardEvent::Options(Some(",grp:alt_shift_toggle,,caps:escape".to_string())),
+            KeyboardEvent::RepeatRate(30),
+        ];
+        for event in events {
+            kde.apply_event(Event::Input(InputEvent::Keyboard(event))).unwrap();
+        }
+
+        let kxkbrc = std::fs::read_to_string(dir.join("kxkbrc")).unwrap();
+        let kcminputrc = std::fs::read_to_string(dir.join("kcminputrc")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        for line in [
+            "Use=true",
+            "LayoutList=us,de",
+            "VariantList=,nodeadkeys",
+            "ResetOldOptions=true",
+            "Options=grp:alt_shift_toggle,caps:escape",
+        ] {
+            assert!(kxkbrc.contains(line), "{line} missing from {kxkbrc}");
+        }
+        assert!(kcminputrc.contains("RepeatRate=30"), "{kcminputrc}");
+        assert_eq!(kb_options(None), "");
+    }
+
+    #[test]
+    fn layouts_do_not_reconfigure_kwin() {
+        let dir = std::env::temp_dir().join(format!("cosmolith-kde-reload-{}", std::process::id()));
+        let kde = Kde {
+            config_dir: Some(dir.clone()),
+            ..Kde::new()
+        };
+        kde.write_layout("LayoutList", "us").unwrap();
+        assert!(kde.pending_layout_reload.load(Ordering::Relaxed));
+        assert!(!kde.pending_reload.load(Ordering::Relaxed));
+        kde.write_kwin(&["NightColor"], "Active", "true").unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(kde.pending_reload.load(Ordering::Relaxed));
     }
 }
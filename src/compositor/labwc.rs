@@ -0,0 +1,669 @@
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event as XmlEvent};
+use quick_xml::{Reader, Writer};
+
+use crate::compositor::config_file::{atomic_write, read_or, set_env_line};
+use crate::compositor::color_temp::ColorTemp;
+use crate::compositor::input::{Input, InputResult};
+use crate::compositor::libinput;
+use crate::compositor::numlock;
+use crate::compositor::trace;
+use crate::compositor::theme::{self, Theme};
+use crate::compositor::workspace::Workspace;
+use crate::compositor::{Compositor, CompositorResult};
+use crate::error::Error;
+use crate::event::Event;
+use crate::event::theme::ColorScheme;
+use crate::event::input::InputEvent;
+use crate::units::scroll;
+
+use cosmic_comp_config::input::{
+    AccelConfig, AccelProfile, ClickMethod, DeviceState, ScrollMethod, TapButtonMap, TapConfig,
+};
+use cosmic_comp_config::NumlockState;
+
+/// Events with a real handler in this backend.
+pub const CAPABILITIES: &[&str] = &[
+    "keyboard_rules",
+    "keyboard_model",
+    "keyboard_layout",
+    "keyboard_variant",
+    "keyboard_options",
+    "keyboard_repeat_delay",
+    "keyboard_repeat_rate",
+    "numslock_state",
+    "touchpad_state",
+    "touchpad_acceleration",
+    "touchpad_accel_speed",
+    "touchpad_accel_profile",
+    "touchpad_click_method",
+    "touchpad_disable_while_typing",
+    "touchpad_left_handed",
+    "touchpad_middle_button_emulation",
+    "touchpad_scroll_method",
+    "touchpad_natural_scroll",
+    "touchpad_scroll_factor",
+    "touchpad_tap_config",
+    "touchpad_tap_enabled",
+    "touchpad_tap_button_map",
+    "touchpad_tap_drag",
+    "touchpad_tap_drag_lock",
+    "mouse_state",
+    "mouse_acceleration",
+    "mouse_accel_speed",
+    "mouse_accel_profile",
+    "mouse_left_handed",
+    "mouse_middle_button_emulation",
+    "mouse_scroll_method",
+    "mouse_natural_scroll",
+    "mouse_scroll_factor",
+    "theme_mode",
+];
+
+// labwc device categories in `<libinput><device category="...">`.
+const TOUCHPAD: &str = "touchpad";
+const MOUSE: &str = "non-touch";
+
+const EMPTY_RC: &str = "<?xml version=\"1.0\"?>\n<labwc_config>\n</labwc_config>\n";
+
+/// labwc is configured through `rc.xml` (libinput, keyboard repeat) and `environment`
+/// (XKB). Every write patches the file in place; labwc is asked to reconfigure once the
+/// event or batch is written.
+pub struct Labwc {
+    config_dir: PathBuf,
+    /// Set by every write, so `apply_event`/`apply_events` reconfigure once at the end.
+    pending_reload: AtomicBool,
+}
+
+impl Labwc {
+    pub fn new() -> Self {
+        Self {
+            config_dir: dirs::config_dir().unwrap_or_default().join("labwc"),
+            pending_reload: AtomicBool::new(false),
+        }
+    }
+
+    fn dispatch(&self, event: Event) -> CompositorResult {
+        match event {
+            Event::Input(InputEvent::TouchPad { event: ev, .. }) => self.apply_touchpad_event(ev),
+            Event::Input(InputEvent::Mouse { event: ev, .. }) => self.apply_mouse_event(ev),
+            Event::Input(InputEvent::Keyboard(ev)) => self.apply_keyboard_event(ev),
+            Event::Shortcut(_) => Ok(()),
+            Event::Workspace(ev) => self.apply_workspace_event(ev),
+            Event::Theme(ev) => self.apply_theme_event(ev),
+            Event::Cursor(ev) => self.apply_cursor_event(ev),
+            Event::ColorTemp(ev) => self.apply_color_temp_event(ev),
+        }
+    }
+
+    /// Reconfigure when anything was written since the last time, including writes that
+    /// went through before a failed one.
+    fn reload_if_written(&self) -> CompositorResult {
+        if self.pending_reload.swap(false, Ordering::Relaxed) {
+            return self.reload();
+        }
+        Ok(())
+    }
+
+    fn yes_no(value: bool) -> &'static str {
+        if value { "yes" } else { "no" }
+    }
+
+    fn map_accel_profile(profile: &AccelProfile) -> Result<&'static str, Error> {
+        Ok(match libinput::AccelProfile::try_from(profile)? {
+            libinput::AccelProfile::Flat => "flat",
+            libinput::AccelProfile::Adaptive => "adaptive",
+        })
+    }
+
+    fn map_click_method(method: &ClickMethod) -> Result<&'static str, Error> {
+        Ok(match libinput::ClickMethod::try_from(method)? {
+            libinput::ClickMethod::ButtonAreas => "buttonAreas",
+            libinput::ClickMethod::Clickfinger => "clickfinger",
+        })
+    }
+
+    fn map_scroll_method(method: &ScrollMethod) -> Result<&'static str, Error> {
+        Ok(match libinput::ScrollMethod::try_from(method)? {
+            libinput::ScrollMethod::TwoFinger => "twofinger",
+            libinput::ScrollMethod::Edge => "edge",
+            libinput::ScrollMethod::OnButtonDown => "button",
+            libinput::ScrollMethod::NoScroll => "none",
+        })
+    }
+
+    fn map_tap_button_map(map: &TapButtonMap) -> Result<&'static str, Error> {
+        Ok(match libinput::TapButtonMap::try_from(map)? {
+            libinput::TapButtonMap::LeftRightMiddle => "lrm",
+            libinput::TapButtonMap::LeftMiddleRight => "lmr",
+        })
+    }
+
+    fn map_device_state(state: &DeviceState) -> &'static str {
+        match state {
+            DeviceState::Enabled => "yes",
+            DeviceState::Disabled => "no",
+            DeviceState::DisabledOnExternalMouse => "disabledOnExternalMouse",
+        }
+    }
+
+    /// Set `<libinput><device category="..."><key>value</key>`.
+    fn set_libinput(&self, category: &str, key: &str, value: impl ToString) -> InputResult {
+        self.patch_rc(&["libinput", "device"], Some(category), key, &value.to_string())
+    }
+
+    /// Set `<keyboard><key>value</key>`.
+    fn set_keyboard(&self, key: &str, value: impl ToString) -> InputResult {
+        self.patch_rc(&["keyboard"], None, key, &value.to_string())
+    }
+
+    fn set_opt_bool(&self, category: &str, key: &str, value: Option<bool>) -> InputResult {
+        if let Some(value) = value {
+            return self.set_libinput(category, key, Self::yes_no(value));
+        }
+        Ok(())
+    }
+
+    fn patch_rc(
+        &self,
+        section: &[&str],
+        category: Option<&str>,
+        key: &str,
+        value: &str,
+    ) -> InputResult {
+        let path = self.config_dir.join("rc.xml");
+        trace::write("labwc", || format!("rc.xml {} {key}={value}", section.join("/")), || {
+            let xml = read_or(&path, EMPTY_RC)?;
+            let patched = patch_xml(&xml, section, category, key, value)
+                .map_err(|err| format!("could not patch {}: {err}", path.display()))?;
+            atomic_write(&path, &patched)?;
+            Ok::<_, Error>(())
+        })?;
+        self.pending_reload.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn set_environment(&self, var: &str, value: &str) -> InputResult {
+        let path = self.config_dir.join("environment");
+        trace::write("labwc", || format!("environment {var}={value}"), || {
+            let contents = read_or(&path, "")?;
+            atomic_write(&path, &set_env_line(&contents, var, value))
+        })?;
+        self.pending_reload.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn acceleration(&self, category: &str, accel: Option<AccelConfig>) -> InputResult {
+        if let Some(accel) = accel {
+            self.set_libinput(category, "pointerSpeed", accel.speed.clamp(-1.0, 1.0))?;
+            if let Some(profile) = accel.profile {
+                let value = Self::map_accel_profile(&profile)?;
+                self.set_libinput(category, "accelProfile", value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Set the text of `key` inside the element at `section` (e.g. `libinput/device`),
+/// creating any missing elements. When `category` is given, the innermost section
+/// element must carry a matching `category` attribute. Everything else in the
+/// document, including comments and unrelated devices, is written back unchanged.
+fn patch_xml(
+    xml: &str,
+    section: &[&str],
+    category: Option<&str>,
+    key: &str,
+    value: &str,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let xml = if xml.trim().is_empty() { EMPTY_RC } else { xml };
+    let mut reader = Reader::from_str(xml);
+    let mut writer = Writer::new(Vec::new());
+
+    // `matched` counts the levels of `section` the currently open path goes through;
+    // `depth` counts open elements, with the document root at 1.
+    let mut matched = 0;
+    let mut depth = 0;
+    let mut replacing = false;
+    let mut done = false;
+
+    let is_target = |e: &BytesStart, level: usize| -> bool {
+        if e.name().as_ref() != section[level].as_bytes() {
+            return false;
+        }
+        if level + 1 < section.len() {
+            return true;
+        }
+        match category {
+            Some(category) => e
+                .try_get_attribute("category")
+                .ok()
+                .flatten()
+                .is_some_and(|attr| attr.value.as_ref() == category.as_bytes()),
+            None => true,
+        }
+    };
+
+    loop {
+        match reader.read_event()? {
+            XmlEvent::Eof => break,
+            XmlEvent::Start(e) => {
+                if replacing {
+                    continue;
+                }
+                depth += 1;
+                if !done && matched == section.len() && depth == section.len() + 2 {
+                    if e.name().as_ref() == key.as_bytes() {
+                        writer.write_event(XmlEvent::Start(e))?;
+                        replacing = true;
+                        continue;
+                    }
+                } else if !done && matched < section.len() && depth == matched + 2 {
+                    if is_target(&e, matched) {
+                        matched += 1;
+                    }
+                }
+                writer.write_event(XmlEvent::Start(e))?;
+            }
+            XmlEvent::Empty(e) => {
+                if replacing {
+                    continue;
+                }
+                if !done && matched == section.len() && depth == section.len() + 1 {
+                    if e.name().as_ref() == key.as_bytes() {
+                        write_missing(&mut writer, section, category, section.len(), key, value)?;
+                        done = true;
+                        continue;
+                    }
+                } else if !done
+                    && matched + 1 == section.len()
+                    && depth == matched + 1
+                    && is_target(&e, matched)
+                {
+                    // `<device category="..."/>`: expand it to hold the key.
+                    writer.write_event(XmlEvent::Start(e.to_owned()))?;
+                    write_missing(&mut writer, section, category, section.len(), key, value)?;
+                    writer.write_event(XmlEvent::End(e.to_end().into_owned()))?;
+                    done = true;
+                    continue;
+                }
+                writer.write_event(XmlEvent::Empty(e))?;
+            }
+            XmlEvent::End(e) => {
+                if replacing {
+                    if e.name().as_ref() == key.as_bytes() {
+                        writer.write_event(XmlEvent::Text(BytesText::new(value)))?;
+                        writer.write_event(XmlEvent::End(e))?;
+                        replacing = false;
+                        done = true;
+                        depth -= 1;
+                    }
+                    continue;
+                }
+                if !done && depth == matched + 1 {
+                    // Leaving the deepest matched element (or the root) without having
+                    // found the key: create whatever is missing right here.
+                    write_missing(&mut writer, section, category, matched, key, value)?;
+                    done = true;
+                }
+                if matched > 0 && depth == matched + 1 {
+                    matched -= 1;
+                }
+                depth -= 1;
+                writer.write_event(XmlEvent::End(e))?;
+            }
+            event => {
+                if !replacing {
+                    writer.write_event(event)?;
+                }
+            }
+        }
+    }
+
+    Ok(String::from_utf8(writer.into_inner())?)
+}
+
+/// Write the part of the `section` path below level `from`, containing `key`.
+fn write_missing(
+    writer: &mut Writer<Vec<u8>>,
+    section: &[&str],
+    category: Option<&str>,
+    from: usize,
+    key: &str,
+    value: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    for (level, name) in section.iter().enumerate().skip(from) {
+        let mut start = BytesStart::new(*name);
+        if level + 1 == section.len() {
+            if let Some(category) = category {
+                start.push_attribute(("category", category));
+            }
+        }
+        writer.write_event(XmlEvent::Start(start))?;
+    }
+    writer.write_event(XmlEvent::Start(BytesStart::new(key)))?;
+    writer.write_event(XmlEvent::Text(BytesText::new(value)))?;
+    writer.write_event(XmlEvent::End(BytesEnd::new(key)))?;
+    for name in section.iter().skip(from).rev() {
+        writer.write_event(XmlEvent::End(BytesEnd::new(*name)))?;
+    }
+    Ok(())
+}
+
+impl Compositor for Labwc {
+    fn init(&mut self) -> CompositorResult {
+        if self.config_dir.as_os_str().is_empty() {
+            return Err("Could not resolve the labwc config directory".into());
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "labwc"
+    }
+
+    fn is_running(&self) -> bool {
+        env::var("LABWC_PID").is_ok()
+    }
+
+    fn capabilities(&self) -> &'static [&'static str] {
+        CAPABILITIES
+    }
+
+    // `sendEventsMode` takes `disabledOnExternalMouse` for touchpads.
+    fn disables_touchpad_on_external_mouse(&self) -> bool {
+        true
+    }
+
+    fn apply_event(&self, event: Event) -> CompositorResult {
+        let result = self.dispatch(event);
+        let reloaded = self.reload_if_written();
+        result.and(reloaded)
+    }
+
+    // One reconfigure for the whole batch instead of one per event.
+    fn apply_events(&self, events: Vec<Event>) -> CompositorResult {
+        let mut result = Ok(());
+        for event in events {
+            let applied = self.dispatch(event);
+            if result.is_ok() {
+                result = applied;
+            }
+        }
+        let reloaded = self.reload_if_written();
+        result.and(reloaded)
+    }
+
+    fn reload(&self) -> CompositorResult {
+        // `labwc --reconfigure` sends SIGHUP to the instance named by $LABWC_PID.
+        let status = trace::write("labwc", || "labwc --reconfigure".to_string(), || {
+            Command::new("labwc").arg("--reconfigure").status()
+        })?;
+        if !status.success() {
+            return Err(format!("labwc --reconfigure exited with {status}").into());
+        }
+        Ok(())
+    }
+
+    fn shutdown(&self) -> CompositorResult {
+        Ok(())
+    }
+}
+
+// labwc desktops live in rc.xml `<desktops>` and are not synced yet.
+impl Workspace for Labwc {}
+
+// labwc has no night light; run gammastep or wlsunset alongside it.
+impl ColorTemp for Labwc {}
+
+// No appearance settings of its own; GTK apps follow the GSettings interface keys.
+impl Theme for Labwc {
+    fn theme_mode(&self, scheme: ColorScheme) -> CompositorResult {
+        theme::set_gtk_color_scheme("labwc", scheme, true)
+    }
+}
+
+impl Input for Labwc {
+    fn keyboard_rules(&self, rules: String) -> InputResult {
+        self.set_environment("XKB_DEFAULT_RULES", &rules)
+    }
+
+    fn keyboard_model(&self, model: String) -> InputResult {
+        self.set_environment("XKB_DEFAULT_MODEL", &model)
+    }
+
+    fn keyboard_layout(&self, layout: String) -> InputResult {
+        self.set_environment("XKB_DEFAULT_LAYOUT", &layout)
+    }
+
+    fn keyboard_variant(&self, variant: String) -> InputResult {
+        self.set_environment("XKB_DEFAULT_VARIANT", &variant)
+    }
+
+    fn keyboard_options(&self, options: Option<String>) -> InputResult {
+        self.set_environment("XKB_DEFAULT_OPTIONS", options.as_deref().unwrap_or_default())
+    }
+
+    fn keyboard_repeat_delay(&self, delay: u32) -> InputResult {
+        self.set_keyboard("repeatDelay", delay)
+    }
+
+    fn keyboard_repeat_rate(&self, rate: u32) -> InputResult {
+        self.set_keyboard("repeatRate", rate)
+    }
+
+    fn numslock_state(&self, state: NumlockState) -> InputResult {
+        match numlock::target(state) {
+            Some(true) => self.set_keyboard("numlock", "on"),
+            Some(false) => self.set_keyboard("numlock", "off"),
+            None => Ok(()),
+        }
+    }
+
+    /* Touchpad */
+
+    fn touchpad_state(&self, state: DeviceState) -> InputResult {
+        self.set_libinput(TOUCHPAD, "sendEventsMode", Self::map_device_state(&state))
+    }
+
+    fn touchpad_acceleration(&self, accel: Option<AccelConfig>) -> InputResult {
+        self.acceleration(TOUCHPAD, accel)
+    }
+
+    fn touchpad_accel_speed(&self, speed: f64) -> InputResult {
+        self.set_libinput(TOUCHPAD, "pointerSpeed", speed.clamp(-1.0, 1.0))
+    }
+
+    fn touchpad_accel_profile(&self, profile: Option<AccelProfile>) -> InputResult {
+        if let Some(profile) = profile {
+            let value = Self::map_accel_profile(&profile)?;
+            return self.set_libinput(TOUCHPAD, "accelProfile", value);
+        }
+        Ok(())
+    }
+
+    fn touchpad_click_method(&self, method: Option<ClickMethod>) -> InputResult {
+        if let Some(method) = method {
+            let value = Self::map_click_method(&method)?;
+            return self.set_libinput(TOUCHPAD, "clickMethod", value);
+        }
+        Ok(())
+    }
+
+    fn touchpad_disable_while_typing(&self, enabled: Option<bool>) -> InputResult {
+        self.set_opt_bool(TOUCHPAD, "disableWhileTyping", enabled)
+    }
+
+    fn touchpad_left_handed(&self, enabled: Option<bool>) -> InputResult {
+        self.set_opt_bool(TOUCHPAD, "leftHanded", enabled)
+    }
+
+    fn touchpad_middle_button_emulation(&self, enabled: Option<bool>) -> InputResult {
+        self.set_opt_bool(TOUCHPAD, "middleEmulation", enabled)
+    }
+
+    fn touchpad_scroll_method(&self, method: Option<ScrollMethod>) -> InputResult {
+        if let Some(method) = method {
+            let value = Self::map_scroll_method(&method)?;
+            return self.set_libinput(TOUCHPAD, "scrollMethod", value);
+        }
+        Ok(())
+    }
+
+    fn touchpad_natural_scroll(&self, enabled: Option<bool>) -> InputResult {
+        self.set_opt_bool(TOUCHPAD, "naturalScroll", enabled)
+    }
+
+    fn touchpad_scroll_factor(&self, factor: Option<f64>) -> InputResult {
+        if let Some(factor) = factor {
+            return self.set_libinput(TOUCHPAD, "scrollFactor", scroll::multiplier(factor));
+        }
+        Ok(())
+    }
+
+    fn touchpad_tap_config(&self, config: Option<TapConfig>) -> InputResult {
+        if let Some(config) = config {
+            self.touchpad_tap_enabled(config.enabled)?;
+            self.touchpad_tap_drag(config.drag)?;
+            self.touchpad_tap_drag_lock(config.drag_lock)?;
+        }
+        Ok(())
+    }
+
+    fn touchpad_tap_enabled(&self, enabled: bool) -> InputResult {
+        self.set_libinput(TOUCHPAD, "tap", Self::yes_no(enabled))
+    }
+
+    fn touchpad_tap_button_map(&self, map: Option<TapButtonMap>) -> InputResult {
+        if let Some(map) = map {
+            let value = Self::map_tap_button_map(&map)?;
+            return self.set_libinput(TOUCHPAD, "tapButtonMap", value);
+        }
+        Ok(())
+    }
+
+    fn touchpad_tap_drag(&self, enabled: bool) -> InputResult {
+        self.set_libinput(TOUCHPAD, "tapAndDrag", Self::yes_no(enabled))
+    }
+
+    fn touchpad_tap_drag_lock(&self, enabled: bool) -> InputResult {
+        self.set_libinput(TOUCHPAD, "dragLock", Self::yes_no(enabled))
+    }
+
+    /* Mouse */
+
+    fn mouse_state(&self, state: DeviceState) -> InputResult {
+        // disabledOnExternalMouse only makes sense for touchpads.
+        let state = match state {
+            DeviceState::DisabledOnExternalMouse => DeviceState::Enabled,
+            state => state,
+        };
+        self.set_libinput(MOUSE, "sendEventsMode", Self::map_device_state(&state))
+    }
+
+    fn mouse_acceleration(&self, accel: Option<AccelConfig>) -> InputResult {
+        self.acceleration(MOUSE, accel)
+    }
+
+    fn mouse_accel_speed(&self, speed: f64) -> InputResult {
+        self.set_libinput(MOUSE, "pointerSpeed", speed.clamp(-1.0, 1.0))
+    }
+
+    fn mouse_accel_profile(&self, profile: Option<AccelProfile>) -> InputResult {
+        if let Some(profile) = profile {
+            let value = Self::map_accel_profile(&profile)?;
+            return self.set_libinput(MOUSE, "accelProfile", value);
+        }
+        Ok(())
+    }
+
+    fn mouse_left_handed(&self, enabled: Option<bool>) -> InputResult {
+        self.set_opt_bool(MOUSE, "leftHanded", enabled)
+    }
+
+    fn mouse_middle_button_emulation(&self, enabled: Option<bool>) -> InputResult {
+        self.set_opt_bool(MOUSE, "middleEmulation", enabled)
+    }
+
+    fn mouse_scroll_method(&self, method: Option<ScrollMethod>) -> InputResult {
+        if let Some(method) = method {
+            let value = Self::map_scroll_method(&method)?;
+            return self.set_libinput(MOUSE, "scrollMethod", value);
+        }
+        Ok(())
+    }
+
+    fn mouse_natural_scroll(&self, enabled: Option<bool>) -> InputResult {
+        self.set_opt_bool(MOUSE, "naturalScroll", enabled)
+    }
+
+    fn mouse_scroll_factor(&self, factor: Option<f64>) -> InputResult {
+        if let Some(factor) = factor {
+            return self.set_libinput(MOUSE, "scrollFactor", scroll::multiplier(factor));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEVICE: &[&str] = &["libinput", "device"];
+
+    fn patch(xml: &str) -> String {
+        patch_xml(xml, DEVICE, Some(TOUCHPAD), "naturalScroll", "yes").unwrap()
+    }
+
+    #[test]
+    fn a_missing_libinput_section_is_created() {
+        let xml = "<labwc_config><keyboard><repeatRate>25</repeatRate></keyboard></labwc_config>";
+        assert_eq!(
+            patch(xml),
+            "<labwc_config><keyboard><repeatRate>25</repeatRate></keyboard>\
+             <libinput><device category=\"touchpad\"><naturalScroll>yes</naturalScroll>\
+             </device></libinput></labwc_config>"
+        );
+    }
+
+    #[test]
+    fn a_self_closing_device_is_expanded() {
+        let xml =
+            "<labwc_config><libinput><device category=\"touchpad\"/></libinput></labwc_config>";
+        assert_eq!(
+            patch(xml),
+            "<labwc_config><libinput><device category=\"touchpad\">\
+             <naturalScroll>yes</naturalScroll></device></libinput></labwc_config>"
+        );
+    }
+
+    #[test]
+    fn other_categories_and_keys_are_kept() {
+        let xml = "<labwc_config><libinput>\
+                   <device category=\"non-touch\"><naturalScroll>no</naturalScroll></device>\
+                   <device category=\"touchpad\">\
+                   <naturalScroll>no</naturalScroll><tap>yes</tap></device>\
+                   </libinput></labwc_config>";
+        assert_eq!(
+            patch(xml),
+            "<labwc_config><libinput>\
+             <device category=\"non-touch\"><naturalScroll>no</naturalScroll></device>\
+             <device category=\"touchpad\">\
+             <naturalScroll>yes</naturalScroll><tap>yes</tap></device>\
+             </libinput></labwc_config>"
+        );
+    }
+
+    #[test]
+    fn an_empty_file_gets_the_whole_section() {
+        let patched = patch("");
+        assert!(patched.contains(
+            "<libinput><device category=\"touchpad\"><naturalScroll>yes</naturalScroll>\
+             </device></libinput></labwc_config>"
+        ));
+    }
+}
@@ -0,0 +1,168 @@
+//! Exhaustive mirrors of the libinput enums in `cosmic_comp_config::input`.
+//!
+//! Upstream marks those enums `#[non_exhaustive]`, so matching on them directly forces a
+//! `_` arm that silently picks some default when cosmic-comp adds a variant. Backends
+//! convert through here instead: known variants map one to one, and anything new
+//! becomes a visible `Error::UnsupportedValue` until it is added below.
+
+use cosmic_comp_config::input;
+
+use crate::error::Error;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccelProfile {
+    Flat,
+    Adaptive,
+}
+
+impl AccelProfile {
+    /// COSMIC's "Acceleration" toggle as a profile: on is adaptive, off is flat. COSMIC
+    /// stores the profile itself, so this is the whole mapping.
+    pub fn from_enabled(enabled: bool) -> AccelProfile {
+        if enabled {
+            AccelProfile::Adaptive
+        } else {
+            AccelProfile::Flat
+        }
+    }
+
+    /// Whether acceleration is on. No profile means libinput's default, adaptive.
+    pub fn is_enabled(profile: Option<&AccelProfile>) -> bool {
+        !matches!(profile, Some(AccelProfile::Flat))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClickMethod {
+    ButtonAreas,
+    Clickfinger,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScrollMethod {
+    NoScroll,
+    TwoFinger,
+    Edge,
+    OnButtonDown,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TapButtonMap {
+    LeftRightMiddle,
+    LeftMiddleRight,
+}
+
+fn unsupported(setting: &'static str, value: &impl std::fmt::Debug) -> Error {
+    Error::UnsupportedValue {
+        setting,
+        value: format!("{value:?}"),
+    }
+}
+
+impl TryFrom<&input::AccelProfile> for AccelProfile {
+    type Error = Error;
+
+    fn try_from(value: &input::AccelProfile) -> Result<Self, Error> {
+        match value {
+            input::AccelProfile::Flat => Ok(AccelProfile::Flat),
+            input::AccelProfile::Adaptive => Ok(AccelProfile::Adaptive),
+            #[allow(unreachable_patterns)]
+            other => Err(unsupported("accel_profile", other)),
+        }
+    }
+}
+
+impl TryFrom<&input::ClickMethod> for ClickMethod {
+    type Error = Error;
+
+    fn try_from(value: &input::ClickMethod) -> Result<Self, Error> {
+        match value {
+            input::ClickMethod::ButtonAreas => Ok(ClickMethod::ButtonAreas),
+            input::ClickMethod::Clickfinger => Ok(ClickMethod::Clickfinger),
+            #[allow(unreachable_patterns)]
+            other => Err(unsupported("click_method", other)),
+        }
+    }
+}
+
+impl TryFrom<&input::ScrollMethod> for ScrollMethod {
+    type Error = Error;
+
+    fn try_from(value: &input::ScrollMethod) -> Result<Self, Error> {
+        match value {
+            input::ScrollMethod::NoScroll => Ok(ScrollMethod::NoScroll),
+            input::ScrollMethod::TwoFinger => Ok(ScrollMethod::TwoFinger),
+            input::ScrollMethod::Edge => Ok(ScrollMethod::Edge),
+            input::ScrollMethod::OnButtonDown => Ok(ScrollMethod::OnButtonDown),
+            #[allow(unreachable_patterns)]
+            other => Err(unsupported("scroll_method", other)),
+        }
+    }
+}
+
+impl TryFrom<&input::TapButtonMap> for TapButtonMap {
+    type Error = Error;
+
+    fn try_from(value: &input::TapButtonMap) -> Result<Self, Error> {
+        match value {
+            input::TapButtonMap::LeftRightMiddle => Ok(TapButtonMap::LeftRightMiddle),
+            input::TapButtonMap::LeftMiddleRight => Ok(TapButtonMap::LeftMiddleRight),
+            #[allow(unreachable_patterns)]
+            other => Err(unsupported("tap_button_map", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmic_comp_config::input;
+
+    use super::*;
+
+    #[test]
+    fn acceleration_toggle_maps_to_flat_and_adaptive() {
+        assert_eq!(AccelProfile::from_enabled(false), AccelProfile::Flat);
+        assert_eq!(AccelProfile::from_enabled(true), AccelProfile::Adaptive);
+        assert!(AccelProfile::is_enabled(None));
+        assert!(!AccelProfile::is_enabled(Some(&AccelProfile::Flat)));
+    }
+
+    // Every variant cosmolith knows about must convert. A new upstream variant is not
+    // caught here (the enums are non-exhaustive), but it surfaces as UnsupportedValue
+    // at runtime instead of a silently wrong mapping.
+    #[test]
+    fn known_upstream_variants_convert() {
+        assert_eq!(
+            AccelProfile::try_from(&input::AccelProfile::Flat).ok(),
+            Some(AccelProfile::Flat)
+        );
+        assert_eq!(
+            AccelProfile::try_from(&input::AccelProfile::Adaptive).ok(),
+            Some(AccelProfile::Adaptive)
+        );
+        assert_eq!(
+            ClickMethod::try_from(&input::ClickMethod::ButtonAreas).ok(),
+            Some(ClickMethod::ButtonAreas)
+        );
+        assert_eq!(
+            ClickMethod::try_from(&input::ClickMethod::Clickfinger).ok(),
+            Some(ClickMethod::Clickfinger)
+        );
+        for (upstream, local) in [
+            (input::ScrollMethod::NoScroll, ScrollMethod::NoScroll),
+            (input::ScrollMethod::TwoFinger, ScrollMethod::TwoFinger),
+            (input::ScrollMethod::Edge, ScrollMethod::Edge),
+            (input::ScrollMethod::OnButtonDown, ScrollMethod::OnButtonDown),
+        ] {
+            assert_eq!(ScrollMethod::try_from(&upstream).ok(), Some(local));
+        }
+        assert_eq!(
+            TapButtonMap::try_from(&input::TapButtonMap::LeftRightMiddle).ok(),
+            Some(TapButtonMap::LeftRightMiddle)
+        );
+        assert_eq!(
+            TapButtonMap::try_from(&input::TapButtonMap::LeftMiddleRight).ok(),
+            Some(TapButtonMap::LeftMiddleRight)
+        );
+    }
+}
@@ -0,0 +1,150 @@
+// A backend that records events instead of sending them, for tests.
+
+use std::sync::{Arc, Mutex};
+
+use crate::compositor::{BACKENDS, Compositor, CompositorResult};
+use crate::event::Event;
+
+/// Records every event it is asked to apply, in order, without talking to any compositor.
+///
+/// Clones share one recording, so a clone can be handed to `Session::with_backend` to
+/// drive the real capability checks and batching while the original is asserted on.
+#[derive(Clone)]
+pub struct MockCompositor {
+    capabilities: &'static [&'static str],
+    events: Arc<Mutex<Vec<Event>>>,
+    batches: Arc<Mutex<Vec<usize>>>,
+}
+
+impl MockCompositor {
+    /// A mock handling exactly `capabilities` (see `Event::name`).
+    pub fn new(capabilities: &'static [&'static str]) -> MockCompositor {
+        MockCompositor {
+            capabilities,
+            events: Arc::default(),
+            batches: Arc::default(),
+        }
+    }
+
+    /// A mock with the capabilities of the `BACKENDS` entry called `backend`.
+    pub fn emulating(backend: &str) -> Option<MockCompositor> {
+        BACKENDS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(backend))
+            .map(|&(_, capabilities)| MockCompositor::new(capabilities))
+    }
+
+    /// Every event applied so far, in the order it reached the backend.
+    pub fn recorded_events(&self) -> Vec<Event> {
+        self.events.lock().unwrap().clone()
+    }
+
+    /// Size of each `apply_events` batch so far. Single `apply_event` calls are not listed.
+    pub fn recorded_batches(&self) -> Vec<usize> {
+        self.batches.lock().unwrap().clone()
+    }
+}
+
+impl Compositor for MockCompositor {
+    fn init(&mut self) -> CompositorResult {
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn is_running(&self) -> bool {
+        true
+    }
+
+    fn capabilities(&self) -> &'static [&'static str] {
+        self.capabilities
+    }
+
+    fn apply_event(&self, event: Event) -> CompositorResult {
+        self.events.lock().unwrap().push(event);
+        Ok(())
+    }
+
+    fn apply_events(&self, events: Vec<Event>) -> CompositorResult {
+        self.batches.lock().unwrap().push(events.len());
+        self.events.lock().unwrap().extend(events);
+        Ok(())
+    }
+
+    fn reload(&self) -> CompositorResult {
+        Ok(())
+    }
+
+    fn shutdown(&self) -> CompositorResult {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmic_comp_config::input::{DeviceState, InputConfig, ScrollConfig, ScrollMethod};
+
+    use super::*;
+    use crate::error::Error;
+    use crate::event::input::TouchpadEvent;
+    use crate::session::Session;
+
+    fn touchpad() -> InputConfig {
+        InputConfig {
+            state: DeviceState::Enabled,
+            acceleration: None,
+            calibration: None,
+            click_method: None,
+            disable_while_typing: Some(true),
+            left_handed: None,
+            middle_button_emulation: None,
+            rotation_angle: None,
+            scroll_config: Some(ScrollConfig {
+                method: Some(ScrollMethod::TwoFinger),
+                natural_scroll: Some(false),
+                scroll_button: None,
+                scroll_factor: Some(1.0),
+            }),
+            tap_config: None,
+            map_to_output: None,
+        }
+    }
+
+    #[test]
+    fn config_diff_reaches_the_backend_as_one_ordered_batch() {
+        let old = touchpad();
+        let mut new = old.clone();
+        new.disable_while_typing = Some(false);
+        if let Some(scroll) = new.scroll_config.as_mut() {
+            scroll.natural_scroll = Some(true);
+        }
+
+        let mock = MockCompositor::emulating("Sway").unwrap();
+        let session = Session::with_backend("test", Box::new(mock.clone()));
+        let events = TouchpadEvent::from(old, new);
+        let expected: Vec<_> = events.iter().map(|event| event.name()).collect();
+        session.apply_events(events).unwrap();
+
+        let recorded: Vec<_> = mock.recorded_events().iter().map(|event| event.name()).collect();
+        assert_eq!(recorded, expected);
+        assert!(recorded.contains(&"touchpad_natural_scroll"));
+        assert!(recorded.contains(&"touchpad_disable_while_typing"));
+        assert_eq!(mock.recorded_batches(), vec![expected.len()]);
+    }
+
+    #[test]
+    fn unsupported_events_never_reach_the_backend() {
+        let old = touchpad();
+        let mut new = old.clone();
+        new.disable_while_typing = Some(false);
+
+        let mock = MockCompositor::new(&[]);
+        let session = Session::with_backend("test", Box::new(mock.clone()));
+        let err = session.apply_events(TouchpadEvent::from(old, new)).unwrap_err();
+
+        assert!(matches!(err, Error::NotImplemented { .. }));
+        assert!(mock.recorded_events().is_empty());
+    }
+}
@@ -1,12 +1,43 @@
+pub mod color_temp;
+pub mod config_file;
+pub mod dry_run;
 pub mod gnome;
 pub mod hyprland;
 pub mod input;
 pub mod kde;
+pub mod labwc;
+pub mod libinput;
+pub mod mock;
+pub mod numlock;
+pub mod reconnect;
 pub mod sway;
 pub mod shortcut;
+pub mod theme;
+pub mod trace;
+pub mod wayfire;
+pub mod weston;
+pub mod workspace;
+pub mod xfce;
+pub mod xinput;
 use crate::event::Event;
+use crate::identifier::Desktop;
 use std::error::Error;
-pub type CompositorResult = Result<(), Box<dyn Error + Send + Sync>>;
+use tracing::warn;
+pub type CompositorResult = crate::error::Result<()>;
+
+/// Every backend with the events it handles, without having to construct or connect it.
+pub const BACKENDS: &[(&str, &[&str])] = &[
+    ("Hyprland", hyprland::CAPABILITIES),
+    ("Sway", sway::CAPABILITIES),
+    ("labwc", labwc::CAPABILITIES),
+    ("Wayfire", wayfire::CAPABILITIES),
+    ("Weston", weston::CAPABILITIES),
+    ("KDE Plasma", kde::CAPABILITIES),
+    ("GNOME", gnome::CAPABILITIES),
+    ("XFCE", xfce::CAPABILITIES),
+    ("X11 (xinput)", xinput::CAPABILITIES),
+];
+
 /// Central compositor interface used by the dispatcher.
 #[allow(unused)]
 pub trait Compositor {
@@ -19,12 +50,56 @@ pub trait Compositor {
     /// Fast check to see if the compositor is running/available.
     fn is_running(&self) -> bool;
 
+    /// Names of the events this compositor actually handles (see `Event::name`).
+    /// Handlers left on the `Input`/`Shortcut` defaults must not be listed here.
+    fn capabilities(&self) -> &'static [&'static str];
+
     /// Whether a given event is supported by this compositor.
-    fn supports(&self, event: &Event) -> bool;
+    fn supports(&self, event: &Event) -> bool {
+        self.capabilities().contains(&event.name())
+    }
+
+    /// Whether the compositor applies `DeviceState::DisabledOnExternalMouse` itself. When
+    /// it does not, the daemon resolves the state to `Enabled` or `Disabled` as external
+    /// mice come and go (see `watcher::device_monitor`).
+    fn disables_touchpad_on_external_mouse(&self) -> bool {
+        false
+    }
+
+    /// Whether settings applied by this backend outlive the compositor process, e.g.
+    /// because they are written to its config files. Backends that only change the
+    /// running compositor get the full sync at every start instead of a diff against the
+    /// last applied state, since a restarted compositor is back on its own defaults.
+    fn keeps_settings(&self) -> bool {
+        true
+    }
+
+    /// Current value of the setting behind `event_name`, if the backend can query it.
+    /// Only used by `self-test` to confirm that a handler took effect.
+    fn read_back(&self, event_name: &str) -> Option<String> {
+        None
+    }
 
     /// Apply a single event to the compositor.
     fn apply_event(&self, event: Event) -> CompositorResult;
 
+    /// Apply a batch of events in the order given, which is the order the config diff
+    /// produced them in. Every event is attempted even after one fails; the first error
+    /// is returned.
+    ///
+    /// Backends that can submit several settings in one IPC transaction override this so
+    /// a batch is not left half-applied by a dropped connection.
+    fn apply_events(&self, events: Vec<Event>) -> CompositorResult {
+        let mut result = Ok(());
+        for event in events {
+            let applied = self.apply_event(event);
+            if result.is_ok() {
+                result = applied;
+            }
+        }
+        result
+    }
+
     /// Optional reload hook if compositor exposes a reload action.
     fn reload(&self) -> CompositorResult;
 
@@ -32,36 +107,183 @@ pub trait Compositor {
     fn shutdown(&self) -> CompositorResult;
 }
 
-pub fn init_compositor(desktop: crate::identifier::Desktop) -> Option<Box<dyn Compositor>> {
+/// Construct and initialize the backend for `desktop`.
+///
+/// Returns `Ok(None)` when there is no backend for the desktop, and the `init` error
+/// when there is one but it could not be initialized.
+pub fn init_compositor(
+    desktop: Desktop,
+) -> Result<Option<Box<dyn Compositor>>, Box<dyn Error + Send + Sync>> {
+    // No wildcard arm: a new Desktop variant must be wired here or given a reason in
+    // `backend_for`.
     match desktop {
-        crate::identifier::Desktop::Hyprland => {
-            let mut compositor = hyprland::Hyprland::new();
-            if compositor.init().is_ok() {
-                return Some(Box::new(compositor));
+        Desktop::Hyprland => init_backend(hyprland::Hyprland::new()),
+        Desktop::Sway => init_backend(sway::Sway::new()),
+        Desktop::Labwc => init_backend(labwc::Labwc::new()),
+        Desktop::Wayfire => init_backend(wayfire::Wayfire::new()),
+        Desktop::Weston => init_backend(weston::Weston::new()),
+        Desktop::Kde | Desktop::Plasma => init_backend(kde::Kde::new()),
+        Desktop::Gnome => init_backend(gnome::Gnome::new()),
+        Desktop::Xfce => init_backend(xfce::Xfce::new()),
+        // Without a display there is no X server for xinput to talk to.
+        Desktop::X11 if std::env::var_os("DISPLAY").is_some() => {
+            init_backend(xinput::Xinput::new())
+        }
+        Desktop::X11 => {
+            warn!("No backend for {desktop:?}: DISPLAY is not set, so xinput has no X server");
+            Ok(None)
+        }
+        Desktop::Cosmic | Desktop::Wayland | Desktop::Tty | Desktop::Unknown(_) => {
+            if let Err(reason) = backend_for(&desktop) {
+                warn!("No backend for {desktop:?}: {reason}");
             }
-            None
+            Ok(None)
         }
-        crate::identifier::Desktop::Sway => {
-            let mut compositor = sway::Sway::new();
-            if compositor.init().is_ok() {
-                return Some(Box::new(compositor));
+    }
+}
+
+/// The `BACKENDS` entry `init_compositor` uses for `desktop`, or why there is none.
+pub fn backend_for(desktop: &Desktop) -> Result<&'static str, &'static str> {
+    match desktop {
+        Desktop::Hyprland => Ok("Hyprland"),
+        Desktop::Sway => Ok("Sway"),
+        Desktop::Labwc => Ok("labwc"),
+        Desktop::Wayfire => Ok("Wayfire"),
+        Desktop::Weston => Ok("Weston"),
+        Desktop::Kde | Desktop::Plasma => Ok("KDE Plasma"),
+        Desktop::Gnome => Ok("GNOME"),
+        Desktop::Xfce => Ok("XFCE"),
+        Desktop::Cosmic => Err("COSMIC applies its own settings, there is nothing to sync"),
+        Desktop::Wayland => Err("the Wayland compositor was not recognised"),
+        Desktop::X11 => Ok("X11 (xinput)"),
+        Desktop::Tty => Err("not a graphical session"),
+        Desktop::Unknown(_) => Err("the desktop was not recognised"),
+    }
+}
+
+/// The desktop whose backend is the `BACKENDS` entry called `backend`, the inverse of
+/// `backend_for`. Matched case-insensitively, as in `--compositor sway`.
+pub fn desktop_for(backend: &str) -> Option<Desktop> {
+    let (name, _) = BACKENDS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(backend))?;
+    match *name {
+        "Hyprland" => Some(Desktop::Hyprland),
+        "Sway" => Some(Desktop::Sway),
+        "labwc" => Some(Desktop::Labwc),
+        "Wayfire" => Some(Desktop::Wayfire),
+        "Weston" => Some(Desktop::Weston),
+        "KDE Plasma" => Some(Desktop::Kde),
+        "GNOME" => Some(Desktop::Gnome),
+        "XFCE" => Some(Desktop::Xfce),
+        "X11 (xinput)" => Some(Desktop::X11),
+        _ => None,
+    }
+}
+
+fn init_backend<C: Compositor + 'static>(
+    mut compositor: C,
+) -> Result<Option<Box<dyn Compositor>>, Box<dyn Error + Send + Sync>> {
+    compositor.init()?;
+    Ok(Some(Box::new(compositor)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{BACKENDS, backend_for, desktop_for};
+    use crate::identifier::Desktop;
+    use crate::event::catalog::{EVENTS, describe};
+
+    // Capabilities are matched against `Event::name`, so a renamed or misspelled entry
+    // would silently mark a handler unsupported. Every entry must be a catalog event.
+    #[test]
+    fn capabilities_name_real_events() {
+        for (backend, capabilities) in BACKENDS {
+            let mut seen = HashSet::new();
+            for name in *capabilities {
+                assert!(describe(name).is_some(), "{backend}: unknown event {name}");
+                assert!(seen.insert(*name), "{backend}: {name} listed twice");
             }
-            None
         }
-        crate::identifier::Desktop::Kde => {
-              let mut compositor = kde::Kde::new();
-              if compositor.init().is_ok() {
-                  return Some(Box::new(compositor));
-              }
-              None
-         }
-        crate::identifier::Desktop::Gnome => {
-            let mut compositor = gnome::Gnome::new();
-            if compositor.init().is_ok() {
-                return Some(Box::new(compositor));
+    }
+
+    // Every capability is a catalog event (see above), and every catalog event is handled
+    // by some backend, apart from the ones no supported compositor can express yet.
+    #[test]
+    fn matrix_covers_every_event() {
+        const UNHANDLED: &[&str] = &[
+            "touchpad_rotation_angle",
+            "mouse_rotation_angle",
+            "mouse_tap_config",
+        ];
+        assert!(BACKENDS.iter().all(|(_, capabilities)| !capabilities.is_empty()));
+        for event in EVENTS {
+            let handled = BACKENDS
+                .iter()
+                .any(|(_, capabilities)| capabilities.contains(&event.name));
+            let expected = !UNHANDLED.contains(&event.name);
+            let name = event.name;
+            assert_eq!(handled, expected, "{name} is handled: {handled}");
+        }
+    }
+
+    #[test]
+    fn every_desktop_has_a_backend_or_a_reason() {
+        // Adding a Desktop variant fails to compile here until it is listed.
+        let all = |desktop: &Desktop| match desktop {
+            Desktop::Hyprland
+            | Desktop::Sway
+            | Desktop::Labwc
+            | Desktop::Wayfire
+            | Desktop::Weston
+            | Desktop::Gnome
+            | Desktop::Kde
+            | Desktop::Plasma
+            | Desktop::Xfce
+            | Desktop::Cosmic
+            | Desktop::Wayland
+            | Desktop::X11
+            | Desktop::Tty
+            | Desktop::Unknown(_) => (),
+        };
+        let desktops = [
+            Desktop::Hyprland,
+            Desktop::Sway,
+            Desktop::Labwc,
+            Desktop::Wayfire,
+            Desktop::Weston,
+            Desktop::Gnome,
+            Desktop::Kde,
+            Desktop::Plasma,
+            Desktop::Xfce,
+            Desktop::Cosmic,
+            Desktop::Wayland,
+            Desktop::X11,
+            Desktop::Tty,
+            Desktop::Unknown("cage".to_string()),
+        ];
+
+        for desktop in &desktops {
+            all(desktop);
+            match backend_for(desktop) {
+                Ok(name) => assert!(
+                    BACKENDS.iter().any(|(backend, _)| *backend == name),
+                    "{desktop:?} maps to unknown backend {name}"
+                ),
+                Err(reason) => assert!(!reason.is_empty(), "{desktop:?} has no reason"),
             }
-            None
         }
-        _ => None,
+    }
+
+    #[test]
+    fn every_backend_can_be_forced() {
+        for (name, _) in BACKENDS {
+            let desktop = desktop_for(&name.to_lowercase())
+                .unwrap_or_else(|| panic!("{name} has no desktop"));
+            assert_eq!(backend_for(&desktop), Ok(*name));
+        }
+        assert!(desktop_for("niri").is_none());
     }
 }
@@ -0,0 +1,118 @@
+//! Resolves COSMIC's numlock setting to the state a backend should configure.
+//!
+//! COSMIC's `LastBoot` means "restore the state from the previous session". cosmolith
+//! records every numlock state it resolves in `$XDG_STATE_HOME/cosmolith/numlock` and
+//! restores that on `LastBoot`; before anything was recorded the
+//! `numlock.default_when_unknown` policy from the cosmolith config decides. Backends call
+//! [`target`] so every one of them treats `LastBoot` the same way. The startup sync sends
+//! any state other than the default, so `LastBoot` is resolved at every start.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use cosmic_comp_config::NumlockState;
+use tracing::warn;
+
+use crate::compositor::config_file::{atomic_write, read_or};
+use crate::compositor::trace;
+use crate::config::NumlockDefault;
+
+static DEFAULT_WHEN_UNKNOWN: AtomicU8 = AtomicU8::new(encode(NumlockDefault::Leave));
+
+const fn encode(policy: NumlockDefault) -> u8 {
+    match policy {
+        NumlockDefault::On => 0,
+        NumlockDefault::Off => 1,
+        NumlockDefault::Leave => 2,
+    }
+}
+
+pub fn set_default_when_unknown(policy: NumlockDefault) {
+    DEFAULT_WHEN_UNKNOWN.store(encode(policy), Ordering::Relaxed);
+}
+
+pub fn default_when_unknown() -> NumlockDefault {
+    match DEFAULT_WHEN_UNKNOWN.load(Ordering::Relaxed) {
+        0 => NumlockDefault::On,
+        1 => NumlockDefault::Off,
+        _ => NumlockDefault::Leave,
+    }
+}
+
+/// Whether numlock should be on at startup, or `None` to leave the backend's setting as is.
+/// The result is recorded for the next `LastBoot`.
+pub fn target(state: NumlockState) -> Option<bool> {
+    let target = resolve(state, last_state(), default_when_unknown());
+    if let Some(enabled) = target {
+        record(enabled);
+    }
+    target
+}
+
+/// `$XDG_STATE_HOME/cosmolith/numlock`, falling back to `~/.local/state`.
+fn state_path() -> Option<PathBuf> {
+    Some(dirs::state_dir()?.join("cosmolith").join("numlock"))
+}
+
+/// The numlock state recorded by the last [`target`], if any.
+fn last_state() -> Option<bool> {
+    let contents = read_or(&state_path()?, "").ok()?;
+    match contents.trim() {
+        "on" => Some(true),
+        "off" => Some(false),
+        _ => None,
+    }
+}
+
+fn record(enabled: bool) {
+    // Under --dry-run nothing was applied, so there is nothing to restore later.
+    let Some(path) = state_path().filter(|_| !trace::dry_run()) else {
+        return;
+    };
+    let contents = if enabled { "on\n" } else { "off\n" };
+    // Most syncs repeat the recorded state; leave the file alone then.
+    if fs::read_to_string(&path).is_ok_and(|current| current == contents) {
+        return;
+    }
+    if let Err(err) = atomic_write(&path, contents) {
+        warn!("Failed to record the numlock state in {}: {err}", path.display());
+    }
+}
+
+fn resolve(state: NumlockState, last: Option<bool>, policy: NumlockDefault) -> Option<bool> {
+    match state {
+        NumlockState::BootOn => Some(true),
+        NumlockState::BootOff => Some(false),
+        NumlockState::LastBoot => last.or(match policy {
+            NumlockDefault::On => Some(true),
+            NumlockDefault::Off => Some(false),
+            NumlockDefault::Leave => None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_boot_follows_the_policy() {
+        assert_eq!(resolve(NumlockState::LastBoot, None, NumlockDefault::Leave), None);
+        assert_eq!(resolve(NumlockState::LastBoot, None, NumlockDefault::On), Some(true));
+        assert_eq!(resolve(NumlockState::LastBoot, None, NumlockDefault::Off), Some(false));
+    }
+
+    #[test]
+    fn last_boot_restores_the_recorded_state() {
+        let last_boot = |last, policy| resolve(NumlockState::LastBoot, last, policy);
+        assert_eq!(last_boot(Some(true), NumlockDefault::Off), Some(true));
+        assert_eq!(last_boot(Some(false), NumlockDefault::Leave), Some(false));
+    }
+
+    #[test]
+    fn explicit_states_ignore_the_policy() {
+        assert_eq!(resolve(NumlockState::BootOn, Some(false), NumlockDefault::Off), Some(true));
+        assert_eq!(resolve(NumlockState::BootOff, Some(true), NumlockDefault::On), Some(false));
+    }
+}
@@ -0,0 +1,196 @@
+//! Reconnect-with-backoff shared by the backends that keep an IPC connection open.
+//!
+//! A backend keeps its connection in an `Option` and calls [`with_reconnect`] with a
+//! closure that opens a new connection and one that sends over it. A failed send drops
+//! the connection and retries after a growing delay, until the policy gives up with
+//! `Error::IpcReconnectFailed`.
+
+use std::fmt::Display;
+use std::sync::{Mutex, MutexGuard, RwLock};
+use std::thread;
+use std::time::Duration;
+
+use serde::Deserialize;
+use tracing::{error, warn};
+
+use crate::config::millis;
+use crate::error::Error;
+
+/// How often and how patiently a backend reconnects before reporting failure.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReconnectPolicy {
+    /// Reconnects after the first failed call; 0 fails on the first error.
+    pub max_attempts: u32,
+    /// Delay before the first reconnect, doubled for each one after it.
+    #[serde(rename = "base_delay_ms", deserialize_with = "millis")]
+    pub base_delay: Duration,
+    #[serde(rename = "max_delay_ms", deserialize_with = "millis")]
+    pub max_delay: Duration,
+}
+
+impl ReconnectPolicy {
+    pub const DEFAULT: ReconnectPolicy = ReconnectPolicy {
+        max_attempts: 3,
+        base_delay: Duration::from_millis(100),
+        max_delay: Duration::from_secs(2),
+    };
+
+    /// Delay before reconnect number `attempt` (starting at 1).
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+static POLICY: RwLock<ReconnectPolicy> = RwLock::new(ReconnectPolicy::DEFAULT);
+
+/// Set the policy every backend uses, from the cosmolith config.
+pub fn set_policy(policy: ReconnectPolicy) {
+    *POLICY.write().unwrap_or_else(|err| err.into_inner()) = policy;
+}
+
+pub fn policy() -> ReconnectPolicy {
+    *POLICY.read().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Lock a backend's connection, recovering from a poisoned lock.
+///
+/// A thread that panicked while holding the lock may have left the connection mid-call,
+/// so it is dropped and the next `with_reconnect` connects again instead of every later
+/// call failing for the rest of the session.
+pub fn lock_connection<'a, C>(
+    backend: &'static str,
+    connection: &'a Mutex<Option<C>>,
+) -> MutexGuard<'a, Option<C>> {
+    connection.lock().unwrap_or_else(|poisoned| {
+        warn!("{backend} connection lock was poisoned; reconnecting on next use");
+        connection.clear_poison();
+        let mut guard = poisoned.into_inner();
+        *guard = None;
+        guard
+    })
+}
+
+/// Run `call` over `conn`, connecting first if there is no connection yet.
+///
+/// On failure the connection is dropped and rebuilt with `connect`, waiting
+/// `policy.delay(n)` before reconnect `n`. Failed connects count as attempts too.
+pub fn with_reconnect<C, T, E: Display>(
+    policy: &ReconnectPolicy,
+    backend: &'static str,
+    conn: &mut Option<C>,
+    mut connect: impl FnMut() -> Result<C, E>,
+    mut call: impl FnMut(&mut C) -> Result<T, E>,
+) -> Result<T, Error> {
+    let mut attempt = 0;
+    loop {
+        let result = match conn {
+            Some(existing) => call(existing),
+            None => connect().and_then(|new| call(conn.insert(new))),
+        };
+        let err = match result {
+            Ok(value) => return Ok(value),
+            Err(err) => err,
+        };
+
+        *conn = None;
+        if attempt >= policy.max_attempts {
+            return Err(Error::IpcReconnectFailed {
+                backend,
+                attempts: attempt,
+                reason: err.to_string(),
+            });
+        }
+        attempt += 1;
+        let delay = policy.delay(attempt);
+        error!("{backend} IPC error: {err}; reconnecting in {delay:?} (attempt {attempt})");
+        thread::sleep(delay);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NO_DELAY: ReconnectPolicy = ReconnectPolicy {
+        max_attempts: 2,
+        base_delay: Duration::ZERO,
+        max_delay: Duration::ZERO,
+    };
+
+    #[test]
+    fn poisoned_lock_drops_the_connection() {
+        let connection = Mutex::new(Some(1));
+        let _ = std::panic::catch_unwind(|| {
+            let _guard = connection.lock().unwrap();
+            panic!("backend call panicked");
+        });
+        assert!(connection.is_poisoned());
+
+        assert_eq!(*lock_connection("test", &connection), None);
+        assert!(!connection.is_poisoned());
+    }
+
+    #[test]
+    fn delay_doubles_up_to_the_cap() {
+        let policy = ReconnectPolicy::DEFAULT;
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(200));
+        assert_eq!(policy.delay(5), Duration::from_millis(1600));
+        assert_eq!(policy.delay(6), Duration::from_secs(2));
+        assert_eq!(policy.delay(40), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn recovers_after_a_reconnect() {
+        let mut conn = Some(0);
+        let mut connects = 0;
+        let result = with_reconnect(
+            &NO_DELAY,
+            "test",
+            &mut conn,
+            || {
+                connects += 1;
+                Ok::<_, String>(connects)
+            },
+            |conn: &mut i32| {
+                if *conn == 0 {
+                    Err("stale".to_string())
+                } else {
+                    Ok(*conn)
+                }
+            },
+        );
+        assert_eq!(result.unwrap(), 1);
+        assert_eq!(conn, Some(1));
+    }
+
+    #[test]
+    fn gives_up_after_max_attempts() {
+        let mut conn: Option<()> = None;
+        let mut connects = 0;
+        let result = with_reconnect(
+            &NO_DELAY,
+            "test",
+            &mut conn,
+            || {
+                connects += 1;
+                Err::<(), _>("refused")
+            },
+            |_| Ok(()),
+        );
+        assert!(matches!(
+            result,
+            Err(Error::IpcReconnectFailed { attempts: 2, .. })
+        ));
+        assert_eq!(connects, 3);
+        assert!(conn.is_none());
+    }
+}
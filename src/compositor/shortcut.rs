@@ -1,6 +1,7 @@
 use crate::compositor::CompositorResult;
-use crate::event::shortcuts::{Shortcut as ShortcutAction, ShortcutEvent};
+use crate::event::shortcuts::{Shortcut as ShortcutAction, ShortcutEvent, SystemAction};
 use cosmic_settings_config::shortcuts::Binding;
+use tracing::warn;
 
 /// Compositor shortcut interface. Implement this for each compositor backend.
 pub trait Shortcut {
@@ -12,12 +13,39 @@ pub trait Shortcut {
     }
 
     fn add_shortcut(&self, _shortcut: ShortcutAction, _binding: Binding) -> CompositorResult {
-        eprintln!("add_shortcut not implemented");
+        warn!("add_shortcut not implemented");
         Ok(())
     }
 
     fn remove_shortcut(&self, _shortcut: ShortcutAction, _binding: Binding) -> CompositorResult {
-        eprintln!("remove_shortcut not implemented");
+        warn!("remove_shortcut not implemented");
         Ok(())
     }
 }
+
+/// Command for a COSMIC system action on compositors that have no built-in for it.
+///
+/// Locking the screen and logging out depend on the compositor, so backends map those
+/// themselves; `None` for them and for actions without a command.
+pub fn system_command(action: &SystemAction) -> Option<&'static str> {
+    Some(match action {
+        SystemAction::Launcher => "/usr/bin/cosmic-launcher",
+        SystemAction::AppLibrary => "/usr/bin/cosmic-app-library",
+        SystemAction::Terminal => "/usr/bin/cosmic-term",
+        SystemAction::WebBrowser => "google-chrome",
+        SystemAction::HomeFolder => "xdg-open ~",
+        SystemAction::Screenshot => "cosmic-screenshot",
+        SystemAction::BrightnessDown => "brightnessctl s 5%-",
+        SystemAction::BrightnessUp => "brightnessctl s +5%",
+        SystemAction::VolumeLower => "wpctl set-volume @DEFAULT_AUDIO_SINK@ 5%-",
+        SystemAction::VolumeRaise => "wpctl set-volume -l 1.5 @DEFAULT_AUDIO_SINK@ 5%+",
+        SystemAction::Mute => "wpctl set-mute @DEFAULT_AUDIO_SINK@ toggle",
+        SystemAction::MuteMic => "wpctl set-mute @DEFAULT_AUDIO_SOURCE@ toggle",
+        SystemAction::PlayPause => "playerctl play-pause",
+        SystemAction::PlayNext => "playerctl next",
+        SystemAction::PlayPrev => "playerctl previous",
+        SystemAction::PowerOff => "systemctl poweroff",
+        SystemAction::Suspend => "systemctl suspend",
+        SystemAction::LockScreen | SystemAction::LogOut | SystemAction::Unknown => return None,
+    })
+}
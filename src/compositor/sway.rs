@@ -3,58 +3,188 @@ use std::sync::Mutex;
 
 use swayipc::Connection;
 
+use crate::compositor::color_temp::{ColorTemp, Gammastep};
 use crate::compositor::input::{Input, InputResult};
+use crate::compositor::libinput;
+use crate::compositor::numlock;
+use crate::compositor::reconnect;
 use crate::compositor::{Compositor, CompositorResult};
+use crate::error::Error;
 use crate::event::Event;
+use crate::event::color_temp::ColorTempEvent;
+use crate::event::cursor::CursorEvent;
+use crate::event::theme::ColorScheme;
 use crate::event::input::InputEvent;
 use crate::event::shortcuts::ShortcutEvent;
-use crate::compositor::shortcut::Shortcut;
+use crate::units::scroll;
+use crate::compositor::shortcut::{self, Shortcut};
+use crate::compositor::trace;
+use crate::compositor::theme::{self, Cursor, Theme};
+use crate::compositor::workspace::Workspace;
 
 use cosmic_comp_config::input::{
-    AccelConfig, AccelProfile, ClickMethod, ScrollConfig, ScrollMethod, TapConfig,
+    AccelConfig, AccelProfile, ClickMethod, DeviceState, ScrollConfig, ScrollMethod, TapConfig,
 };
 use cosmic_comp_config::NumlockState;
+use tracing::warn;
+
+/// Events with a real handler in this backend.
+pub const CAPABILITIES: &[&str] = &[
+    "keyboard_rules",
+    "keyboard_model",
+    "keyboard_layout",
+    "keyboard_variant",
+    "keyboard_options",
+    "keyboard_repeat_delay",
+    "keyboard_repeat_rate",
+    "numslock_state",
+    "touchpad_state",
+    "touchpad_acceleration",
+    "touchpad_accel_speed",
+    "touchpad_accel_profile",
+    "touchpad_calibration",
+    "touchpad_click_method",
+    "touchpad_disable_while_typing",
+    "touchpad_left_handed",
+    "touchpad_middle_button_emulation",
+    "touchpad_scroll_config",
+    "touchpad_scroll_method",
+    "touchpad_natural_scroll",
+    "touchpad_scroll_factor",
+    "touchpad_scroll_button",
+    "touchpad_tap_config",
+    "touchpad_tap_enabled",
+    "touchpad_tap_drag",
+    "touchpad_tap_drag_lock",
+    "touchpad_map_to_output",
+    "mouse_state",
+    "mouse_acceleration",
+    "mouse_accel_speed",
+    "mouse_accel_profile",
+    "mouse_calibration",
+    "mouse_click_method",
+    "mouse_disable_while_typing",
+    "mouse_left_handed",
+    "mouse_middle_button_emulation",
+    "mouse_scroll_config",
+    "mouse_scroll_method",
+    "mouse_natural_scroll",
+    "mouse_scroll_factor",
+    "mouse_scroll_button",
+    "mouse_map_to_output",
+    "add_shortcut",
+    "remove_shortcut",
+    "workspace_names",
+    "theme_mode",
+    "cursor_theme",
+    "cursor_size",
+    "color_temp",
+];
+
+/// Sway versions the bundled swayipc speaks the IPC protocol of.
+const EXPECTED_VERSION: &str = "Sway 1.x";
 
 #[derive(Debug, Default)]
 pub struct Sway {
     connection: Mutex<Option<Connection>>,
+    // `(identifier, type)` of the connected inputs. Cleared for every event and on
+    // reconnect, so hotplugged devices are picked up and one event lists them only once.
+    inputs: Mutex<Option<Vec<(String, String)>>>,
+    // Commands of the batch being applied, collected by `apply_events`.
+    batch: Mutex<Option<Vec<String>>>,
+    // `seat xcursor_theme` sets theme and size together, so both are remembered.
+    cursor: Mutex<Cursor>,
+    // Sway has no night light; gammastep holds the temperature while it is on.
+    night_light: Gammastep,
 }
 
 impl Sway {
     pub fn new() -> Self {
-        Self {
-            connection: Mutex::new(None),
+        Self::default()
+    }
+
+    fn clear_inputs(&self) {
+        *self.inputs.lock().unwrap_or_else(|err| err.into_inner()) = None;
+    }
+
+    fn dispatch(&self, event: Event) -> CompositorResult {
+        match event {
+            Event::Input(InputEvent::TouchPad { event, device }) => {
+                self.input(device.as_deref()).apply_touchpad_event(event)
+            }
+            Event::Input(InputEvent::Mouse { event, device }) => {
+                self.input(device.as_deref()).apply_mouse_event(event)
+            }
+            Event::Input(InputEvent::Keyboard(ev)) => self.input(None).apply_keyboard_event(ev),
+            Event::Shortcut(ev) => self.apply_shortcut_event(ev),
+            Event::Workspace(ev) => self.apply_workspace_event(ev),
+            Event::Theme(ev) => self.apply_theme_event(ev),
+            Event::Cursor(ev) => self.apply_cursor_event(ev),
+            Event::ColorTemp(ev) => self.apply_color_temp_event(ev),
         }
     }
 
+    /// The input handlers for an event limited to `device`, or for every device.
+    fn input<'a>(&'a self, device: Option<&'a str>) -> DeviceInput<'a> {
+        DeviceInput { sway: self, device }
+    }
+
+    /// Rewrite an `input type:touchpad|type:pointer ...` command for one device.
+    fn retarget(cmd: String, device: Option<&str>) -> String {
+        let Some(device) = device else {
+            return cmd;
+        };
+        ["input type:touchpad ", "input type:pointer "]
+            .iter()
+            .find_map(|prefix| cmd.strip_prefix(prefix))
+            .map_or_else(|| cmd.clone(), |rest| format!("input \"{device}\" {rest}"))
+    }
+
     fn bool_to_sway(value: bool) -> &'static str {
         if value { "enabled" } else { "disabled" }
     }
 
-    fn map_click_method(method: &ClickMethod) -> &'static str {
-        match method {
-            ClickMethod::ButtonAreas => "button_areas",
-            ClickMethod::Clickfinger => "clickfinger",
-            _ => "none",
-        }
+    fn map_click_method(method: &ClickMethod) -> Result<&'static str, Error> {
+        Ok(match libinput::ClickMethod::try_from(method)? {
+            libinput::ClickMethod::ButtonAreas => "button_areas",
+            libinput::ClickMethod::Clickfinger => "clickfinger",
+        })
     }
 
-    fn map_scroll_method(method: &ScrollMethod) -> &'static str {
-        match method {
-            ScrollMethod::TwoFinger => "two_finger",
-            ScrollMethod::Edge => "edge",
-            ScrollMethod::OnButtonDown => "on_button",
-            ScrollMethod::NoScroll => "none",
-            _ => "none",
-        }
+    fn map_scroll_method(method: &ScrollMethod) -> Result<&'static str, Error> {
+        Ok(match libinput::ScrollMethod::try_from(method)? {
+            libinput::ScrollMethod::TwoFinger => "two_finger",
+            libinput::ScrollMethod::Edge => "edge",
+            libinput::ScrollMethod::OnButtonDown => "on_button",
+            libinput::ScrollMethod::NoScroll => "none",
+        })
     }
 
-    fn map_accel_profile(profile: &AccelProfile) -> &'static str {
-        match profile {
-            AccelProfile::Flat => "flat",
-            AccelProfile::Adaptive => "adaptive",
-            _ => "none",
-        }
+    fn map_accel_profile(profile: &AccelProfile) -> Result<&'static str, Error> {
+        Ok(match libinput::AccelProfile::try_from(profile)? {
+            libinput::AccelProfile::Flat => "flat",
+            libinput::AccelProfile::Adaptive => "adaptive",
+        })
+    }
+
+    /// `None` means COSMIC wants the libinput default. Sway has no keyword to reset
+    /// `accel_profile`, so write libinput's default for pointers and touchpads explicitly;
+    /// otherwise a previously set `flat` would stick.
+    fn accel_profile_value(profile: Option<AccelProfile>) -> Result<&'static str, Error> {
+        profile.as_ref().map_or(Ok("adaptive"), Self::map_accel_profile)
+    }
+
+    /// `events ...` for `state`, or `None` when Sway rejects it for the input type:
+    /// `disabled_on_external_mouse` is only valid on touchpads.
+    fn state_setting(input_type: &str, state: DeviceState) -> Option<&'static str> {
+        Some(match state {
+            DeviceState::Enabled => "events enabled",
+            DeviceState::Disabled => "events disabled",
+            DeviceState::DisabledOnExternalMouse if input_type == "touchpad" => {
+                "events disabled_on_external_mouse"
+            }
+            DeviceState::DisabledOnExternalMouse => return None,
+        })
     }
 
     fn clamp_speed(speed: f64) -> f64 {
@@ -62,49 +192,202 @@ impl Sway {
     }
 
     fn run_command(&self, cmd: String) -> InputResult {
-        let mut guard = self.connection.lock().map_err(|_| {
-            std::io::Error::new(std::io::ErrorKind::Other, "Sway connection lock poisoned")
-        })?;
+        self.run_commands(vec![cmd])
+    }
 
-        if guard.is_none() {
-            *guard = Some(Connection::new()?);
+    /// Run `cmds` as one IPC message, so they are a single round trip and Sway applies
+    /// them together.
+    fn run_commands(&self, mut cmds: Vec<String>) -> InputResult {
+        if cmds.is_empty() {
+            return Ok(());
+        }
+        if let Some(batch) = self.batch.lock().unwrap_or_else(|err| err.into_inner()).as_mut() {
+            batch.append(&mut cmds);
+            return Ok(());
         }
+        let cmd = cmds.join("; ");
+        trace::write("Sway", || cmd.clone(), || self.run_command_untraced(&cmd))
+    }
 
-        let result = guard.as_mut().unwrap().run_command(&cmd);
-        match result {
-            Ok(results) => {
-                for res in results {
-                    if let Err(err) = res {
-                        eprintln!("Sway command error: {err}");
-                    }
+    /// Run `call` over the shared connection, reconnecting as the policy allows.
+    ///
+    /// Replies swayipc cannot parse usually mean a Sway release changed the protocol;
+    /// those fail with `Error::IpcConnection` naming the running version.
+    fn with_connection<T>(
+        &self,
+        mut call: impl FnMut(&mut Connection) -> swayipc::Fallible<T>,
+    ) -> Result<T, Error> {
+        let mut guard = reconnect::lock_connection("Sway", &self.connection);
+        let policy = reconnect::policy();
+        let mut protocol_error = None;
+        let checked_call = |conn: &mut Connection| {
+            let result = call(conn);
+            if let Err(err) = &result {
+                if Self::is_protocol_error(err) {
+                    protocol_error = Some(err.to_string());
                 }
-                Ok(())
-            }
-            Err(err) => {
-                eprintln!("Sway IPC error: {err}. Reconnecting...");
-                *guard = Some(Connection::new()?);
-                let results = guard.as_mut().unwrap().run_command(&cmd)?;
-                for res in results {
-                    if let Err(err) = res {
-                        eprintln!("Sway command error: {err}");
-                    }
-                }
-                Ok(())
             }
+            result
+        };
+        // Inputs may have changed while the connection was down.
+        let connect = || {
+            self.clear_inputs();
+            Connection::new()
+        };
+        let result =
+            reconnect::with_reconnect(&policy, "Sway", &mut *guard, connect, checked_call);
+        drop(guard);
+        match (result, protocol_error) {
+            (Ok(value), _) => Ok(value),
+            (Err(_), Some(reason)) => Err(Error::IpcConnection {
+                backend: "Sway",
+                detected: Self::detected_version(),
+                expected: EXPECTED_VERSION,
+                reason,
+            }),
+            (Err(err), None) => Err(err),
         }
     }
 
-    fn set_bool(&self, target: &str, setting: &str, value: Option<bool>) -> InputResult {
-        if let Some(value) = value {
-            let val = Self::bool_to_sway(value);
-            return self.run_command(format!("input {target} {setting} {val}"));
+    /// Errors from replies swayipc could not understand, as opposed to a lost socket or
+    /// a command Sway rejected.
+    fn is_protocol_error(err: &swayipc::Error) -> bool {
+        matches!(
+            err,
+            swayipc::Error::SerdeJson(_)
+                | swayipc::Error::InvalidMagic(_)
+                | swayipc::Error::UnimplementedEvent(..)
+        )
+    }
+
+    /// The running Sway's version string, or "unknown" when it cannot be read either.
+    fn detected_version() -> String {
+        Connection::new()
+            .and_then(|mut conn| conn.get_version())
+            .map(|version| version.human_readable)
+            .unwrap_or_else(|_| "unknown".to_string())
+    }
+
+    /// Identifiers of the connected inputs of `input_type` ("touchpad", "pointer").
+    fn input_identifiers(&self, input_type: &str) -> Result<Vec<String>, Error> {
+        let cached = self.inputs.lock().unwrap_or_else(|err| err.into_inner()).clone();
+        let inputs = match cached {
+            Some(inputs) => inputs,
+            None => {
+                let inputs: Vec<(String, String)> = self
+                    .with_connection(|conn| conn.get_inputs())?
+                    .into_iter()
+                    .map(|input| (input.identifier, input.input_type))
+                    .collect();
+                *self.inputs.lock().unwrap_or_else(|err| err.into_inner()) = Some(inputs.clone());
+                inputs
+            }
+        };
+        Ok(inputs
+            .into_iter()
+            .filter(|(_, kind)| kind == input_type)
+            .map(|(identifier, _)| identifier)
+            .collect())
+    }
+
+    /// `input <identifier> <setting>` for every device. Settings such as calibration and
+    /// output mapping only make sense per device, but COSMIC only names the device class,
+    /// so each device of the class gets the value.
+    fn per_device_commands(identifiers: &[String], setting: &str) -> Result<Vec<String>, Error> {
+        if identifiers.is_empty() {
+            return Err(Error::RoutingFailed {
+                reason: format!("no input device to apply `{setting}` to"),
+            });
         }
+        Ok(identifiers
+            .iter()
+            .map(|id| format!("input \"{id}\" {setting}"))
+            .collect())
+    }
+
+    fn calibration_setting(cal: Option<[f32; 6]>) -> String {
+        // No matrix means libinput's identity calibration.
+        let matrix = cal.unwrap_or([1.0, 0.0, 0.0, 0.0, 1.0, 0.0]);
+        let values: Vec<String> = matrix.iter().map(f32::to_string).collect();
+        format!("calibration_matrix {}", values.join(" "))
+    }
+
+    fn run_command_untraced(&self, cmd: &str) -> InputResult {
+        let results = self.with_connection(|conn| conn.run_command(cmd))?;
+        Self::check_replies(cmd, results)?;
         Ok(())
     }
 
-    fn set_bool_required(&self, target: &str, setting: &str, value: bool) -> InputResult {
+    /// `Error::IpcCommand` when Sway rejected any of the commands in `cmd`. A rejection
+    /// is not an IPC failure, so it is never retried over a new connection.
+    fn check_replies(cmd: &str, results: Vec<swayipc::Fallible<()>>) -> Result<(), Error> {
+        let failures: Vec<String> = results
+            .into_iter()
+            .filter_map(|result| result.err())
+            .map(|err| err.to_string())
+            .collect();
+        if failures.is_empty() {
+            return Ok(());
+        }
+        Err(Error::IpcCommand {
+            backend: "Sway",
+            command: cmd.to_string(),
+            reason: failures.join("; "),
+        })
+    }
+
+    fn bool_command(target: &str, setting: &str, value: bool) -> String {
         let val = Self::bool_to_sway(value);
-        self.run_command(format!("input {target} {setting} {val}"))
+        format!("input {target} {setting} {val}")
+    }
+
+    fn accel_commands(target: &str, accel: AccelConfig) -> Result<Vec<String>, Error> {
+        let speed = Self::clamp_speed(accel.speed);
+        let profile = Self::accel_profile_value(accel.profile)?;
+        Ok(vec![
+            format!("input {target} pointer_accel {speed}"),
+            format!("input {target} accel_profile {profile}"),
+        ])
+    }
+
+    fn scroll_commands(target: &str, config: ScrollConfig) -> Vec<String> {
+        let mut cmds = Vec::new();
+        if let Some(factor) = config.scroll_factor {
+            let factor = scroll::multiplier(factor);
+            cmds.push(format!("input {target} scroll_factor {factor}"));
+        }
+        if let Some(natural) = config.natural_scroll {
+            cmds.push(Self::bool_command(target, "natural_scroll", natural));
+        }
+        cmds
+    }
+
+    fn tap_commands(target: &str, config: TapConfig) -> Vec<String> {
+        vec![
+            Self::bool_command(target, "tap", config.enabled),
+            Self::bool_command(target, "tap_and_drag", config.drag),
+            Self::bool_command(target, "drag_lock", config.drag_lock),
+        ]
+    }
+
+    /// `value` as one double-quoted command argument, for names COSMIC takes from the
+    /// user. A `;` is refused rather than risk it starting a second command.
+    fn quoted(what: &str, value: &str) -> Result<String, Error> {
+        if value.contains(';') {
+            return Err(format!("{what} {value:?} contains `;`, which Sway cannot take").into());
+        }
+        let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+        Ok(format!("\"{escaped}\""))
+    }
+
+    fn rename_command(id: i32, name: &str) -> Result<String, Error> {
+        let name = Self::quoted("workspace name", &format!("{id}:{name}"))?;
+        Ok(format!("rename workspace number {id} to {name}"))
+    }
+
+    fn cursor_command(theme: &str, size: u32) -> Result<String, Error> {
+        let theme = Self::quoted("cursor theme", theme)?;
+        Ok(format!("seat * xcursor_theme {theme} {size}"))
     }
 
     fn normalize_kb_options(options: &str) -> String {
@@ -154,32 +437,15 @@ impl Sway {
             Shortcut::Move(Direction::Right) => "move right".to_string(),
             Shortcut::Move(Direction::Up) => "move up".to_string(),
             Shortcut::Move(Direction::Down) => "move down".to_string(),
-            Shortcut::Workspace(_) => String::new(), 
+            Shortcut::Workspace(id) => format!("workspace number {}", id),
             Shortcut::MoveToWorkspace(id) => format!("move container to workspace {}", id),
             Shortcut::Terminate => "exec swaymsg exit".to_string(),
             Shortcut::Custom(cmd) => format!("exec {}", cmd),
-            Shortcut::System(sys_action) => match sys_action {
-                SystemAction::Launcher => "exec /usr/bin/cosmic-launcher".to_string(),
-                SystemAction::AppLibrary => "exec /usr/bin/cosmic-app-library".to_string(),
-                SystemAction::Terminal => "exec /usr/bin/cosmic-term".to_string(),
-                SystemAction::WebBrowser => "exec google-chrome".to_string(),
-                SystemAction::HomeFolder => "exec xdg-open ~".to_string(),
-                SystemAction::Screenshot => "exec cosmic-screenshot".to_string(),
-                SystemAction::BrightnessDown => "exec brightnessctl s 5%-".to_string(),
-                SystemAction::BrightnessUp => "exec brightnessctl s +5%".to_string(),
-                SystemAction::VolumeLower => "exec wpctl set-volume @DEFAULT_AUDIO_SINK@ 5%-".to_string(),
-                SystemAction::VolumeRaise => "exec wpctl set-volume -l 1.5 @DEFAULT_AUDIO_SINK@ 5%+".to_string(),
-                SystemAction::Mute => "exec wpctl set-mute @DEFAULT_AUDIO_SINK@ toggle".to_string(),
-                SystemAction::MuteMic => "exec wpctl set-mute @DEFAULT_AUDIO_SOURCE@ toggle".to_string(),
-                SystemAction::PlayPause => "exec playerctl play-pause".to_string(),
-                SystemAction::PlayNext => "exec playerctl next".to_string(),
-                SystemAction::PlayPrev => "exec playerctl previous".to_string(),
-                SystemAction::LockScreen => "exec swaylock".to_string(),
-                SystemAction::LogOut => "exec swaymsg exit".to_string(),
-                SystemAction::PowerOff => "exec systemctl poweroff".to_string(),
-                SystemAction::Suspend => "exec systemctl suspend".to_string(),
-                _ => String::new(),
-            },
+            Shortcut::System(SystemAction::LockScreen) => "exec swaylock".to_string(),
+            Shortcut::System(SystemAction::LogOut) => "exec swaymsg exit".to_string(),
+            Shortcut::System(sys_action) => shortcut::system_command(sys_action)
+                .map(|cmd| format!("exec {}", cmd))
+                .unwrap_or_default(),
             _ => String::new(),
         }
     }
@@ -189,10 +455,16 @@ impl Shortcut for Sway {
     fn add_shortcut(&self, shortcut: crate::event::shortcuts::Shortcut, binding: cosmic_settings_config::shortcuts::Binding) -> CompositorResult {
         let keys = Self::format_binding(&binding);
         let cmd = Self::format_action(&shortcut);
-        if !keys.is_empty() && !cmd.is_empty() {
-            self.run_command(format!("bindsym {} {}", keys, cmd))?;
+        if keys.is_empty() {
+            return Ok(());
         }
-        Ok(())
+        if cmd.is_empty() {
+            return Err(Error::UnsupportedValue {
+                setting: "shortcut",
+                value: format!("{shortcut:?}"),
+            });
+        }
+        self.run_command(format!("bindsym {} {}", keys, cmd))
     }
 
     fn remove_shortcut(&self, _shortcut: crate::event::shortcuts::Shortcut, binding: cosmic_settings_config::shortcuts::Binding) -> CompositorResult {
@@ -206,10 +478,35 @@ impl Shortcut for Sway {
 
 impl Compositor for Sway {
     fn init(&mut self) -> CompositorResult {
-        let mut guard = self.connection.lock().map_err(|_| {
-            std::io::Error::new(std::io::ErrorKind::Other, "Sway connection lock poisoned")
-        })?;
-        *guard = Some(Connection::new()?);
+        // Only SWAYSOCK is required up front. At login the daemon can start before the
+        // socket accepts connections, so a failed connect is deferred to `run_command`,
+        // which connects on first use.
+        if !self.is_running() {
+            return Err(Error::MissingEnvVar { var: "SWAYSOCK" });
+        }
+        match Connection::new() {
+            Ok(mut connection) => {
+                match connection.get_version() {
+                    Ok(version) if version.major != 1 => warn!(
+                        "Sway {} is newer or older than {EXPECTED_VERSION}, which cosmolith \
+                         is built for; settings may fail to apply.",
+                        version.human_readable
+                    ),
+                    Ok(_) => {}
+                    Err(err) if Self::is_protocol_error(&err) => {
+                        return Err(Error::IpcConnection {
+                            backend: "Sway",
+                            detected: "unknown".to_string(),
+                            expected: EXPECTED_VERSION,
+                            reason: err.to_string(),
+                        });
+                    }
+                    Err(err) => warn!("Could not read the Sway version: {err}"),
+                }
+                *reconnect::lock_connection("Sway", &self.connection) = Some(connection);
+            }
+            Err(err) => warn!("Sway IPC not ready yet ({err}); will connect on first use."),
+        }
         Ok(())
     }
 
@@ -221,17 +518,51 @@ impl Compositor for Sway {
         env::var("SWAYSOCK").is_ok()
     }
 
-    fn supports(&self, event: &Event) -> bool {
-        matches!(event, Event::Input(_) | Event::Shortcut(_))
+    fn capabilities(&self) -> &'static [&'static str] {
+        CAPABILITIES
+    }
+
+    // `events disabled_on_external_mouse` on touchpads.
+    fn disables_touchpad_on_external_mouse(&self) -> bool {
+        true
+    }
+
+    // `input` commands only change the running Sway.
+    fn keeps_settings(&self) -> bool {
+        false
     }
 
     fn apply_event(&self, event: Event) -> CompositorResult {
-        match event {
-            Event::Input(InputEvent::TouchPad(ev)) => self.apply_touchpad_event(ev),
-            Event::Input(InputEvent::Mouse(ev)) => self.apply_mouse_event(ev),
-            Event::Input(InputEvent::Keyboard(ev)) => self.apply_keyboard_event(ev),
-            Event::Shortcut(ev) => self.apply_shortcut_event(ev),
+        self.clear_inputs();
+        self.dispatch(event)
+    }
+
+    /// Every command of the batch goes out in one IPC message, and the inputs are listed
+    /// once for the whole batch.
+    fn apply_events(&self, events: Vec<Event>) -> CompositorResult {
+        self.clear_inputs();
+        *self.batch.lock().unwrap_or_else(|err| err.into_inner()) = Some(Vec::new());
+        let mut result = Ok(());
+        for event in events {
+            let applied = self.dispatch(event);
+            if result.is_ok() {
+                result = applied;
+            }
         }
+        let cmds = self
+            .batch
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .take()
+            .unwrap_or_default();
+        // Commands queued before a failing handler still go out, as they would unbatched.
+        let sent = if cmds.is_empty() {
+            Ok(())
+        } else {
+            let cmd = cmds.join("; ");
+            trace::write("Sway", || cmd.clone(), || self.run_command_untraced(&cmd))
+        };
+        result.and(sent)
     }
 
     fn reload(&self) -> CompositorResult {
@@ -245,7 +576,102 @@ impl Compositor for Sway {
 
 // #todo: For all Ok(()) if there exists a if let Some(),
 // define a error variants for such errors and send upwards
-impl Input for Sway {
+// Sway creates and destroys workspaces on demand, so there is no count to set.
+impl Workspace for Sway {
+    fn workspace_names(&self, names: Vec<String>) -> CompositorResult {
+        // Only workspaces that currently exist can be renamed; Sway rejects the others.
+        let existing: Vec<i32> = self
+            .with_connection(|conn| conn.get_workspaces())?
+            .iter()
+            .map(|workspace| workspace.num)
+            .collect();
+        for (id, name) in (1..).zip(names) {
+            if !name.is_empty() && existing.contains(&id) {
+                self.run_command(Self::rename_command(id, &name)?)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ColorTemp for Sway {
+    fn apply_color_temp_event(&self, event: ColorTempEvent) -> CompositorResult {
+        self.night_light.apply("Sway", event)
+    }
+}
+
+// No appearance settings of its own; GTK apps follow the GSettings interface keys.
+impl Theme for Sway {
+    fn theme_mode(&self, scheme: ColorScheme) -> CompositorResult {
+        theme::set_gtk_color_scheme("Sway", scheme, true)
+    }
+
+    fn apply_cursor_event(&self, event: CursorEvent) -> CompositorResult {
+        let (theme, size) = self.cursor.lock().unwrap_or_else(|err| err.into_inner()).update(event);
+        self.run_command(Self::cursor_command(&theme, size)?)
+    }
+}
+
+/// Sway's input handlers for one event. A touchpad or mouse event can name one device;
+/// its `type:` commands are then limited to that device.
+struct DeviceInput<'a> {
+    sway: &'a Sway,
+    device: Option<&'a str>,
+}
+
+impl DeviceInput<'_> {
+    fn run_command(&self, cmd: String) -> InputResult {
+        self.run_commands(vec![cmd])
+    }
+
+    fn run_commands(&self, cmds: Vec<String>) -> InputResult {
+        let cmds = cmds
+            .into_iter()
+            .map(|cmd| Sway::retarget(cmd, self.device))
+            .collect();
+        self.sway.run_commands(cmds)
+    }
+
+    /// Enable or disable every device of `input_type`. Sends per-device commands: a
+    /// `type:` selector would also be stored as the default for devices plugged in later.
+    fn set_state(&self, input_type: &str, state: DeviceState) -> InputResult {
+        match Sway::state_setting(input_type, state) {
+            // Without such a device there is nothing to enable or disable.
+            Some(_)
+                if self.device.is_none() && self.sway.input_identifiers(input_type)?.is_empty() =>
+            {
+                Ok(())
+            }
+            Some(setting) => self.set_per_device(input_type, setting),
+            // Disabling the mouse instead could leave the user without a pointer.
+            None => Err(Error::NotImplemented {
+                handler: "mouse_state",
+            }),
+        }
+    }
+
+    fn set_per_device(&self, input_type: &str, setting: &str) -> InputResult {
+        let identifiers = match self.device {
+            Some(device) => vec![device.to_string()],
+            None => self.sway.input_identifiers(input_type)?,
+        };
+        self.run_commands(Sway::per_device_commands(&identifiers, setting)?)
+    }
+
+    fn set_bool(&self, target: &str, setting: &str, value: Option<bool>) -> InputResult {
+        if let Some(value) = value {
+            let val = Sway::bool_to_sway(value);
+            return self.run_command(format!("input {target} {setting} {val}"));
+        }
+        Ok(())
+    }
+
+    fn set_bool_required(&self, target: &str, setting: &str, value: bool) -> InputResult {
+        self.run_command(Sway::bool_command(target, setting, value))
+    }
+}
+
+impl Input for DeviceInput<'_> {
     fn keyboard_rules(&self, rules: String) -> InputResult {
         self.run_command(format!("input type:keyboard xkb_rules {rules}"))
     }
@@ -264,7 +690,7 @@ impl Input for Sway {
 
     fn keyboard_options(&self, options: Option<String>) -> InputResult {
         if let Some(options) = options {
-            let cleaned = Self::normalize_kb_options(&options);
+            let cleaned = Sway::normalize_kb_options(&options);
             return self.run_command(format!("input type:keyboard xkb_options {cleaned}"));
         }
         Ok(())
@@ -279,40 +705,41 @@ impl Input for Sway {
     }
 
     fn numslock_state(&self, state: NumlockState) -> InputResult {
-        match state {
-            NumlockState::BootOn => self.run_command("input type:keyboard xkb_numlock enabled".to_string()),
-            NumlockState::BootOff => self.run_command("input type:keyboard xkb_numlock disabled".to_string()),
-            NumlockState::LastBoot => Ok(()), // Don't change
+        match numlock::target(state) {
+            Some(true) => self.run_command("input type:keyboard xkb_numlock enabled".to_string()),
+            Some(false) => self.run_command("input type:keyboard xkb_numlock disabled".to_string()),
+            None => Ok(()),
         }
     }
 
-    // fn touchpad_state(&self, _state: DeviceState) -> InputResult {
-    //     // TODO: Requires device-specific identifiers; DisabledOnExternalMouse not supported.
-    //     dbg!("Sway: touchpad enable/disable not supported via type:touchpad");
-    //     Ok(())
-    // }
+    fn touchpad_state(&self, state: DeviceState) -> InputResult {
+        self.set_state("touchpad", state)
+    }
 
     fn touchpad_acceleration(&self, accel: Option<AccelConfig>) -> InputResult {
         if let Some(accel) = accel {
-            let speed = Self::clamp_speed(accel.speed);
-            self.run_command(format!("input type:touchpad pointer_accel {speed}"))?;
-            if let Some(profile) = accel.profile {
-                let value = Self::map_accel_profile(&profile);
-                self.run_command(format!("input type:touchpad accel_profile {value}"))?;
-            }
+            return self.run_commands(Sway::accel_commands("type:touchpad", accel)?);
         }
         Ok(())
     }
 
-    // fn touchpad_calibration(&self, _cal: Option<[f32; 6]>) -> InputResult {
-    //     // TODO: No calibration support in Sway IPC.
-    //     dbg!("Sway: touchpad calibration not supported");
-    //     Ok(())
-    // }
+    fn touchpad_accel_speed(&self, speed: f64) -> InputResult {
+        let speed = Sway::clamp_speed(speed);
+        self.run_command(format!("input type:touchpad pointer_accel {speed}"))
+    }
+
+    fn touchpad_accel_profile(&self, profile: Option<AccelProfile>) -> InputResult {
+        let value = Sway::accel_profile_value(profile)?;
+        self.run_command(format!("input type:touchpad accel_profile {value}"))
+    }
+
+    fn touchpad_calibration(&self, cal: Option<[f32; 6]>) -> InputResult {
+        self.set_per_device("touchpad", &Sway::calibration_setting(cal))
+    }
 
     fn touchpad_click_method(&self, method: Option<ClickMethod>) -> InputResult {
         if let Some(method) = method {
-            let value = Self::map_click_method(&method);
+            let value = Sway::map_click_method(&method)?;
             return self.run_command(format!("input type:touchpad click_method {value}"));
         }
         Ok(())
@@ -338,20 +765,14 @@ impl Input for Sway {
 
     fn touchpad_scroll_config(&self, config: Option<ScrollConfig>) -> InputResult {
         if let Some(config) = config {
-            if let Some(factor) = config.scroll_factor {
-                self.run_command(format!("input type:touchpad scroll_factor {factor}"))?;
-            }
-            if let Some(natural) = config.natural_scroll {
-                let value = Self::bool_to_sway(natural);
-                self.run_command(format!("input type:touchpad natural_scroll {value}"))?;
-            }
+            return self.run_commands(Sway::scroll_commands("type:touchpad", config));
         }
         Ok(())
     }
 
     fn touchpad_scroll_method(&self, method: Option<ScrollMethod>) -> InputResult {
         if let Some(method) = method {
-            let value = Self::map_scroll_method(&method);
+            let value = Sway::map_scroll_method(&method)?;
             return self.run_command(format!("input type:touchpad scroll_method {value}"));
         }
         Ok(())
@@ -363,6 +784,7 @@ impl Input for Sway {
 
     fn touchpad_scroll_factor(&self, factor: Option<f64>) -> InputResult {
         if let Some(factor) = factor {
+            let factor = scroll::multiplier(factor);
             return self.run_command(format!("input type:touchpad scroll_factor {factor}"));
         }
         Ok(())
@@ -377,9 +799,7 @@ impl Input for Sway {
 
     fn touchpad_tap_config(&self, config: Option<TapConfig>) -> InputResult {
         if let Some(config) = config {
-            self.set_bool_required("type:touchpad", "tap", config.enabled)?;
-            self.set_bool_required("type:touchpad", "tap_and_drag", config.drag)?;
-            self.set_bool_required("type:touchpad", "drag_lock", config.drag_lock)?;
+            return self.run_commands(Sway::tap_commands("type:touchpad", config));
         }
         Ok(())
     }
@@ -402,39 +822,42 @@ impl Input for Sway {
         self.set_bool_required("type:touchpad", "drag_lock", enabled)
     }
 
-    // fn touchpad_map_to_output(&self, _output: Option<String>) -> InputResult {
-    //     // TODO: Requires device-specific identifiers; not supported via type:touchpad.
-    //     dbg!("Sway: touchpad map_to_output not supported");
-    //     Ok(())
-    // }
+    fn touchpad_map_to_output(&self, output: Option<String>) -> InputResult {
+        // Sway has no command to undo a mapping; clearing it takes a config reload.
+        match output {
+            Some(output) => self.set_per_device("touchpad", &format!("map_to_output {output}")),
+            None => Ok(()),
+        }
+    }
 
-    // fn mouse_state(&self, _state: DeviceState) -> InputResult {
-    //     // TODO: Requires device-specific identifiers; DisabledOnExternalMouse not supported.
-    //     dbg!("Sway: mouse enable/disable not supported via type:pointer");
-    //     Ok(())
-    // }
+    fn mouse_state(&self, state: DeviceState) -> InputResult {
+        self.set_state("pointer", state)
+    }
 
     fn mouse_acceleration(&self, accel: Option<AccelConfig>) -> InputResult {
         if let Some(accel) = accel {
-            let speed = Self::clamp_speed(accel.speed);
-            self.run_command(format!("input type:pointer pointer_accel {speed}"))?;
-            if let Some(profile) = accel.profile {
-                let value = Self::map_accel_profile(&profile);
-                self.run_command(format!("input type:pointer accel_profile {value}"))?;
-            }
+            return self.run_commands(Sway::accel_commands("type:pointer", accel)?);
         }
         Ok(())
     }
 
-    // fn mouse_calibration(&self, _cal: Option<[f32; 6]>) -> InputResult {
-    //     // TODO: No calibration support in Sway IPC.
-    //     dbg!("Sway: mouse calibration not supported");
-    //     Ok(())
-    // }
+    fn mouse_accel_speed(&self, speed: f64) -> InputResult {
+        let speed = Sway::clamp_speed(speed);
+        self.run_command(format!("input type:pointer pointer_accel {speed}"))
+    }
+
+    fn mouse_accel_profile(&self, profile: Option<AccelProfile>) -> InputResult {
+        let value = Sway::accel_profile_value(profile)?;
+        self.run_command(format!("input type:pointer accel_profile {value}"))
+    }
+
+    fn mouse_calibration(&self, cal: Option<[f32; 6]>) -> InputResult {
+        self.set_per_device("pointer", &Sway::calibration_setting(cal))
+    }
 
     fn mouse_click_method(&self, method: Option<ClickMethod>) -> InputResult {
         if let Some(method) = method {
-            let value = Self::map_click_method(&method);
+            let value = Sway::map_click_method(&method)?;
             return self.run_command(format!("input type:pointer click_method {value}"));
         }
         Ok(())
@@ -460,20 +883,14 @@ impl Input for Sway {
 
     fn mouse_scroll_config(&self, config: Option<ScrollConfig>) -> InputResult {
         if let Some(config) = config {
-            if let Some(factor) = config.scroll_factor {
-                self.run_command(format!("input type:pointer scroll_factor {factor}"))?;
-            }
-            if let Some(natural) = config.natural_scroll {
-                let value = Self::bool_to_sway(natural);
-                self.run_command(format!("input type:pointer natural_scroll {value}"))?;
-            }
+            return self.run_commands(Sway::scroll_commands("type:pointer", config));
         }
         Ok(())
     }
 
     fn mouse_scroll_method(&self, method: Option<ScrollMethod>) -> InputResult {
         if let Some(method) = method {
-            let value = Self::map_scroll_method(&method);
+            let value = Sway::map_scroll_method(&method)?;
             return self.run_command(format!("input type:pointer scroll_method {value}"));
         }
         Ok(())
@@ -485,6 +902,7 @@ impl Input for Sway {
 
     fn mouse_scroll_factor(&self, factor: Option<f64>) -> InputResult {
         if let Some(factor) = factor {
+            let factor = scroll::multiplier(factor);
             return self.run_command(format!("input type:pointer scroll_factor {factor}"));
         }
         Ok(())
@@ -503,9 +921,122 @@ impl Input for Sway {
     //     Ok(())
     // }
 
-    // fn mouse_map_to_output(&self, _output: Option<String>) -> InputResult {
-    //     // TODO: Requires device-specific identifiers; not supported via type:pointer.
-    //     dbg!("Sway: mouse map_to_output not supported");
-    //     Ok(())
-    // }
+    fn mouse_map_to_output(&self, output: Option<String>) -> InputResult {
+        match output {
+            Some(output) => self.set_per_device("pointer", &format!("map_to_output {output}")),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmic_comp_config::input::{AccelProfile, DeviceState, TapConfig};
+
+    use super::Sway;
+    use crate::error::Error;
+
+    #[test]
+    fn cleared_accel_profile_is_adaptive() {
+        assert_eq!(Sway::accel_profile_value(None).ok(), Some("adaptive"));
+        assert_eq!(Sway::accel_profile_value(Some(AccelProfile::Flat)).ok(), Some("flat"));
+    }
+
+    #[test]
+    fn disabled_on_external_mouse_is_touchpad_only() {
+        assert_eq!(
+            Sway::state_setting("touchpad", DeviceState::DisabledOnExternalMouse),
+            Some("events disabled_on_external_mouse")
+        );
+        assert_eq!(Sway::state_setting("pointer", DeviceState::DisabledOnExternalMouse), None);
+
+        let ids = ["1133:16495:Logitech_MX_Master".to_string()];
+        let setting = Sway::state_setting("pointer", DeviceState::Disabled).unwrap();
+        assert_eq!(
+            Sway::per_device_commands(&ids, setting).unwrap(),
+            ["input \"1133:16495:Logitech_MX_Master\" events disabled"]
+        );
+    }
+
+    #[test]
+    fn per_device_settings_need_a_device() {
+        let setting = Sway::calibration_setting(None);
+        assert_eq!(setting, "calibration_matrix 1 0 0 0 1 0");
+        assert!(matches!(
+            Sway::per_device_commands(&[], &setting),
+            Err(Error::RoutingFailed { .. })
+        ));
+
+        let ids = ["1267:12377:ELAN1300:00_04F3:3057_Touchpad".to_string()];
+        assert_eq!(
+            Sway::per_device_commands(&ids, "map_to_output eDP-1").unwrap(),
+            ["input \"1267:12377:ELAN1300:00_04F3:3057_Touchpad\" map_to_output eDP-1"]
+        );
+    }
+    #[test]
+    fn unparseable_replies_are_protocol_errors() {
+        assert!(Sway::is_protocol_error(&swayipc::Error::InvalidMagic(*b"i3-ipx")));
+        assert!(!Sway::is_protocol_error(&swayipc::Error::CommandFailed(
+            "Unknown/invalid command".to_string()
+        )));
+    }
+
+    #[test]
+    fn tap_config_is_one_message() {
+        let config = TapConfig {
+            enabled: true,
+            button_map: None,
+            drag: false,
+            drag_lock: true,
+        };
+        let cmds = Sway::tap_commands("type:touchpad", config);
+        assert_eq!(
+            cmds.join("; "),
+            "input type:touchpad tap enabled; input type:touchpad tap_and_drag disabled; \
+             input type:touchpad drag_lock enabled"
+        );
+    }
+
+    #[test]
+    fn device_events_replace_the_type_selector() {
+        let cmd = "input type:touchpad tap enabled".to_string();
+        assert_eq!(Sway::retarget(cmd.clone(), None), cmd);
+        assert_eq!(
+            Sway::retarget(cmd, Some("2:7:SynPS/2_Synaptics_TouchPad")),
+            "input \"2:7:SynPS/2_Synaptics_TouchPad\" tap enabled"
+        );
+        assert_eq!(
+            Sway::retarget("input type:keyboard repeat_rate 30".to_string(), Some("mouse")),
+            "input type:keyboard repeat_rate 30"
+        );
+    }
+
+    #[test]
+    fn rejected_commands_are_reported() {
+        let replies = vec![
+            Ok(()),
+            Err(swayipc::Error::CommandFailed("Unknown/invalid command 'nope'".to_string())),
+        ];
+        let err = Sway::check_replies("kill; nope", replies).unwrap_err();
+        assert!(matches!(err, Error::IpcCommand { ref reason, .. } if reason.contains("nope")));
+        assert!(Sway::check_replies("kill", vec![Ok(())]).is_ok());
+    }
+
+    #[test]
+    fn workspace_names_cannot_inject_commands() {
+        assert_eq!(
+            Sway::rename_command(2, r#"web "dev" \ 2"#).unwrap(),
+            r#"rename workspace number 2 to "2:web \"dev\" \\ 2""#
+        );
+        assert!(Sway::rename_command(1, "x\"; exec rm -rf ~; \"").is_err());
+    }
+
+    #[test]
+    fn cursor_themes_are_quoted() {
+        assert_eq!(
+            Sway::cursor_command("Bibata Modern Ice", 24).unwrap(),
+            r#"seat * xcursor_theme "Bibata Modern Ice" 24"#
+        );
+        assert!(Sway::cursor_command("default; exit", 24).is_err());
+    }
 }
@@ -0,0 +1,184 @@
+use std::env;
+
+use gio::prelude::*;
+use gio::{Settings, SettingsSchemaSource};
+
+use crate::compositor::CompositorResult;
+use crate::compositor::trace;
+use crate::error::Error;
+use crate::event::cursor::CursorEvent;
+use crate::event::theme::{ColorScheme, ThemeEvent};
+
+/// Compositor appearance interface: light/dark mode and the cursor. Like `Workspace`, the
+/// defaults return `Error::NotImplemented` so an unsynced setting is reported instead of
+/// ignored.
+pub trait Theme {
+    fn apply_theme_event(&self, event: ThemeEvent) -> CompositorResult {
+        match event {
+            ThemeEvent::Mode(scheme) => self.theme_mode(scheme),
+        }
+    }
+
+    fn theme_mode(&self, _scheme: ColorScheme) -> CompositorResult {
+        Err(Error::NotImplemented {
+            handler: "theme_mode",
+        })
+    }
+
+    fn apply_cursor_event(&self, event: CursorEvent) -> CompositorResult {
+        match event {
+            CursorEvent::Theme(theme) => self.cursor_theme(theme),
+            CursorEvent::Size(size) => self.cursor_size(size),
+        }
+    }
+
+    fn cursor_theme(&self, _theme: String) -> CompositorResult {
+        Err(Error::NotImplemented {
+            handler: "cursor_theme",
+        })
+    }
+
+    fn cursor_size(&self, _size: u32) -> CompositorResult {
+        Err(Error::NotImplemented {
+            handler: "cursor_size",
+        })
+    }
+}
+
+/// Size used until COSMIC sends one; the XCursor default.
+pub const DEFAULT_CURSOR_SIZE: u32 = 24;
+
+/// Last cursor theme and size, for backends whose command sets both at once
+/// (`hyprctl setcursor`, Sway's `seat xcursor_theme`).
+#[derive(Debug, Default)]
+pub struct Cursor {
+    theme: Option<String>,
+    size: Option<u32>,
+}
+
+impl Cursor {
+    /// Remember `event` and return the theme and size to apply. Until COSMIC has sent a
+    /// theme, `$XCURSOR_THEME` (or the `default` theme) is kept.
+    pub fn update(&mut self, event: CursorEvent) -> (String, u32) {
+        match event {
+            CursorEvent::Theme(theme) => self.theme = Some(theme),
+            CursorEvent::Size(size) => self.size = Some(size),
+        }
+        let theme = self
+            .theme
+            .clone()
+            .or_else(|| env::var("XCURSOR_THEME").ok())
+            .unwrap_or_else(|| "default".to_string());
+        (theme, self.size.unwrap_or(DEFAULT_CURSOR_SIZE))
+    }
+}
+
+const INTERFACE_SCHEMA: &str = "org.gnome.desktop.interface";
+
+/// Set `color-scheme` in `org.gnome.desktop.interface`, which libadwaita and GTK 4 apps
+/// follow through the settings portal. With `gtk_theme`, also switch `gtk-theme` to the
+/// matching `-dark` variant for GTK 3 apps, which only read the theme name.
+///
+/// Desktops without their own appearance settings (the wlroots compositors) use this;
+/// it needs the GSettings schemas, which most GTK installs ship.
+pub fn set_gtk_color_scheme(
+    backend: &'static str,
+    scheme: ColorScheme,
+    gtk_theme: bool,
+) -> CompositorResult {
+    let settings = gsettings(INTERFACE_SCHEMA)?;
+    let value = color_scheme(scheme);
+    trace::write(
+        backend,
+        || format!("color-scheme={value:?}"),
+        || settings.set_string("color-scheme", value),
+    )?;
+    if gtk_theme {
+        let current = settings.string("gtk-theme");
+        let theme = gtk_theme_variant(&current, scheme);
+        if theme != current.as_str() {
+            trace::write(
+                backend,
+                || format!("gtk-theme={theme:?}"),
+                || settings.set_string("gtk-theme", &theme),
+            )?;
+        }
+    }
+    Settings::sync();
+    Ok(())
+}
+
+/// Set `cursor-theme` or `cursor-size` in `org.gnome.desktop.interface`.
+pub fn set_gtk_cursor(backend: &'static str, event: CursorEvent) -> CompositorResult {
+    let settings = gsettings(INTERFACE_SCHEMA)?;
+    match event {
+        CursorEvent::Theme(theme) => trace::write(
+            backend,
+            || format!("cursor-theme={theme:?}"),
+            || settings.set_string("cursor-theme", &theme),
+        )?,
+        CursorEvent::Size(size) => {
+            let size = i32::try_from(size).map_err(|err| format!("cursor size {size}: {err}"))?;
+            trace::write(
+                backend,
+                || format!("cursor-size={size}"),
+                || settings.set_int("cursor-size", size),
+            )?
+        }
+    }
+    Settings::sync();
+    Ok(())
+}
+
+/// `Settings` for `schema`, or an error when it is not installed.
+pub fn gsettings(schema: &str) -> Result<Settings, Error> {
+    let source = SettingsSchemaSource::default()
+        .ok_or_else(|| Error::DetectionFailed("no GSettings schemas are installed".into()))?;
+    // `Settings::new` aborts the process on a missing schema.
+    if source.lookup(schema, true).is_none() {
+        let reason = format!("GSettings schema {schema} is not installed");
+        return Err(Error::DetectionFailed(reason));
+    }
+    Ok(Settings::new(schema))
+}
+
+/// `org.gnome.desktop.interface color-scheme` value for `scheme`.
+pub fn color_scheme(scheme: ColorScheme) -> &'static str {
+    match scheme {
+        ColorScheme::Dark => "prefer-dark",
+        ColorScheme::Light => "default",
+    }
+}
+
+/// `current` with its `-dark` suffix added or removed, so a custom theme keeps its name.
+fn gtk_theme_variant(current: &str, scheme: ColorScheme) -> String {
+    let light = current.strip_suffix("-dark").unwrap_or(current);
+    let light = if light.is_empty() { "Adwaita" } else { light };
+    match scheme {
+        ColorScheme::Dark => format!("{light}-dark"),
+        ColorScheme::Light => light.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gtk_theme_keeps_its_name_across_modes() {
+        assert_eq!(gtk_theme_variant("Adwaita", ColorScheme::Dark), "Adwaita-dark");
+        assert_eq!(gtk_theme_variant("Arc-dark", ColorScheme::Light), "Arc");
+        assert_eq!(gtk_theme_variant("Arc-dark", ColorScheme::Dark), "Arc-dark");
+        assert_eq!(gtk_theme_variant("", ColorScheme::Light), "Adwaita");
+    }
+
+    #[test]
+    fn cursor_keeps_the_last_theme_and_size() {
+        let mut cursor = Cursor::default();
+        let (_, size) = cursor.update(CursorEvent::Theme("Pop".to_string()));
+        assert_eq!(size, DEFAULT_CURSOR_SIZE);
+        assert_eq!(cursor.update(CursorEvent::Size(32)), ("Pop".to_string(), 32));
+        let adwaita = CursorEvent::Theme("Adwaita".to_string());
+        assert_eq!(cursor.update(adwaita), ("Adwaita".to_string(), 32));
+    }
+}
@@ -0,0 +1,86 @@
+//! Optional timing of every backend IPC call, enabled with `--trace-ipc`, and the
+//! `--dry-run` switch that prints writes instead of making them.
+//!
+//! Each backend funnels its writes through one helper (`set_keyword`, `run_command`,
+//! `run_kde_cmd`, ...), and that helper wraps the call in [`write`] (or [`timed`] for
+//! reads). When both are off the only cost is a relaxed atomic load or two.
+//!
+//! Whatever the flags, every event sent to a backend goes through [`dispatch`], which
+//! logs its outcome with `tracing` inside a span naming the backend and the event.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+use tracing::{error, info, info_span, warn};
+
+use crate::compositor::CompositorResult;
+use crate::error::Error;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn set_dry_run(dry_run: bool) {
+    DRY_RUN.store(dry_run, Ordering::Relaxed);
+}
+
+/// Whether writes are printed instead of made. Reads still reach the compositor, so
+/// per-device commands name the real devices.
+pub fn dry_run() -> bool {
+    DRY_RUN.load(Ordering::Relaxed)
+}
+
+/// Print a write that `--dry-run` skipped.
+pub fn print_skipped(backend: &str, command: &str) {
+    println!("[dry-run] {backend}: {command}");
+}
+
+/// [`timed`] for calls that change settings. Under `--dry-run` the command is printed
+/// and `call` is skipped as if it had succeeded with `T::default()`.
+pub fn write<T: Default, E>(
+    backend: &str,
+    command: impl FnOnce() -> String,
+    call: impl FnOnce() -> Result<T, E>,
+) -> Result<T, E> {
+    if dry_run() {
+        print_skipped(backend, &command());
+        return Ok(T::default());
+    }
+    timed(backend, command, call)
+}
+
+/// Run `call`, logging the backend, the command and how long it took when tracing is on.
+pub fn timed<T>(backend: &str, command: impl FnOnce() -> String, call: impl FnOnce() -> T) -> T {
+    if !enabled() {
+        return call();
+    }
+    let start = Instant::now();
+    let result = call();
+    info!(backend, took = ?start.elapsed(), "{}", command());
+    result
+}
+
+/// Run `apply` in a `dispatch` span carrying `compositor` and `event` (an `Event::name`,
+/// or several joined with commas for a batch), and log the result: `info!` when
+/// applied, `warn!` when the backend does not implement it and `error!` otherwise.
+pub fn dispatch(
+    compositor: &str,
+    event: &str,
+    apply: impl FnOnce() -> CompositorResult,
+) -> CompositorResult {
+    let _span = info_span!("dispatch", compositor, event).entered();
+    let result = apply();
+    match &result {
+        Ok(()) => info!("applied"),
+        Err(err @ Error::NotImplemented { .. }) => warn!("{err}"),
+        Err(err) => error!("{err}"),
+    }
+    result
+}
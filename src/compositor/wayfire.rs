@@ -0,0 +1,405 @@
+use std::env;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+
+use crate::compositor::config_file::{atomic_write, read_or, set_ini_key};
+use crate::compositor::color_temp::ColorTemp;
+use crate::compositor::input::{Input, InputResult};
+use crate::compositor::libinput;
+use crate::compositor::numlock;
+use crate::compositor::trace;
+use crate::compositor::theme::{self, Theme};
+use crate::compositor::workspace::Workspace;
+use crate::compositor::{Compositor, CompositorResult};
+use crate::error::Error;
+use crate::event::Event;
+use crate::event::theme::ColorScheme;
+use crate::event::input::InputEvent;
+
+use cosmic_comp_config::input::{AccelConfig, AccelProfile, ClickMethod, ScrollMethod, TapConfig};
+use cosmic_comp_config::NumlockState;
+use tracing::warn;
+
+/// Events with a real handler in this backend.
+pub const CAPABILITIES: &[&str] = &[
+    "keyboard_rules",
+    "keyboard_model",
+    "keyboard_layout",
+    "keyboard_variant",
+    "keyboard_options",
+    "keyboard_repeat_delay",
+    "keyboard_repeat_rate",
+    "numslock_state",
+    "touchpad_acceleration",
+    "touchpad_accel_speed",
+    "touchpad_accel_profile",
+    "touchpad_click_method",
+    "touchpad_disable_while_typing",
+    "touchpad_scroll_method",
+    "touchpad_natural_scroll",
+    "touchpad_tap_config",
+    "touchpad_tap_enabled",
+    "touchpad_tap_drag_lock",
+    "mouse_acceleration",
+    "mouse_accel_speed",
+    "mouse_accel_profile",
+    "mouse_left_handed",
+    "mouse_middle_button_emulation",
+    "theme_mode",
+];
+
+const SECTION: &str = "input";
+
+/// Wayfire is configured through the `[input]` section of `wayfire.ini`. Every write
+/// patches the file, which keeps the setting across restarts, and then sets the same
+/// option over IPC so it applies without waiting for Wayfire to notice the file change.
+pub struct Wayfire {
+    config_path: PathBuf,
+}
+
+impl Wayfire {
+    pub fn new() -> Self {
+        // Wayfire reads $WAYFIRE_CONFIG_FILE when set, like `wayfire -c`.
+        let config_path = env::var_os("WAYFIRE_CONFIG_FILE")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| dirs::config_dir().unwrap_or_default().join("wayfire.ini"));
+        Self { config_path }
+    }
+
+    fn map_accel_profile(profile: &AccelProfile) -> Result<&'static str, Error> {
+        Ok(match libinput::AccelProfile::try_from(profile)? {
+            libinput::AccelProfile::Flat => "flat",
+            libinput::AccelProfile::Adaptive => "adaptive",
+        })
+    }
+
+    fn map_click_method(method: &ClickMethod) -> Result<&'static str, Error> {
+        Ok(match libinput::ClickMethod::try_from(method)? {
+            libinput::ClickMethod::ButtonAreas => "button-areas",
+            libinput::ClickMethod::Clickfinger => "clickfinger",
+        })
+    }
+
+    fn map_scroll_method(method: &ScrollMethod) -> Result<&'static str, Error> {
+        Ok(match libinput::ScrollMethod::try_from(method)? {
+            libinput::ScrollMethod::TwoFinger => "two-finger",
+            libinput::ScrollMethod::Edge => "edge",
+            libinput::ScrollMethod::OnButtonDown => "on-button-down",
+            libinput::ScrollMethod::NoScroll => "none",
+        })
+    }
+
+    /// Set `option` in `[input]` of wayfire.ini, then in the running Wayfire.
+    fn set(&self, option: &str, value: impl ToString) -> InputResult {
+        let value = value.to_string();
+        let path = &self.config_path;
+        trace::write("Wayfire", || format!("wayfire.ini [{SECTION}] {option}={value}"), || {
+            let contents = read_or(path, "")?;
+            atomic_write(path, &set_ini_key(&contents, SECTION, option, &value))
+        })?;
+        self.set_running(option, &value)
+    }
+
+    fn set_opt_bool(&self, option: &str, value: Option<bool>) -> InputResult {
+        if let Some(value) = value {
+            return self.set(option, value);
+        }
+        Ok(())
+    }
+
+    /// Set `input/<option>` through the IPC plugin. Without `$WAYFIRE_SOCKET` (the `ipc`
+    /// plugin is not loaded) Wayfire still picks the change up from wayfire.ini.
+    fn set_running(&self, option: &str, value: &str) -> InputResult {
+        let Some(socket) = env::var_os("WAYFIRE_SOCKET") else {
+            return Ok(());
+        };
+        let request = format!(
+            "{{\"method\":\"wayfire/set-config-options\",\"data\":{{\"{}\":\"{}\"}}}}",
+            json_escape(&format!("{SECTION}/{option}")),
+            json_escape(value)
+        );
+        let reply = trace::write("Wayfire", || request.clone(), || {
+            let mut stream = UnixStream::connect(&socket)?;
+            ipc_round_trip(&mut stream, &request)
+        })?;
+        if reply.contains("\"error\"") {
+            return Err(Error::IpcCommand {
+                backend: "Wayfire",
+                command: format!("{SECTION}/{option}={value}"),
+                reason: reply,
+            });
+        }
+        Ok(())
+    }
+
+    fn acceleration(&self, prefix: &str, accel: Option<AccelConfig>) -> InputResult {
+        if let Some(accel) = accel {
+            self.set(&format!("{prefix}_cursor_speed"), accel.speed.clamp(-1.0, 1.0))?;
+            if let Some(profile) = accel.profile {
+                let value = Self::map_accel_profile(&profile)?;
+                self.set(&format!("{prefix}_accel_profile"), value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Send one message in Wayfire's IPC framing (a little-endian `u32` length followed by
+/// JSON) and read the reply.
+fn ipc_round_trip(stream: &mut UnixStream, request: &str) -> std::io::Result<String> {
+    stream.write_all(&(request.len() as u32).to_le_bytes())?;
+    stream.write_all(request.as_bytes())?;
+
+    let mut len = [0; 4];
+    stream.read_exact(&mut len)?;
+    let mut reply = vec![0; u32::from_le_bytes(len) as usize];
+    stream.read_exact(&mut reply)?;
+    Ok(String::from_utf8_lossy(&reply).into_owned())
+}
+
+fn json_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl Compositor for Wayfire {
+    fn init(&mut self) -> CompositorResult {
+        if self.config_path.as_os_str().is_empty() {
+            return Err("Could not resolve the Wayfire config file".into());
+        }
+        if env::var_os("WAYFIRE_SOCKET").is_none() {
+            warn!(
+                "WAYFIRE_SOCKET is not set (is the ipc plugin loaded?); settings will only be \
+                 written to {}",
+                self.config_path.display()
+            );
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "Wayfire"
+    }
+
+    fn is_running(&self) -> bool {
+        env::var("WAYFIRE_SOCKET").is_ok()
+    }
+
+    fn capabilities(&self) -> &'static [&'static str] {
+        CAPABILITIES
+    }
+
+    fn apply_event(&self, event: Event) -> CompositorResult {
+        match event {
+            Event::Input(InputEvent::TouchPad { event: ev, .. }) => self.apply_touchpad_event(ev),
+            Event::Input(InputEvent::Mouse { event: ev, .. }) => self.apply_mouse_event(ev),
+            Event::Input(InputEvent::Keyboard(ev)) => self.apply_keyboard_event(ev),
+            Event::Shortcut(_) => Ok(()),
+            Event::Workspace(ev) => self.apply_workspace_event(ev),
+            Event::Theme(ev) => self.apply_theme_event(ev),
+            Event::Cursor(ev) => self.apply_cursor_event(ev),
+            Event::ColorTemp(ev) => self.apply_color_temp_event(ev),
+        }
+    }
+
+    fn reload(&self) -> CompositorResult {
+        // Every option is set over IPC as it is written, and Wayfire reloads wayfire.ini
+        // by itself when the file changes.
+        Ok(())
+    }
+
+    fn shutdown(&self) -> CompositorResult {
+        Ok(())
+    }
+}
+
+// Wayfire workspaces are a fixed grid from `[core] vwidth/vheight`, not synced yet.
+impl Workspace for Wayfire {}
+
+// Wayfire has no night light of its own.
+impl ColorTemp for Wayfire {}
+
+// No appearance settings of its own; GTK apps follow the GSettings interface keys.
+impl Theme for Wayfire {
+    fn theme_mode(&self, scheme: ColorScheme) -> CompositorResult {
+        theme::set_gtk_color_scheme("Wayfire", scheme, true)
+    }
+}
+
+impl Input for Wayfire {
+    fn keyboard_rules(&self, rules: String) -> InputResult {
+        self.set("xkb_rules", rules)
+    }
+
+    fn keyboard_model(&self, model: String) -> InputResult {
+        self.set("xkb_model", model)
+    }
+
+    fn keyboard_layout(&self, layout: String) -> InputResult {
+        self.set("xkb_layout", layout)
+    }
+
+    fn keyboard_variant(&self, variant: String) -> InputResult {
+        self.set("xkb_variant", variant)
+    }
+
+    fn keyboard_options(&self, options: Option<String>) -> InputResult {
+        self.set("xkb_options", options.unwrap_or_default())
+    }
+
+    fn keyboard_repeat_delay(&self, delay: u32) -> InputResult {
+        self.set("kb_repeat_delay", delay)
+    }
+
+    fn keyboard_repeat_rate(&self, rate: u32) -> InputResult {
+        self.set("kb_repeat_rate", rate)
+    }
+
+    fn numslock_state(&self, state: NumlockState) -> InputResult {
+        match numlock::target(state) {
+            Some(enabled) => self.set("kb_numlock_default_state", enabled),
+            None => Ok(()),
+        }
+    }
+
+    /* Touchpad */
+
+    fn touchpad_acceleration(&self, accel: Option<AccelConfig>) -> InputResult {
+        self.acceleration("touchpad", accel)
+    }
+
+    fn touchpad_accel_speed(&self, speed: f64) -> InputResult {
+        self.set("touchpad_cursor_speed", speed.clamp(-1.0, 1.0))
+    }
+
+    fn touchpad_accel_profile(&self, profile: Option<AccelProfile>) -> InputResult {
+        if let Some(profile) = profile {
+            return self.set("touchpad_accel_profile", Self::map_accel_profile(&profile)?);
+        }
+        Ok(())
+    }
+
+    fn touchpad_click_method(&self, method: Option<ClickMethod>) -> InputResult {
+        if let Some(method) = method {
+            return self.set("click_method", Self::map_click_method(&method)?);
+        }
+        Ok(())
+    }
+
+    fn touchpad_disable_while_typing(&self, enabled: Option<bool>) -> InputResult {
+        self.set_opt_bool("disable_touchpad_while_typing", enabled)
+    }
+
+    fn touchpad_scroll_method(&self, method: Option<ScrollMethod>) -> InputResult {
+        if let Some(method) = method {
+            return self.set("scroll_method", Self::map_scroll_method(&method)?);
+        }
+        Ok(())
+    }
+
+    fn touchpad_natural_scroll(&self, enabled: Option<bool>) -> InputResult {
+        self.set_opt_bool("natural_scroll", enabled)
+    }
+
+    fn touchpad_tap_config(&self, config: Option<TapConfig>) -> InputResult {
+        if let Some(config) = config {
+            self.touchpad_tap_enabled(config.enabled)?;
+            self.touchpad_tap_drag_lock(config.drag_lock)?;
+        }
+        Ok(())
+    }
+
+    fn touchpad_tap_enabled(&self, enabled: bool) -> InputResult {
+        self.set("tap_to_click", enabled)
+    }
+
+    fn touchpad_tap_drag_lock(&self, enabled: bool) -> InputResult {
+        self.set("drag_lock", enabled)
+    }
+
+    /* Mouse */
+
+    fn mouse_acceleration(&self, accel: Option<AccelConfig>) -> InputResult {
+        self.acceleration("mouse", accel)
+    }
+
+    fn mouse_accel_speed(&self, speed: f64) -> InputResult {
+        self.set("mouse_cursor_speed", speed.clamp(-1.0, 1.0))
+    }
+
+    fn mouse_accel_profile(&self, profile: Option<AccelProfile>) -> InputResult {
+        if let Some(profile) = profile {
+            return self.set("mouse_accel_profile", Self::map_accel_profile(&profile)?);
+        }
+        Ok(())
+    }
+
+    // Wayfire has no per-class options for these; they apply to every pointer device.
+    fn mouse_left_handed(&self, enabled: Option<bool>) -> InputResult {
+        self.set_opt_bool("left_handed_mode", enabled)
+    }
+
+    fn mouse_middle_button_emulation(&self, enabled: Option<bool>) -> InputResult {
+        self.set_opt_bool("middle_emulation", enabled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options_use_wayfire_names() {
+        let profile = |profile| Wayfire::map_accel_profile(&profile).unwrap();
+        assert_eq!(profile(AccelProfile::Flat), "flat");
+        assert_eq!(profile(AccelProfile::Adaptive), "adaptive");
+
+        let click = |method| Wayfire::map_click_method(&method).unwrap();
+        assert_eq!(click(ClickMethod::ButtonAreas), "button-areas");
+        assert_eq!(click(ClickMethod::Clickfinger), "clickfinger");
+
+        let scroll = |method| Wayfire::map_scroll_method(&method).unwrap();
+        assert_eq!(scroll(ScrollMethod::NoScroll), "none");
+        assert_eq!(scroll(ScrollMethod::TwoFinger), "two-finger");
+        assert_eq!(scroll(ScrollMethod::Edge), "edge");
+        assert_eq!(scroll(ScrollMethod::OnButtonDown), "on-button-down");
+    }
+
+    #[test]
+    fn settings_are_written_to_the_input_section() {
+        let path = env::temp_dir().join(format!("cosmolith-wayfire-{}.ini", std::process::id()));
+        std::fs::write(&path, "[core]\nplugins=ipc\n\n[input]\nxkb_layout=us\n").unwrap();
+        let wayfire = Wayfire {
+            config_path: path.clone(),
+        };
+        wayfire.keyboard_layout("us,de".to_string()).unwrap();
+        wayfire.touchpad_natural_scroll(None).unwrap();
+        wayfire
+            .touchpad_scroll_method(Some(ScrollMethod::Edge))
+            .unwrap();
+        let accel = AccelConfig {
+            profile: Some(AccelProfile::Flat),
+            speed: 2.0,
+        };
+        wayfire.mouse_acceleration(Some(accel)).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            contents,
+            "[core]\n\
+             plugins=ipc\n\
+             \n\
+             [input]\n\
+             xkb_layout=us,de\n\
+             scroll_method=edge\n\
+             mouse_cursor_speed=1\n\
+             mouse_accel_profile=flat\n"
+        );
+    }
+
+    #[test]
+    fn ipc_values_are_json_escaped() {
+        assert_eq!(json_escape(r#"a"b\c"#), r#"a\"b\\c"#);
+    }
+}
@@ -0,0 +1,331 @@
+use std::env;
+use std::path::PathBuf;
+
+use crate::compositor::config_file::{atomic_write, read_or, set_ini_key};
+use crate::compositor::color_temp::ColorTemp;
+use crate::compositor::input::{Input, InputResult};
+use crate::compositor::libinput;
+use crate::compositor::numlock;
+use crate::compositor::trace;
+use crate::compositor::theme::Theme;
+use crate::compositor::workspace::Workspace;
+use crate::compositor::{Compositor, CompositorResult};
+use crate::error::Error;
+use crate::event::Event;
+use crate::event::input::InputEvent;
+
+use cosmic_comp_config::input::{AccelProfile, ClickMethod, ScrollMethod, TapConfig};
+use cosmic_comp_config::NumlockState;
+use tracing::info;
+
+/// Events with a real handler in this backend.
+pub const CAPABILITIES: &[&str] = &[
+    "keyboard_rules",
+    "keyboard_model",
+    "keyboard_layout",
+    "keyboard_variant",
+    "keyboard_options",
+    "keyboard_repeat_delay",
+    "keyboard_repeat_rate",
+    "numslock_state",
+    "touchpad_accel_speed",
+    "touchpad_accel_profile",
+    "touchpad_click_method",
+    "touchpad_disable_while_typing",
+    "touchpad_left_handed",
+    "touchpad_middle_button_emulation",
+    "touchpad_scroll_method",
+    "touchpad_natural_scroll",
+    "touchpad_tap_config",
+    "touchpad_tap_enabled",
+    "touchpad_tap_drag",
+    "touchpad_tap_drag_lock",
+];
+
+const KEYBOARD: &str = "keyboard";
+const LIBINPUT: &str = "libinput";
+
+/// Weston is configured through `weston.ini` and only reads it at startup, so every
+/// write takes effect the next time Weston starts. Mostly useful for CI and headless
+/// test sessions.
+///
+/// `[libinput]` applies to every device. Only the touchpad settings are written there, so
+/// mouse settings cannot overwrite them.
+pub struct Weston {
+    config_path: PathBuf,
+}
+
+impl Weston {
+    pub fn new() -> Self {
+        // Weston exports the file it loaded (including one given with `--config`).
+        let config_path = env::var_os("WESTON_CONFIG_FILE")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| dirs::config_dir().unwrap_or_default().join("weston.ini"));
+        Self { config_path }
+    }
+
+    fn map_accel_profile(profile: &AccelProfile) -> Result<&'static str, Error> {
+        Ok(match libinput::AccelProfile::try_from(profile)? {
+            libinput::AccelProfile::Flat => "flat",
+            libinput::AccelProfile::Adaptive => "adaptive",
+        })
+    }
+
+    fn map_click_method(method: &ClickMethod) -> Result<&'static str, Error> {
+        Ok(match libinput::ClickMethod::try_from(method)? {
+            libinput::ClickMethod::ButtonAreas => "button-areas",
+            libinput::ClickMethod::Clickfinger => "clickfinger",
+        })
+    }
+
+    fn map_scroll_method(method: &ScrollMethod) -> Result<&'static str, Error> {
+        Ok(match libinput::ScrollMethod::try_from(method)? {
+            libinput::ScrollMethod::TwoFinger => "two-finger",
+            libinput::ScrollMethod::Edge => "edge",
+            libinput::ScrollMethod::OnButtonDown => "button",
+            libinput::ScrollMethod::NoScroll => "none",
+        })
+    }
+
+    /// Set `key` in `[section]` of weston.ini.
+    fn set(&self, section: &str, key: &str, value: impl ToString) -> InputResult {
+        let value = value.to_string();
+        let path = &self.config_path;
+        trace::write("Weston", || format!("weston.ini [{section}] {key}={value}"), || {
+            let contents = read_or(path, "")?;
+            atomic_write(path, &set_ini_key(&contents, section, key, &value))
+        })?;
+        Ok(())
+    }
+
+    fn set_opt_bool(&self, key: &str, value: Option<bool>) -> InputResult {
+        if let Some(value) = value {
+            return self.set(LIBINPUT, key, value);
+        }
+        Ok(())
+    }
+}
+
+impl Compositor for Weston {
+    fn init(&mut self) -> CompositorResult {
+        if self.config_path.as_os_str().is_empty() {
+            return Err("Could not resolve the Weston config file".into());
+        }
+        info!(
+            "Weston reads {} only at startup; changes apply after a restart",
+            self.config_path.display()
+        );
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "Weston"
+    }
+
+    fn is_running(&self) -> bool {
+        env::var("WESTON_CONFIG_FILE").is_ok()
+    }
+
+    fn capabilities(&self) -> &'static [&'static str] {
+        CAPABILITIES
+    }
+
+    fn apply_event(&self, event: Event) -> CompositorResult {
+        match event {
+            Event::Input(InputEvent::TouchPad { event: ev, .. }) => self.apply_touchpad_event(ev),
+            Event::Input(InputEvent::Mouse { event: ev, .. }) => self.apply_mouse_event(ev),
+            Event::Input(InputEvent::Keyboard(ev)) => self.apply_keyboard_event(ev),
+            Event::Shortcut(_) => Ok(()),
+            Event::Workspace(ev) => self.apply_workspace_event(ev),
+            Event::Theme(ev) => self.apply_theme_event(ev),
+            Event::Cursor(ev) => self.apply_cursor_event(ev),
+            Event::ColorTemp(ev) => self.apply_color_temp_event(ev),
+        }
+    }
+
+    fn reload(&self) -> CompositorResult {
+        // Weston has no way to reload weston.ini.
+        Ok(())
+    }
+
+    fn shutdown(&self) -> CompositorResult {
+        Ok(())
+    }
+}
+
+// Weston has no configurable workspaces.
+impl Workspace for Weston {}
+
+// Weston has no night light.
+impl ColorTemp for Weston {}
+
+// Weston has no appearance settings for clients to follow.
+impl Theme for Weston {}
+
+impl Input for Weston {
+    fn keyboard_rules(&self, rules: String) -> InputResult {
+        self.set(KEYBOARD, "keymap_rules", rules)
+    }
+
+    fn keyboard_model(&self, model: String) -> InputResult {
+        self.set(KEYBOARD, "keymap_model", model)
+    }
+
+    fn keyboard_layout(&self, layout: String) -> InputResult {
+        self.set(KEYBOARD, "keymap_layout", layout)
+    }
+
+    fn keyboard_variant(&self, variant: String) -> InputResult {
+        self.set(KEYBOARD, "keymap_variant", variant)
+    }
+
+    fn keyboard_options(&self, options: Option<String>) -> InputResult {
+        self.set(KEYBOARD, "keymap_options", options.unwrap_or_default())
+    }
+
+    fn keyboard_repeat_delay(&self, delay: u32) -> InputResult {
+        self.set(KEYBOARD, "repeat-delay", delay)
+    }
+
+    fn keyboard_repeat_rate(&self, rate: u32) -> InputResult {
+        self.set(KEYBOARD, "repeat-rate", rate)
+    }
+
+    fn numslock_state(&self, state: NumlockState) -> InputResult {
+        match numlock::target(state) {
+            Some(enabled) => self.set(KEYBOARD, "numlock-on", enabled),
+            None => Ok(()),
+        }
+    }
+
+    /* Touchpad */
+
+    fn touchpad_accel_speed(&self, speed: f64) -> InputResult {
+        self.set(LIBINPUT, "accel-speed", speed.clamp(-1.0, 1.0))
+    }
+
+    fn touchpad_accel_profile(&self, profile: Option<AccelProfile>) -> InputResult {
+        if let Some(profile) = profile {
+            return self.set(LIBINPUT, "accel-profile", Self::map_accel_profile(&profile)?);
+        }
+        Ok(())
+    }
+
+    fn touchpad_click_method(&self, method: Option<ClickMethod>) -> InputResult {
+        if let Some(method) = method {
+            return self.set(LIBINPUT, "click-method", Self::map_click_method(&method)?);
+        }
+        Ok(())
+    }
+
+    fn touchpad_disable_while_typing(&self, enabled: Option<bool>) -> InputResult {
+        self.set_opt_bool("disable-while-typing", enabled)
+    }
+
+    fn touchpad_left_handed(&self, enabled: Option<bool>) -> InputResult {
+        self.set_opt_bool("left-handed", enabled)
+    }
+
+    fn touchpad_middle_button_emulation(&self, enabled: Option<bool>) -> InputResult {
+        self.set_opt_bool("middle-button-emulation", enabled)
+    }
+
+    fn touchpad_scroll_method(&self, method: Option<ScrollMethod>) -> InputResult {
+        if let Some(method) = method {
+            return self.set(LIBINPUT, "scroll-method", Self::map_scroll_method(&method)?);
+        }
+        Ok(())
+    }
+
+    fn touchpad_natural_scroll(&self, enabled: Option<bool>) -> InputResult {
+        self.set_opt_bool("natural-scroll", enabled)
+    }
+
+    fn touchpad_tap_config(&self, config: Option<TapConfig>) -> InputResult {
+        if let Some(config) = config {
+            self.touchpad_tap_enabled(config.enabled)?;
+            self.touchpad_tap_drag(config.drag)?;
+            self.touchpad_tap_drag_lock(config.drag_lock)?;
+        }
+        Ok(())
+    }
+
+    fn touchpad_tap_enabled(&self, enabled: bool) -> InputResult {
+        self.set(LIBINPUT, "enable-tap", enabled)
+    }
+
+    fn touchpad_tap_drag(&self, enabled: bool) -> InputResult {
+        self.set(LIBINPUT, "tap-and-drag", enabled)
+    }
+
+    fn touchpad_tap_drag_lock(&self, enabled: bool) -> InputResult {
+        self.set(LIBINPUT, "tap-and-drag-lock", enabled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::input::{KeyboardEvent, MouseEvent, TouchpadEvent};
+
+    #[test]
+    fn settings_go_to_their_weston_ini_sections() {
+        let path = env::temp_dir().join(format!("cosmolith-weston-{}.ini", std::process::id()));
+        let existing = "[core]\nidle-time=0\n\n[keyboard]\nkeymap_layout=us\n";
+        std::fs::write(&path, existing).unwrap();
+        let weston = Weston {
+            config_path: path.clone(),
+        };
+        let scroll_method = TouchpadEvent::ScrollMethod(Some(ScrollMethod::OnButtonDown));
+        let events = [
+            InputEvent::Keyboard(KeyboardEvent::Layout("us,de".to_string())),
+            InputEvent::Keyboard(KeyboardEvent::RepeatRate(30)),
+            InputEvent::touchpad(scroll_method),
+            InputEvent::touchpad(TouchpadEvent::AccelSpeed(-2.0)),
+            InputEvent::touchpad(TouchpadEvent::NaturalScroll(None)),
+            // Mice share [libinput] with touchpads, so their settings are left out.
+            InputEvent::mouse(MouseEvent::NaturalScroll(Some(true))),
+        ];
+        for event in events {
+            weston.apply_event(Event::Input(event)).unwrap();
+        }
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            contents,
+            "[core]\n\
+             idle-time=0\n\
+             \n\
+             [keyboard]\n\
+             keymap_layout=us,de\n\
+             repeat-rate=30\n\
+             \n\
+             [libinput]\n\
+             scroll-method=button\n\
+             accel-speed=-1\n"
+        );
+    }
+
+    #[test]
+    fn tap_config_is_written_field_by_field() {
+        let path = env::temp_dir().join(format!("cosmolith-weston-tap-{}.ini", std::process::id()));
+        let weston = Weston {
+            config_path: path.clone(),
+        };
+        let tap = TapConfig {
+            enabled: true,
+            button_map: None,
+            drag: false,
+            drag_lock: true,
+        };
+        weston.touchpad_tap_config(Some(tap)).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            contents,
+            "[libinput]\nenable-tap=true\ntap-and-drag=false\ntap-and-drag-lock=true\n"
+        );
+    }
+}
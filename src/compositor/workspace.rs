@@ -0,0 +1,27 @@
+use crate::compositor::CompositorResult;
+use crate::error::Error;
+use crate::event::workspaces::WorkspaceEvent;
+
+/// Compositor workspace interface. Unlike `Input`, the defaults return
+/// `Error::NotImplemented`: workspace models differ enough that silently doing nothing
+/// would hide a mismatch.
+pub trait Workspace {
+    fn apply_workspace_event(&self, event: WorkspaceEvent) -> CompositorResult {
+        match event {
+            WorkspaceEvent::Count(count) => self.workspace_count(count),
+            WorkspaceEvent::Names(names) => self.workspace_names(names),
+        }
+    }
+
+    fn workspace_count(&self, _count: u32) -> CompositorResult {
+        Err(Error::NotImplemented {
+            handler: "workspace_count",
+        })
+    }
+
+    fn workspace_names(&self, _names: Vec<String>) -> CompositorResult {
+        Err(Error::NotImplemented {
+            handler: "workspace_names",
+        })
+    }
+}
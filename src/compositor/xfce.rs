@@ -0,0 +1,306 @@
+use std::process::Command;
+
+use crate::compositor::color_temp::ColorTemp;
+use crate::compositor::input::{Input, InputResult};
+use crate::compositor::trace;
+use crate::compositor::theme::Theme;
+use crate::compositor::workspace::Workspace;
+use crate::compositor::{Compositor, CompositorResult};
+use crate::error::Error;
+use crate::event::Event;
+use crate::event::input::InputEvent;
+
+use cosmic_comp_config::input::{AccelConfig, TapConfig};
+
+/// Events with a real handler in this backend. Anything else is rejected with
+/// `Error::NotImplemented` by `Session::apply`, since xfconf has no key for it.
+pub const CAPABILITIES: &[&str] = &[
+    "touchpad_acceleration",
+    "touchpad_accel_speed",
+    "touchpad_left_handed",
+    "touchpad_natural_scroll",
+    "touchpad_tap_config",
+    "touchpad_tap_enabled",
+    "touchpad_tap_drag",
+    "mouse_acceleration",
+    "mouse_accel_speed",
+    "mouse_left_handed",
+    "mouse_natural_scroll",
+];
+
+const CHANNEL: &str = "pointers";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DeviceKind {
+    Touchpad,
+    Mouse,
+}
+
+/// xfconf value types, as passed to `xfconf-query -t`.
+enum Value {
+    Bool(bool),
+    Int(i32),
+    Double(f64),
+}
+
+/// XFCE backend. xfsettingsd watches the `pointers` channel and applies changes to the
+/// devices, so writing the property is all that is needed.
+///
+/// Settings are stored per device under `/<device name>/`, so every write goes to each
+/// device of the right kind that xfconf already knows about.
+#[derive(Debug, Default)]
+pub struct Xfce;
+
+impl Xfce {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn xfconf_query(&self, args: &[&str]) -> Result<String, Error> {
+        trace::timed("XFCE", || command(args), || run(args))
+    }
+
+    /// [`Self::xfconf_query`] for commands that change settings, which `--dry-run` skips.
+    fn xfconf_write(&self, args: &[&str]) -> InputResult {
+        trace::write("XFCE", || command(args), || run(args))?;
+        Ok(())
+    }
+
+    /// Device names in the `pointers` channel of the given kind.
+    fn devices(&self, kind: DeviceKind) -> Result<Vec<String>, Error> {
+        let listing = self.xfconf_query(&["-l"])?;
+        Ok(device_names(&listing)
+            .into_iter()
+            .filter(|name| classify(name) == kind)
+            .collect())
+    }
+
+    /// Set `/<device>/<property>` on every device of `kind`, creating it if needed. Fails
+    /// when xfconf has no device of `kind`.
+    fn set(&self, kind: DeviceKind, property: &str, value: Value) -> InputResult {
+        let (ty, value) = match value {
+            Value::Bool(value) => ("bool", value.to_string()),
+            Value::Int(value) => ("int", value.to_string()),
+            Value::Double(value) => ("double", value.to_string()),
+        };
+        let devices = self.devices(kind)?;
+        if devices.is_empty() {
+            // xfconf only has devices xfsettingsd has seen; a new key would go unread.
+            return Err(Error::RoutingFailed {
+                reason: format!("xfconf has no {kind:?} device to set {property} on"),
+            });
+        }
+        for device in devices {
+            let path = format!("/{device}/{property}");
+            self.xfconf_write(&["-p", &path, "-n", "-t", ty, "-s", &value])?;
+        }
+        Ok(())
+    }
+
+    fn set_libinput_flag(&self, kind: DeviceKind, property: &str, enabled: bool) -> InputResult {
+        self.set(
+            kind,
+            &format!("Properties/{property}"),
+            Value::Int(enabled.into()),
+        )
+    }
+
+    fn set_accel_speed(&self, kind: DeviceKind, speed: f64) -> InputResult {
+        let speed = speed.clamp(-1.0, 1.0);
+        self.set(
+            kind,
+            "Properties/libinput_Accel_Speed",
+            Value::Double(speed),
+        )
+    }
+}
+
+/// Run `xfconf-query` on the `pointers` channel and return its output.
+fn run(args: &[&str]) -> Result<String, Error> {
+    let output = Command::new("xfconf-query")
+        .args(["-c", CHANNEL])
+        .args(args)
+        .output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("{} failed: {}", command(args), stderr.trim()).into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// `xfconf-query` with `args`, as traced and printed by `--dry-run`.
+fn command(args: &[&str]) -> String {
+    format!("xfconf-query -c {CHANNEL} {}", args.join(" "))
+}
+
+/// Device names from `xfconf-query -l` output, which lists one property path per line.
+fn device_names(listing: &str) -> Vec<String> {
+    let mut names: Vec<String> = listing
+        .lines()
+        .filter_map(|path| path.trim().strip_prefix('/')?.split('/').next())
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// xfconf names devices after their X11 name with spaces replaced, e.g.
+/// `SynPS2_Synaptics_TouchPad`; only the name tells touchpads and mice apart.
+fn classify(name: &str) -> DeviceKind {
+    let lower = name.to_lowercase();
+    if lower.contains("touchpad") || lower.contains("trackpad") {
+        DeviceKind::Touchpad
+    } else {
+        DeviceKind::Mouse
+    }
+}
+
+impl Compositor for Xfce {
+    fn init(&mut self) -> CompositorResult {
+        self.xfconf_query(&["-l"])
+            .map_err(|err| Error::DetectionFailed(format!("xfconf is not usable: {err}")))?;
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "XFCE"
+    }
+
+    fn is_running(&self) -> bool {
+        std::env::var("XDG_CURRENT_DESKTOP")
+            .map(|val| val.to_uppercase().contains("XFCE"))
+            .unwrap_or(false)
+    }
+
+    fn capabilities(&self) -> &'static [&'static str] {
+        CAPABILITIES
+    }
+
+    fn apply_event(&self, event: Event) -> CompositorResult {
+        match event {
+            Event::Input(InputEvent::TouchPad { event: ev, .. }) => self.apply_touchpad_event(ev),
+            Event::Input(InputEvent::Mouse { event: ev, .. }) => self.apply_mouse_event(ev),
+            Event::Workspace(ev) => self.apply_workspace_event(ev),
+            Event::Theme(ev) => self.apply_theme_event(ev),
+            Event::Cursor(ev) => self.apply_cursor_event(ev),
+            Event::ColorTemp(ev) => self.apply_color_temp_event(ev),
+            _ => Ok(()),
+        }
+    }
+
+    fn reload(&self) -> CompositorResult {
+        Ok(())
+    }
+
+    fn shutdown(&self) -> CompositorResult {
+        Ok(())
+    }
+}
+
+// xfwm4 workspaces are not synced yet.
+impl Workspace for Xfce {}
+
+// XFCE has no night light of its own.
+impl ColorTemp for Xfce {}
+
+// xsettings `Net/ThemeName` is not synced yet.
+impl Theme for Xfce {}
+
+impl Input for Xfce {
+    /* Touchpad */
+
+    // xfconf has no acceleration profile, only the speed.
+    fn touchpad_acceleration(&self, accel: Option<AccelConfig>) -> InputResult {
+        match accel {
+            Some(accel) => self.set_accel_speed(DeviceKind::Touchpad, accel.speed),
+            None => Ok(()),
+        }
+    }
+
+    fn touchpad_accel_speed(&self, speed: f64) -> InputResult {
+        self.set_accel_speed(DeviceKind::Touchpad, speed)
+    }
+
+    fn touchpad_left_handed(&self, enabled: Option<bool>) -> InputResult {
+        match enabled {
+            Some(left) => self.set(DeviceKind::Touchpad, "RightHanded", Value::Bool(!left)),
+            None => Ok(()),
+        }
+    }
+
+    fn touchpad_natural_scroll(&self, enabled: Option<bool>) -> InputResult {
+        match enabled {
+            Some(enabled) => self.set(
+                DeviceKind::Touchpad,
+                "ReverseScrolling",
+                Value::Bool(enabled),
+            ),
+            None => Ok(()),
+        }
+    }
+
+    fn touchpad_tap_config(&self, config: Option<TapConfig>) -> InputResult {
+        if let Some(config) = config {
+            self.touchpad_tap_enabled(config.enabled)?;
+            self.touchpad_tap_drag(config.drag)?;
+        }
+        Ok(())
+    }
+
+    fn touchpad_tap_enabled(&self, enabled: bool) -> InputResult {
+        self.set_libinput_flag(DeviceKind::Touchpad, "libinput_Tapping_Enabled", enabled)
+    }
+
+    fn touchpad_tap_drag(&self, enabled: bool) -> InputResult {
+        self.set_libinput_flag(
+            DeviceKind::Touchpad,
+            "libinput_Tapping_Drag_Enabled",
+            enabled,
+        )
+    }
+
+    /* Mouse */
+
+    fn mouse_acceleration(&self, accel: Option<AccelConfig>) -> InputResult {
+        match accel {
+            Some(accel) => self.set_accel_speed(DeviceKind::Mouse, accel.speed),
+            None => Ok(()),
+        }
+    }
+
+    fn mouse_accel_speed(&self, speed: f64) -> InputResult {
+        self.set_accel_speed(DeviceKind::Mouse, speed)
+    }
+
+    fn mouse_left_handed(&self, enabled: Option<bool>) -> InputResult {
+        match enabled {
+            Some(left) => self.set(DeviceKind::Mouse, "RightHanded", Value::Bool(!left)),
+            None => Ok(()),
+        }
+    }
+
+    fn mouse_natural_scroll(&self, enabled: Option<bool>) -> InputResult {
+        match enabled {
+            Some(enabled) => self.set(DeviceKind::Mouse, "ReverseScrolling", Value::Bool(enabled)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn devices_are_read_from_property_paths() {
+        let listing = "/SynPS2_Synaptics_TouchPad/Properties/libinput_Tapping_Enabled\n\
+                       /SynPS2_Synaptics_TouchPad/RightHanded\n\
+                       /Logitech_USB_Optical_Mouse/RightHanded\n";
+        let names = device_names(listing);
+        assert_eq!(names, ["Logitech_USB_Optical_Mouse", "SynPS2_Synaptics_TouchPad"]);
+        assert_eq!(classify(&names[0]), DeviceKind::Mouse);
+        assert_eq!(classify(&names[1]), DeviceKind::Touchpad);
+    }
+}
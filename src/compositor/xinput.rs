@@ -0,0 +1,284 @@
+use std::env;
+use std::process::Command;
+
+use crate::compositor::input::{Input, InputResult};
+use crate::compositor::trace;
+use crate::compositor::{Compositor, CompositorResult};
+use crate::error::Error;
+use crate::event::Event;
+use crate::event::input::InputEvent;
+
+/// Events with a real handler in this backend.
+pub const CAPABILITIES: &[&str] = &[
+    "touchpad_accel_speed",
+    "touchpad_left_handed",
+    "touchpad_natural_scroll",
+    "touchpad_tap_enabled",
+    "touchpad_tap_drag",
+    "touchpad_tap_drag_lock",
+    "mouse_accel_speed",
+    "mouse_left_handed",
+    "mouse_natural_scroll",
+];
+
+/// Only touchpads expose tapping, which is how they are told apart from mice.
+const TOUCHPAD_MARKER: &str = "libinput Tapping Enabled";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DeviceKind {
+    Touchpad,
+    Mouse,
+}
+
+/// Backend for X11 sessions without a desktop-specific backend, and secondary backend for
+/// X11 clients under Xwayland with `--also-x11`.
+///
+/// Xwayland devices take their libinput settings from `xinput set-prop`, separately from
+/// the Wayland compositor, so under Wayland this runs alongside the real backend.
+#[derive(Debug, Default)]
+pub struct Xinput;
+
+impl Xinput {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn xinput(&self, args: &[&str]) -> Result<String, Error> {
+        trace::timed("xinput", || command(args), || run(args))
+    }
+
+    /// [`Self::xinput`] for commands that change settings, which `--dry-run` skips.
+    fn xinput_write(&self, args: &[&str]) -> InputResult {
+        trace::write("xinput", || command(args), || run(args))?;
+        Ok(())
+    }
+
+    /// Pointer devices with libinput properties, classified as touchpad or mouse.
+    /// Listed on every call so hotplugged devices are picked up.
+    fn devices(&self) -> Result<Vec<(String, DeviceKind)>, Error> {
+        let mut devices = Vec::new();
+        for id in self.xinput(&["list", "--id-only"])?.lines().map(str::trim) {
+            if id.is_empty() {
+                continue;
+            }
+            let Ok(props) = self.xinput(&["list-props", id]) else {
+                continue;
+            };
+            if let Some(kind) = classify(&props) {
+                devices.push((id.to_string(), kind));
+            }
+        }
+        Ok(devices)
+    }
+
+    /// Set `prop` on every device of `kind`. Fails when there is none, and otherwise
+    /// with the first failed device, after trying the others.
+    fn set_prop(&self, kind: DeviceKind, prop: &str, value: &str) -> InputResult {
+        let devices = self.devices()?;
+        let mut args = set_prop_args(&devices, kind, prop, value).peekable();
+        if args.peek().is_none() {
+            return Err(Error::RoutingFailed {
+                reason: format!("no {kind:?} device to set {prop} on"),
+            });
+        }
+        // One failed device should not keep the others unchanged.
+        let mut result = Ok(());
+        for args in args {
+            let written = self.xinput_write(&args);
+            if result.is_ok() {
+                result = written;
+            }
+        }
+        result
+    }
+
+    fn set_bool(&self, kind: DeviceKind, prop: &str, value: Option<bool>) -> InputResult {
+        match value {
+            Some(value) => self.set_prop(kind, prop, if value { "1" } else { "0" }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Run `xinput` with `args` and return its output.
+fn run(args: &[&str]) -> Result<String, Error> {
+    let output = Command::new("xinput").args(args).output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("{} failed: {}", command(args), stderr.trim()).into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// `xinput` with `args`, as traced and printed by `--dry-run`.
+fn command(args: &[&str]) -> String {
+    format!("xinput {}", args.join(" "))
+}
+
+/// The `xinput` arguments that set `prop` to `value` on every device of `kind`.
+fn set_prop_args<'a>(
+    devices: &'a [(String, DeviceKind)],
+    kind: DeviceKind,
+    prop: &'a str,
+    value: &'a str,
+) -> impl Iterator<Item = [&'a str; 4]> {
+    devices
+        .iter()
+        .filter(move |(_, k)| *k == kind)
+        .map(move |(id, _)| ["set-prop", id.as_str(), prop, value])
+}
+
+/// Touchpad, mouse, or neither (keyboards and other devices without libinput pointer
+/// properties), judging by the output of `xinput list-props`.
+fn classify(props: &str) -> Option<DeviceKind> {
+    if props.contains(TOUCHPAD_MARKER) {
+        Some(DeviceKind::Touchpad)
+    } else if props.contains("libinput Accel Speed") {
+        Some(DeviceKind::Mouse)
+    } else {
+        None
+    }
+}
+
+impl Compositor for Xinput {
+    fn init(&mut self) -> CompositorResult {
+        if !self.is_running() {
+            return Err(Error::MissingEnvVar { var: "DISPLAY" });
+        }
+        self.xinput(&["version"])?;
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "X11 (xinput)"
+    }
+
+    fn is_running(&self) -> bool {
+        env::var("DISPLAY").is_ok()
+    }
+
+    fn capabilities(&self) -> &'static [&'static str] {
+        CAPABILITIES
+    }
+
+    // Device properties are reset when the X server restarts.
+    fn keeps_settings(&self) -> bool {
+        false
+    }
+
+    fn apply_event(&self, event: Event) -> CompositorResult {
+        match event {
+            Event::Input(InputEvent::TouchPad { event: ev, .. }) => self.apply_touchpad_event(ev),
+            Event::Input(InputEvent::Mouse { event: ev, .. }) => self.apply_mouse_event(ev),
+            _ => Ok(()),
+        }
+    }
+
+    fn reload(&self) -> CompositorResult {
+        Ok(())
+    }
+
+    fn shutdown(&self) -> CompositorResult {
+        Ok(())
+    }
+}
+
+impl Input for Xinput {
+    fn touchpad_accel_speed(&self, speed: f64) -> InputResult {
+        let speed = speed.clamp(-1.0, 1.0).to_string();
+        self.set_prop(DeviceKind::Touchpad, "libinput Accel Speed", &speed)
+    }
+
+    fn touchpad_left_handed(&self, enabled: Option<bool>) -> InputResult {
+        self.set_bool(
+            DeviceKind::Touchpad,
+            "libinput Left Handed Enabled",
+            enabled,
+        )
+    }
+
+    fn touchpad_natural_scroll(&self, enabled: Option<bool>) -> InputResult {
+        self.set_bool(
+            DeviceKind::Touchpad,
+            "libinput Natural Scrolling Enabled",
+            enabled,
+        )
+    }
+
+    fn touchpad_tap_enabled(&self, enabled: bool) -> InputResult {
+        self.set_bool(
+            DeviceKind::Touchpad,
+            "libinput Tapping Enabled",
+            Some(enabled),
+        )
+    }
+
+    fn touchpad_tap_drag(&self, enabled: bool) -> InputResult {
+        self.set_bool(
+            DeviceKind::Touchpad,
+            "libinput Tapping Drag Enabled",
+            Some(enabled),
+        )
+    }
+
+    fn touchpad_tap_drag_lock(&self, enabled: bool) -> InputResult {
+        self.set_bool(
+            DeviceKind::Touchpad,
+            "libinput Tapping Drag Lock Enabled",
+            Some(enabled),
+        )
+    }
+
+    fn mouse_accel_speed(&self, speed: f64) -> InputResult {
+        let speed = speed.clamp(-1.0, 1.0).to_string();
+        self.set_prop(DeviceKind::Mouse, "libinput Accel Speed", &speed)
+    }
+
+    fn mouse_left_handed(&self, enabled: Option<bool>) -> InputResult {
+        self.set_bool(DeviceKind::Mouse, "libinput Left Handed Enabled", enabled)
+    }
+
+    fn mouse_natural_scroll(&self, enabled: Option<bool>) -> InputResult {
+        self.set_bool(
+            DeviceKind::Mouse,
+            "libinput Natural Scrolling Enabled",
+            enabled,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn devices_are_classified_by_their_properties() {
+        let touchpad = "Device 'SynPS/2 Synaptics TouchPad':\n\
+                        \tlibinput Tapping Enabled (303):\t1\n\
+                        \tlibinput Accel Speed (311):\t0.000000\n";
+        let mouse = "Device 'Logitech USB Receiver':\n\
+                     \tlibinput Accel Speed (311):\t0.000000\n";
+        let keyboard = "Device 'AT Translated Set 2 keyboard':\n\
+                        \tlibinput Send Events Mode Enabled (290):\t0, 0\n";
+        assert_eq!(classify(touchpad), Some(DeviceKind::Touchpad));
+        assert_eq!(classify(mouse), Some(DeviceKind::Mouse));
+        assert_eq!(classify(keyboard), None);
+    }
+
+    #[test]
+    fn properties_are_set_on_devices_of_one_kind() {
+        let devices = [
+            ("11".to_string(), DeviceKind::Touchpad),
+            ("12".to_string(), DeviceKind::Mouse),
+            ("14".to_string(), DeviceKind::Touchpad),
+        ];
+        let prop = "libinput Tapping Enabled";
+        let args: Vec<_> = set_prop_args(&devices, DeviceKind::Touchpad, prop, "1").collect();
+        assert_eq!(
+            args,
+            [["set-prop", "11", prop, "1"], ["set-prop", "14", prop, "1"]]
+        );
+        let args: Vec<_> = set_prop_args(&devices, DeviceKind::Mouse, prop, "0").collect();
+        assert_eq!(args, [["set-prop", "12", prop, "0"]]);
+    }
+}
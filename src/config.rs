@@ -0,0 +1,138 @@
+//! cosmolith's own settings, read from `$XDG_CONFIG_HOME/cosmolith/config.toml`.
+//!
+//! Every key is optional and a missing file means all defaults:
+//!
+//! ```toml
+//! [numlock]
+//! # What to do for "restore numlock from last boot" when no previous state is known.
+//! default_when_unknown = "leave" # "on" | "off" | "leave"
+//!
+//! [reconnect]
+//! # Backoff for backends that keep an IPC connection (Sway, KDE).
+//! max_attempts = 3
+//! base_delay_ms = 100
+//! max_delay_ms = 2000
+//!
+//! [debounce]
+//! # Window per event category; `--debounce` and `--debounce-ms` override it.
+//! scroll_ms = 150
+//! pointer_ms = 150
+//! immediate = ["keyboard_layout", "touchpad_state"]
+//!
+//! [overrides.touchpad_tap_enabled]
+//! # Per event: `disable = true` or a fixed `value`, optionally only for some
+//! # `backends`. See the `overrides` module.
+//! value = false
+//! ```
+
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::compositor::reconnect::ReconnectPolicy;
+use crate::debounce::DebounceConfig;
+use crate::overrides::Overrides;
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CosmolithConfig {
+    pub numlock: NumlockConfig,
+    pub reconnect: ReconnectPolicy,
+    pub debounce: DebounceConfig,
+    pub overrides: Overrides,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NumlockConfig {
+    pub default_when_unknown: NumlockDefault,
+}
+
+/// Numlock state applied for `NumlockState::LastBoot` when the previous state is unknown.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NumlockDefault {
+    On,
+    Off,
+    /// Leave the compositor's numlock setting alone.
+    #[default]
+    Leave,
+}
+
+impl CosmolithConfig {
+    pub fn default_path() -> Option<PathBuf> {
+        Some(dirs::config_dir()?.join("cosmolith").join("config.toml"))
+    }
+
+    /// Read the config at `path`, or the defaults when the file does not exist.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        match fs::read_to_string(path) {
+            Ok(contents) => {
+                Self::parse(&contents).map_err(|err| format!("{}: {err}", path.display()).into())
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+}
+
+/// A `*_ms` key as a `Duration`.
+pub(crate) fn millis<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Duration, D::Error> {
+    u64::deserialize(deserializer).map(Duration::from_millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_config_leaves_numlock_alone() {
+        let config = CosmolithConfig::parse("").unwrap();
+        assert_eq!(config.numlock.default_when_unknown, NumlockDefault::Leave);
+    }
+
+    #[test]
+    fn reconnect_policy_is_parsed_in_milliseconds() {
+        let config = CosmolithConfig::parse("[reconnect]\nmax_attempts = 5\nbase_delay_ms = 50\n");
+        let policy = config.unwrap().reconnect;
+        assert_eq!(policy.max_attempts, 5);
+        assert_eq!(policy.base_delay, std::time::Duration::from_millis(50));
+        assert_eq!(policy.max_delay, ReconnectPolicy::DEFAULT.max_delay);
+    }
+
+    #[test]
+    fn numlock_default_is_parsed() {
+        let config = CosmolithConfig::parse("[numlock]\ndefault_when_unknown = \"on\"\n").unwrap();
+        assert_eq!(config.numlock.default_when_unknown, NumlockDefault::On);
+        assert!(CosmolithConfig::parse("[numlock]\ndefault_when_unknown = \"yes\"\n").is_err());
+    }
+
+    #[test]
+    fn debounce_windows_are_parsed_in_milliseconds() {
+        let contents = "[debounce]\nscroll_ms = 40\nimmediate = [\"touchpad_tap_enabled\"]\n";
+        let debounce = CosmolithConfig::parse(contents).unwrap().debounce;
+        assert_eq!(debounce.scroll, Duration::from_millis(40));
+        assert_eq!(debounce.pointer, DebounceConfig::default().pointer);
+        assert_eq!(debounce.immediate, ["touchpad_tap_enabled"]);
+        assert!(CosmolithConfig::parse("[debounce]\nimmediate = [\"tap\"]\n").is_err());
+        assert!(CosmolithConfig::parse("[debounce]\nscroll = 40\n").is_err());
+    }
+
+    #[test]
+    fn overrides_are_keyed_by_event() {
+        let contents = "[overrides.touchpad_natural_scroll]\ndisable = true\n";
+        let config = CosmolithConfig::parse(contents).unwrap();
+        assert_ne!(config.overrides, Overrides::default());
+        assert!(CosmolithConfig::parse("[overrides.natural_scroll]\ndisable = true\n").is_err());
+    }
+}
@@ -0,0 +1,344 @@
+//! Control socket: lets the user pause and resume syncing while cosmolith runs.
+//!
+//! The socket speaks one command per line and answers every command with one line
+//! holding the current filter state:
+//!
+//! ```text
+//! $ echo "disable mouse" | socat - UNIX-CONNECT:$XDG_RUNTIME_DIR/cosmolith.sock
+//! ok keyboard=on touchpad=on mouse=off shortcut=on workspace=on theme=on night_light=on
+//! ```
+//!
+//! Commands: `status`, `disable <group>`, `enable <group>`, with groups `keyboard`,
+//! `touchpad`, `mouse`, `shortcut`, `workspace`, `theme`, `night_light` or `all`. Re-enabling a
+//! group only affects changes made afterwards; settings changed in COSMIC while it was paused are
+//! not replayed.
+//!
+//! With the `jsonrpc` feature, lines starting with `{` are JSON-RPC 2.0 requests instead;
+//! see `crate::jsonrpc`.
+
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::event::{Event, InputEvent, SourcedEvent};
+
+/// How often a subscribed client's socket is checked for requests.
+const SUBSCRIBER_POLL: Duration = Duration::from_millis(100);
+
+/// Events grouped the way a user thinks about them: by device or feature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Group {
+    Keyboard,
+    Touchpad,
+    Mouse,
+    Shortcut,
+    Workspace,
+    /// Light/dark mode and the cursor.
+    Theme,
+    NightLight,
+}
+
+impl Group {
+    pub const ALL: [Group; 7] = [
+        Group::Keyboard,
+        Group::Touchpad,
+        Group::Mouse,
+        Group::Shortcut,
+        Group::Workspace,
+        Group::Theme,
+        Group::NightLight,
+    ];
+
+    pub fn of(event: &Event) -> Group {
+        match event {
+            Event::Input(InputEvent::Keyboard(_)) => Group::Keyboard,
+            Event::Input(InputEvent::TouchPad { .. }) => Group::Touchpad,
+            Event::Input(InputEvent::Mouse { .. }) => Group::Mouse,
+            Event::Shortcut(_) => Group::Shortcut,
+            Event::Workspace(_) => Group::Workspace,
+            Event::Theme(_) | Event::Cursor(_) => Group::Theme,
+            Event::ColorTemp(_) => Group::NightLight,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Group::Keyboard => "keyboard",
+            Group::Touchpad => "touchpad",
+            Group::Mouse => "mouse",
+            Group::Shortcut => "shortcut",
+            Group::Workspace => "workspace",
+            Group::Theme => "theme",
+            Group::NightLight => "night_light",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Group> {
+        Group::ALL.into_iter().find(|group| group.name() == name)
+    }
+}
+
+/// Which groups are currently synced. Everything is synced by default.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SyncFilter {
+    paused: Vec<Group>,
+}
+
+impl SyncFilter {
+    pub fn allows(&self, event: &Event) -> bool {
+        self.enabled(Group::of(event))
+    }
+
+    pub fn enabled(&self, group: Group) -> bool {
+        !self.paused.contains(&group)
+    }
+
+    pub fn set(&mut self, group: Group, enabled: bool) {
+        self.paused.retain(|paused| *paused != group);
+        if !enabled {
+            self.paused.push(group);
+        }
+    }
+
+    /// `keyboard=on touchpad=off ...`, in `Group::ALL` order.
+    pub fn summary(&self) -> String {
+        Group::ALL
+            .iter()
+            .map(|group| {
+                let state = if self.enabled(*group) { "on" } else { "off" };
+                format!("{}={state}", group.name())
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Clients subscribed to applied settings (JSON-RPC `subscribe`).
+#[derive(Clone, Default)]
+pub struct Subscribers(Arc<Mutex<Vec<UnixStream>>>);
+
+impl Subscribers {
+    /// Subscribe `stream`. It is made non-blocking so a client that stops reading is
+    /// dropped instead of stalling the main loop; since it shares its socket with the
+    /// client's request stream, `serve_client` then polls for requests.
+    pub fn add(&self, stream: UnixStream) -> io::Result<()> {
+        stream.set_nonblocking(true)?;
+        self.0.lock().unwrap_or_else(|err| err.into_inner()).push(stream);
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.lock().unwrap_or_else(|err| err.into_inner()).is_empty()
+    }
+
+    /// Send `line` to every subscriber, dropping the ones that hung up or whose socket
+    /// buffer is full.
+    pub fn broadcast(&self, line: &str) {
+        let mut streams = self.0.lock().unwrap_or_else(|err| err.into_inner());
+        if streams.is_empty() {
+            return;
+        }
+        let line = format!("{line}\n");
+        streams.retain_mut(|stream| match stream.write(line.as_bytes()) {
+            Ok(written) => written == line.len(),
+            Err(_) => false,
+        });
+    }
+}
+
+/// Daemon state reachable from the control socket.
+#[derive(Clone)]
+pub struct Control {
+    pub filter: Arc<Mutex<SyncFilter>>,
+    /// Events sent here go through the same debounce and filter as watcher events.
+    pub events: Arc<Mutex<Sender<SourcedEvent>>>,
+    /// Name of the active backend, if one was initialized. Updated when SIGHUP detects
+    /// the compositor again.
+    pub backend: Arc<Mutex<Option<&'static str>>>,
+    pub subscribers: Subscribers,
+}
+
+impl Control {
+    pub fn backend(&self) -> Option<&'static str> {
+        *self.backend.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// `$XDG_RUNTIME_DIR/cosmolith.sock`.
+pub fn default_path() -> Option<PathBuf> {
+    Some(dirs::runtime_dir()?.join("cosmolith.sock"))
+}
+
+/// Bind the socket at `path` and serve it on a background thread.
+///
+/// A leftover socket file from a crashed run is replaced; one that still accepts
+/// connections belongs to another instance and is left alone.
+pub fn serve(path: &Path, control: Control) -> io::Result<()> {
+    if path.exists() {
+        if UnixStream::connect(path).is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                format!("{} is used by another cosmolith", path.display()),
+            ));
+        }
+        fs::remove_file(path)?;
+    }
+    let listener = UnixListener::bind(path)?;
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else { continue };
+            let control = control.clone();
+            thread::spawn(move || {
+                if let Err(err) = serve_client(stream, &control) {
+                    warn!("Control socket client error: {err}");
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+fn serve_client(stream: UnixStream, control: &Control) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    loop {
+        // A partly read line stays in `line` and is completed by the next read.
+        match reader.read_line(&mut line) {
+            Ok(0) => return Ok(()),
+            Ok(_) => {}
+            // The socket is non-blocking once the client subscribed.
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                thread::sleep(SUBSCRIBER_POLL);
+                continue;
+            }
+            Err(err) => return Err(err),
+        }
+        let line = std::mem::take(&mut line);
+        let line = line.trim_end();
+        if line.is_empty() {
+            continue;
+        }
+        #[cfg(feature = "jsonrpc")]
+        if line.trim_start().starts_with('{') {
+            // Notifications (requests without an id) get no reply.
+            if let Some(reply) = crate::jsonrpc::handle(line, control, &writer) {
+                writeln!(writer, "{reply}")?;
+            }
+            continue;
+        }
+        let reply = {
+            let mut filter = control.filter.lock().unwrap_or_else(|err| err.into_inner());
+            handle(line, &mut filter)
+        };
+        writeln!(writer, "{reply}")?;
+    }
+}
+
+/// Run one command against `filter` and return the reply line.
+pub fn handle(line: &str, filter: &mut SyncFilter) -> String {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let (enabled, name) = match words.as_slice() {
+        ["status"] => return format!("ok {}", filter.summary()),
+        ["enable", name] => (true, *name),
+        ["disable", name] => (false, *name),
+        _ => return format!("error unknown command `{}`", line.trim()),
+    };
+
+    let groups = match (name, Group::from_name(name)) {
+        ("all", _) => Group::ALL.to_vec(),
+        (_, Some(group)) => vec![group],
+        (_, None) => return format!("error unknown group `{name}`"),
+    };
+    for group in groups {
+        filter.set(group, enabled);
+    }
+    format!("ok {}", filter.summary())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::input::{KeyboardEvent, MouseEvent};
+
+    #[test]
+    fn disabling_a_group_filters_only_its_events() {
+        let mut filter = SyncFilter::default();
+        assert_eq!(
+            handle("disable mouse", &mut filter),
+            "ok keyboard=on touchpad=on mouse=off shortcut=on workspace=on theme=on night_light=on"
+        );
+
+        let mouse = Event::Input(InputEvent::mouse(MouseEvent::LeftHanded(Some(true))));
+        let keyboard = Event::Input(InputEvent::Keyboard(KeyboardEvent::RepeatRate(30)));
+        assert!(!filter.allows(&mouse));
+        assert!(filter.allows(&keyboard));
+
+        handle("enable mouse", &mut filter);
+        assert!(filter.allows(&mouse));
+    }
+
+    #[test]
+    fn all_and_status() {
+        let mut filter = SyncFilter::default();
+        handle("disable all", &mut filter);
+        assert_eq!(
+            handle("status", &mut filter),
+            "ok keyboard=off touchpad=off mouse=off shortcut=off workspace=off theme=off \
+             night_light=off"
+        );
+    }
+
+    #[test]
+    fn bad_commands_leave_the_filter_alone() {
+        let mut filter = SyncFilter::default();
+        assert!(handle("disable trackball", &mut filter).starts_with("error"));
+        assert!(handle("pause", &mut filter).starts_with("error"));
+        assert!(handle("enable", &mut filter).starts_with("error"));
+        assert_eq!(filter, SyncFilter::default());
+    }
+
+    #[test]
+    fn a_subscriber_that_stops_reading_is_dropped() {
+        let (_client, server) = UnixStream::pair().unwrap();
+        let subscribers = Subscribers::default();
+        subscribers.add(server).unwrap();
+
+        // Nothing reads, so the socket buffer fills up and the subscriber is dropped
+        // instead of blocking the main loop.
+        for _ in 0..100_000 {
+            subscribers.broadcast(r#"{"jsonrpc":"2.0","method":"applied"}"#);
+            if subscribers.is_empty() {
+                return;
+            }
+        }
+        panic!("slow subscriber was never dropped");
+    }
+
+    #[test]
+    fn a_subscribed_client_can_still_send_commands() {
+        let (client, server) = UnixStream::pair().unwrap();
+        let control = Control {
+            filter: Default::default(),
+            events: Arc::new(Mutex::new(std::sync::mpsc::channel().0)),
+            backend: Default::default(),
+            subscribers: Subscribers::default(),
+        };
+        let subscription = server.try_clone().unwrap();
+        control.subscribers.add(subscription).unwrap();
+        thread::spawn(move || serve_client(server, &control));
+
+        let mut writer = client.try_clone().unwrap();
+        writeln!(writer, "disable mouse").unwrap();
+        let mut reply = String::new();
+        BufReader::new(client).read_line(&mut reply).unwrap();
+        assert!(reply.contains("mouse=off"), "{reply}");
+    }
+}
@@ -0,0 +1,355 @@
+// Coalesces bursts of events before they reach the compositor.
+//
+// Dragging a slider in cosmic-settings writes every intermediate value. Events are held
+// for a per-category window and a newer event of the same kind replaces the pending
+// one, so only the final value is applied. Categories with a zero window (keyboard by
+// default) pass straight through, as do the latency-sensitive events in
+// `DebounceConfig::immediate` whatever their category's window.
+
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use serde::de::Error as _;
+
+use crate::config::millis;
+use crate::event::catalog::describe;
+use crate::event::{Event, InputEvent, SourcedEvent};
+
+/// Groups of events that share a debounce window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Category {
+    /// Layout, repeat and numlock changes.
+    Keyboard,
+    /// Scroll factor, method and direction.
+    Scroll,
+    /// Pointer acceleration speed and profile.
+    Pointer,
+    /// Remaining touchpad/mouse toggles (tap, left-handed, ...).
+    Device,
+    /// Keybinding changes.
+    Shortcut,
+}
+
+impl Category {
+    pub const ALL: [Category; 5] = [
+        Category::Keyboard,
+        Category::Scroll,
+        Category::Pointer,
+        Category::Device,
+        Category::Shortcut,
+    ];
+
+    pub fn of(event: &Event) -> Category {
+        let name = event.name();
+        if name.starts_with("keyboard_") || name == "numslock_state" {
+            Category::Keyboard
+        } else if name.ends_with("_shortcut") {
+            Category::Shortcut
+        } else if name.contains("scroll") {
+            Category::Scroll
+        } else if name.contains("accel") {
+            Category::Pointer
+        } else {
+            Category::Device
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::Keyboard => "keyboard",
+            Category::Scroll => "scroll",
+            Category::Pointer => "pointer",
+            Category::Device => "device",
+            Category::Shortcut => "shortcut",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Category> {
+        Category::ALL.into_iter().find(|category| category.name() == name)
+    }
+}
+
+/// Events applied as soon as they arrive by default: switching layouts, numlock and
+/// turning a device on or off should feel instant even while sliders are coalesced.
+pub const DEFAULT_IMMEDIATE: &[&str] = &[
+    "keyboard_layout",
+    "keyboard_variant",
+    "keyboard_options",
+    "numslock_state",
+    "touchpad_state",
+    "mouse_state",
+];
+
+/// Debounce window for each category, from the `[debounce]` section of the cosmolith
+/// config as `<category>_ms` keys.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DebounceConfig {
+    #[serde(rename = "keyboard_ms", deserialize_with = "millis")]
+    pub keyboard: Duration,
+    #[serde(rename = "scroll_ms", deserialize_with = "millis")]
+    pub scroll: Duration,
+    #[serde(rename = "pointer_ms", deserialize_with = "millis")]
+    pub pointer: Duration,
+    #[serde(rename = "device_ms", deserialize_with = "millis")]
+    pub device: Duration,
+    #[serde(rename = "shortcut_ms", deserialize_with = "millis")]
+    pub shortcut: Duration,
+    /// Event names (see `Event::name`) that are never debounced.
+    #[serde(deserialize_with = "event_names")]
+    pub immediate: Vec<&'static str>,
+}
+
+/// Event names as listed by `--list-events`; unknown ones are rejected.
+fn event_names<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<&'static str>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|name| {
+            describe(name).map(|info| info.name).ok_or_else(|| {
+                D::Error::custom(format!("unknown event {name:?} (see --list-events)"))
+            })
+        })
+        .collect()
+}
+
+impl Default for DebounceConfig {
+    /// Keyboard changes apply immediately; slider-driven settings get a modest window.
+    fn default() -> Self {
+        Self {
+            keyboard: Duration::ZERO,
+            scroll: Duration::from_millis(150),
+            pointer: Duration::from_millis(150),
+            device: Duration::ZERO,
+            shortcut: Duration::ZERO,
+            immediate: DEFAULT_IMMEDIATE.to_vec(),
+        }
+    }
+}
+
+impl DebounceConfig {
+    pub fn window(&self, category: Category) -> Duration {
+        match category {
+            Category::Keyboard => self.keyboard,
+            Category::Scroll => self.scroll,
+            Category::Pointer => self.pointer,
+            Category::Device => self.device,
+            Category::Shortcut => self.shortcut,
+        }
+    }
+
+    pub fn set_window(&mut self, category: Category, window: Duration) {
+        match category {
+            Category::Keyboard => self.keyboard = window,
+            Category::Scroll => self.scroll = window,
+            Category::Pointer => self.pointer = window,
+            Category::Device => self.device = window,
+            Category::Shortcut => self.shortcut = window,
+        }
+    }
+}
+
+/// Sort a batch so keyboard events come first, then touchpad, then mouse, keeping arrival
+/// order within each device. A single COSMIC change can produce interleaved touchpad and
+/// mouse events; grouping them keeps each device's settings applied together.
+pub fn order_by_device(events: &mut [SourcedEvent]) {
+    events.sort_by_key(|sourced| match &sourced.event {
+        Event::Input(InputEvent::Keyboard(_)) => 0,
+        Event::Input(InputEvent::TouchPad { .. }) => 1,
+        Event::Input(InputEvent::Mouse { .. }) => 2,
+        Event::Shortcut(_)
+        | Event::Workspace(_)
+        | Event::Theme(_)
+        | Event::Cursor(_)
+        | Event::ColorTemp(_) => 3,
+    });
+}
+
+pub struct Debouncer {
+    config: DebounceConfig,
+    // Pending events in arrival order, each with the time it becomes due.
+    pending: Vec<(SourcedEvent, Instant)>,
+}
+
+impl Debouncer {
+    pub fn new(config: DebounceConfig) -> Self {
+        Self {
+            config,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Queue an event. Returns it straight back when it is immediate or its category is
+    /// not debounced.
+    ///
+    /// A pending event is replaced by a newer one with the same name for the same device;
+    /// a setting for one touchpad does not hold back or drop another's.
+    pub fn push(&mut self, event: SourcedEvent, now: Instant) -> Option<SourcedEvent> {
+        let name = event.event.name();
+        if self.config.immediate.contains(&name) {
+            return Some(event);
+        }
+        let window = self.config.window(Category::of(&event.event));
+        if window.is_zero() {
+            return Some(event);
+        }
+
+        // Latest value wins; the window restarts with every new value.
+        let device = event.event.device();
+        self.pending.retain(|(pending, _)| {
+            pending.event.name() != name || pending.event.device() != device
+        });
+        self.pending.push((event, now + window));
+        None
+    }
+
+    /// Remove and return every event whose window has elapsed, grouped by device.
+    pub fn drain_due(&mut self, now: Instant) -> Vec<SourcedEvent> {
+        let (due, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|(_, deadline)| *deadline <= now);
+        self.pending = pending;
+        let mut due: Vec<_> = due.into_iter().map(|(event, _)| event).collect();
+        order_by_device(&mut due);
+        due
+    }
+
+    /// Remove and return every pending event regardless of its window, grouped by device.
+    pub fn flush(&mut self) -> Vec<SourcedEvent> {
+        let mut events: Vec<_> = std::mem::take(&mut self.pending)
+            .into_iter()
+            .map(|(event, _)| event)
+            .collect();
+        order_by_device(&mut events);
+        events
+    }
+
+    /// When the next pending event becomes due, if any.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.pending.iter().map(|(_, deadline)| *deadline).min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmic_comp_config::input::DeviceState;
+
+    use crate::event::{Source, TouchpadEvent};
+
+    const SOURCE: Source = Source {
+        namespace: "com.system76.CosmicComp",
+        key: Some("input_touchpad"),
+    };
+
+    fn speed(device: Option<&str>, speed: f64) -> SourcedEvent {
+        let event = InputEvent::TouchPad {
+            event: TouchpadEvent::AccelSpeed(speed),
+            device: device.map(str::to_string),
+        };
+        SourcedEvent::new(Event::Input(event), SOURCE)
+    }
+
+    fn speeds(events: &[SourcedEvent]) -> Vec<(Option<&str>, f64)> {
+        events
+            .iter()
+            .map(|sourced| match &sourced.event {
+                Event::Input(InputEvent::TouchPad {
+                    event: TouchpadEvent::AccelSpeed(speed),
+                    device,
+                }) => (device.as_deref(), *speed),
+                other => panic!("not a speed: {other:?}"),
+            })
+            .collect()
+    }
+
+    fn scroll_factor(factor: f64) -> SourcedEvent {
+        let event = InputEvent::touchpad(TouchpadEvent::ScrollFactor(Some(factor)));
+        SourcedEvent::new(Event::Input(event), SOURCE)
+    }
+
+    fn config(scroll_ms: u64, pointer_ms: u64) -> DebounceConfig {
+        DebounceConfig {
+            scroll: Duration::from_millis(scroll_ms),
+            pointer: Duration::from_millis(pointer_ms),
+            ..DebounceConfig::default()
+        }
+    }
+
+    #[test]
+    fn a_burst_is_coalesced_into_its_last_value() {
+        let mut debouncer = Debouncer::new(config(100, 100));
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        for (ms, value) in [(0, 0.1), (40, 0.2), (80, 0.3)] {
+            assert!(debouncer.push(speed(None, value), at(ms)).is_none());
+        }
+        // Every new value restarts the window.
+        assert_eq!(debouncer.next_deadline(), Some(at(180)));
+        assert!(debouncer.drain_due(at(179)).is_empty());
+        assert_eq!(speeds(&debouncer.drain_due(at(180))), [(None, 0.3)]);
+        assert_eq!(debouncer.next_deadline(), None);
+    }
+
+    #[test]
+    fn each_category_has_its_own_deadline() {
+        let mut debouncer = Debouncer::new(config(50, 200));
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        assert!(debouncer.push(speed(None, 0.5), start).is_none());
+        assert!(debouncer.push(scroll_factor(2.0), start).is_none());
+        assert_eq!(debouncer.next_deadline(), Some(at(50)));
+
+        let due = debouncer.drain_due(at(50));
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].event.name(), "touchpad_scroll_factor");
+        assert_eq!(debouncer.next_deadline(), Some(at(200)));
+        assert_eq!(speeds(&debouncer.drain_due(at(200))), [(None, 0.5)]);
+    }
+
+    #[test]
+    fn flush_returns_everything_pending() {
+        let mut debouncer = Debouncer::new(config(50, 200));
+        let now = Instant::now();
+        assert!(debouncer.push(speed(None, 0.5), now).is_none());
+        assert!(debouncer.push(scroll_factor(2.0), now).is_none());
+        assert_eq!(debouncer.flush().len(), 2);
+        assert_eq!(debouncer.next_deadline(), None);
+    }
+
+    #[test]
+    fn immediate_events_skip_a_held_window() {
+        let mut debouncer = Debouncer::new(DebounceConfig::default());
+        let now = Instant::now();
+        assert!(debouncer.push(speed(None, 0.5), now).is_none());
+
+        let state = InputEvent::touchpad(TouchpadEvent::State(DeviceState::Disabled));
+        let state = SourcedEvent::new(Event::Input(state), SOURCE);
+        assert!(DEFAULT_IMMEDIATE.contains(&state.event.name()));
+        let passed = debouncer.push(state, now).unwrap();
+        assert_eq!(passed.event.name(), "touchpad_state");
+        // The debounced speed is still held.
+        assert_eq!(speeds(&debouncer.flush()), [(None, 0.5)]);
+    }
+
+    #[test]
+    fn a_zero_window_passes_events_through() {
+        let mut debouncer = Debouncer::new(config(0, 0));
+        assert!(debouncer.push(speed(None, 0.5), Instant::now()).is_some());
+        assert_eq!(debouncer.next_deadline(), None);
+    }
+
+    #[test]
+    fn each_device_keeps_its_own_pending_event() {
+        let mut debouncer = Debouncer::new(DebounceConfig::default());
+        let now = Instant::now();
+        assert!(debouncer.push(speed(Some("dev-a"), 0.1), now).is_none());
+        assert!(debouncer.push(speed(Some("dev-b"), 0.2), now).is_none());
+        assert!(debouncer.push(speed(Some("dev-a"), 0.3), now).is_none());
+        assert_eq!(
+            speeds(&debouncer.flush()),
+            [(Some("dev-b"), 0.2), (Some("dev-a"), 0.3)]
+        );
+    }
+}
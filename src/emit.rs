@@ -0,0 +1,149 @@
+//! Event socket (`emit` feature): every event cosmolith generates, as one JSON line per
+//! event, for scripts that want to react to COSMIC setting changes themselves.
+//!
+//! ```text
+//! $ socat - UNIX-CONNECT:$XDG_RUNTIME_DIR/cosmolith-events.sock
+//! {"name":"touchpad_natural_scroll","source":"com.system76.CosmicComp/input_touchpad","event":{...}}
+//! ```
+//!
+//! Events are sent as they arrive from the watchers, before debouncing and the sync
+//! filter, whether or not a backend applies them. Clients only read; a client that
+//! falls behind is disconnected rather than holding up the daemon.
+
+use std::fs;
+use std::io::{self, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use serde_json::json;
+use tracing::warn;
+
+use crate::event::SourcedEvent;
+
+/// Connected event socket clients.
+#[derive(Clone, Default)]
+pub struct Emitter(Arc<Mutex<Vec<UnixStream>>>);
+
+impl Emitter {
+    fn add(&self, stream: UnixStream) {
+        self.0.lock().unwrap_or_else(|err| err.into_inner()).push(stream);
+    }
+
+    /// Send `sourced` to every client without blocking. Clients that hung up, or whose
+    /// socket buffer is full, are dropped; a partly written line would corrupt the stream.
+    pub fn emit(&self, sourced: &SourcedEvent) {
+        let mut streams = self.0.lock().unwrap_or_else(|err| err.into_inner());
+        if streams.is_empty() {
+            return;
+        }
+        let line = format!("{}\n", event_line(sourced));
+        streams.retain_mut(|stream| match stream.write(line.as_bytes()) {
+            Ok(written) => written == line.len(),
+            Err(_) => false,
+        });
+    }
+}
+
+/// `$XDG_RUNTIME_DIR/cosmolith-events.sock`.
+pub fn default_path() -> Option<PathBuf> {
+    Some(dirs::runtime_dir()?.join("cosmolith-events.sock"))
+}
+
+/// Bind the socket at `path` and accept clients on a background thread.
+///
+/// A leftover socket file from a crashed run is replaced; one that still accepts
+/// connections belongs to another instance and is left alone.
+pub fn serve(path: &Path) -> io::Result<Emitter> {
+    if path.exists() {
+        if UnixStream::connect(path).is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                format!("{} is used by another cosmolith", path.display()),
+            ));
+        }
+        fs::remove_file(path)?;
+    }
+    let listener = UnixListener::bind(path)?;
+    let emitter = Emitter::default();
+
+    let clients = emitter.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else { continue };
+            if let Err(err) = stream.set_nonblocking(true) {
+                warn!("Event socket client error: {err}");
+                continue;
+            }
+            clients.add(stream);
+        }
+    });
+    Ok(emitter)
+}
+
+/// The JSON line sent for `sourced`.
+pub fn event_line(sourced: &SourcedEvent) -> String {
+    json!({
+        "name": sourced.event.name(),
+        "source": sourced.source.to_string(),
+        "event": sourced.event,
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader};
+
+    use super::*;
+    use crate::event::input::{InputEvent, TouchpadEvent};
+    use crate::event::{Event, Source};
+
+    fn sourced() -> SourcedEvent {
+        SourcedEvent::new(
+            Event::Input(InputEvent::touchpad(TouchpadEvent::NaturalScroll(Some(true)))),
+            Source {
+                namespace: "com.system76.CosmicComp",
+                key: Some("input_touchpad"),
+            },
+        )
+    }
+
+    #[test]
+    fn clients_receive_one_line_per_event() {
+        let (client, server) = UnixStream::pair().unwrap();
+        server.set_nonblocking(true).unwrap();
+        let emitter = Emitter::default();
+        emitter.add(server);
+
+        emitter.emit(&sourced());
+        emitter.emit(&sourced());
+
+        let mut lines = BufReader::new(client).lines();
+        for _ in 0..2 {
+            let line: serde_json::Value =
+                serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+            assert_eq!(line["name"], "touchpad_natural_scroll");
+            assert_eq!(line["source"], "com.system76.CosmicComp/input_touchpad");
+        }
+    }
+
+    #[test]
+    fn a_client_that_stops_reading_is_dropped() {
+        let (_client, server) = UnixStream::pair().unwrap();
+        server.set_nonblocking(true).unwrap();
+        let emitter = Emitter::default();
+        emitter.add(server);
+
+        // Nothing reads, so the socket buffer fills up and the client is disconnected
+        // instead of blocking the caller.
+        for _ in 0..100_000 {
+            emitter.emit(&sourced());
+            if emitter.0.lock().unwrap().is_empty() {
+                return;
+            }
+        }
+        panic!("slow client was never dropped");
+    }
+}
@@ -1,7 +1,86 @@
 use thiserror::Error;
 
+/// `Result` with cosmolith's [`Error`]. `CompositorResult` and `InputResult` are
+/// `Result<()>`.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Errors cosmolith raises, and the IPC crate errors backends pass through with `?`.
 #[derive(Error, Debug)]
 pub enum Error {
     // #[error("Cli Error: {0}")]
     // FailedRegisterObject(zbus::Error),
-}
\ No newline at end of file
+    #[error("{var} is not set")]
+    MissingEnvVar { var: &'static str },
+    #[error("{handler} is not implemented by this backend")]
+    NotImplemented { handler: &'static str },
+    #[error("{compositor} does not support {event}; it was not applied")]
+    UnsupportedEvent {
+        compositor: &'static str,
+        event: &'static str,
+    },
+    #[error("unsupported {setting} value {value}; cosmolith does not know this variant yet")]
+    UnsupportedValue { setting: &'static str, value: String },
+    #[error("compositor detection failed: {0}")]
+    DetectionFailed(String),
+    #[error("{backend} backend used before init")]
+    NotInitialized { backend: &'static str },
+    #[error("could not route setting to a device: {reason}")]
+    RoutingFailed { reason: String },
+    #[error(
+        "{backend} IPC failed ({reason}); the compositor reports version {detected} but \
+         cosmolith is built for {expected}. Update cosmolith, or report both versions"
+    )]
+    IpcConnection {
+        backend: &'static str,
+        detected: String,
+        expected: &'static str,
+        reason: String,
+    },
+    #[error("forced {backend} backend could not start: {reason}")]
+    CompositorNotRunning { backend: String, reason: String },
+    #[error("{backend} rejected `{command}`: {reason}")]
+    IpcCommand {
+        backend: &'static str,
+        command: String,
+        reason: String,
+    },
+    #[error("{backend} IPC still failing after {attempts} reconnect attempt(s): {reason}")]
+    IpcReconnectFailed {
+        backend: &'static str,
+        attempts: u32,
+        reason: String,
+    },
+    #[error("could not watch {namespace} ({attempts} failed attempt(s), retrying): {reason}")]
+    WatcherSetup {
+        namespace: &'static str,
+        attempts: u32,
+        reason: String,
+    },
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Sway IPC: {0}")]
+    Sway(#[from] swayipc::Error),
+    #[error("Hyprland IPC: {0}")]
+    Hyprland(#[from] hyprland::shared::HyprError),
+    #[error("D-Bus: {0}")]
+    DBus(#[from] zbus::Error),
+    #[error("GSettings: {0}")]
+    GSettings(#[from] gio::glib::BoolError),
+    #[error(transparent)]
+    Glib(#[from] gio::glib::Error),
+    /// A failure described only by its message, e.g. a command's stderr.
+    #[error("{0}")]
+    Other(String),
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::Other(message)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Self {
+        Error::Other(message.to_string())
+    }
+}
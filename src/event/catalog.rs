@@ -0,0 +1,553 @@
+//! Hand-maintained description of every event the crate can emit.
+//!
+//! Backend authors can print it with `cosmolith --list-events` to see exactly which
+//! handlers exist. The tests below fail when a variant is added without an entry here.
+
+/// Documentation for one event variant.
+#[derive(Debug, Clone, Copy)]
+pub struct EventInfo {
+    /// Stable name, as returned by `Event::name` and listed in `Compositor::capabilities`.
+    pub name: &'static str,
+    /// Enum path of the variant.
+    pub variant: &'static str,
+    /// Payload type carried by the variant.
+    pub payload: &'static str,
+    /// One-line description.
+    pub description: &'static str,
+}
+
+pub const EVENTS: &[EventInfo] = &[
+    EventInfo {
+        name: "keyboard_rules",
+        variant: "KeyboardEvent::Rules",
+        payload: "String",
+        description: "XKB rules file.",
+    },
+    EventInfo {
+        name: "keyboard_model",
+        variant: "KeyboardEvent::Model",
+        payload: "String",
+        description: "Keyboard model.",
+    },
+    EventInfo {
+        name: "keyboard_layout",
+        variant: "KeyboardEvent::Layout",
+        payload: "String",
+        description: "Comma-separated keyboard layout(s).",
+    },
+    EventInfo {
+        name: "keyboard_variant",
+        variant: "KeyboardEvent::Variant",
+        payload: "String",
+        description: "Comma-separated keyboard variant(s).",
+    },
+    EventInfo {
+        name: "keyboard_options",
+        variant: "KeyboardEvent::Options",
+        payload: "Option<String>",
+        description: "XKB options, the full set to apply.",
+    },
+    EventInfo {
+        name: "keyboard_repeat_delay",
+        variant: "KeyboardEvent::RepeatDelay",
+        payload: "u32",
+        description: "Key repeat delay in ms.",
+    },
+    EventInfo {
+        name: "keyboard_repeat_rate",
+        variant: "KeyboardEvent::RepeatRate",
+        payload: "u32",
+        description: "Key repeat rate in Hz.",
+    },
+    EventInfo {
+        name: "numslock_state",
+        variant: "KeyboardEvent::NumLock",
+        payload: "NumlockState",
+        description: "Numlock state at session start (BootOn | BootOff | LastBoot).",
+    },
+    EventInfo {
+        name: "touchpad_state",
+        variant: "TouchpadEvent::State",
+        payload: "DeviceState",
+        description: "Device enable state (Enabled | Disabled | DisabledOnExternalMouse).",
+    },
+    EventInfo {
+        name: "touchpad_acceleration",
+        variant: "TouchpadEvent::Acceleration",
+        payload: "Option<AccelConfig>",
+        description: "Whole acceleration config, when it is set or cleared as a whole; the fine-grained accel events follow.",
+    },
+    EventInfo {
+        name: "touchpad_accel_speed",
+        variant: "TouchpadEvent::AccelSpeed",
+        payload: "f64",
+        description: "Acceleration speed in -1.0..=1.0.",
+    },
+    EventInfo {
+        name: "touchpad_accel_profile",
+        variant: "TouchpadEvent::AccelProfile",
+        payload: "Option<AccelProfile>",
+        description: "Acceleration profile (Flat | Adaptive).",
+    },
+    EventInfo {
+        name: "touchpad_calibration",
+        variant: "TouchpadEvent::Calibration",
+        payload: "Option<[f32; 6]>",
+        description: "Calibration matrix for device coordinates.",
+    },
+    EventInfo {
+        name: "touchpad_click_method",
+        variant: "TouchpadEvent::ClickMethod",
+        payload: "Option<ClickMethod>",
+        description: "Click method (ButtonAreas | Clickfinger).",
+    },
+    EventInfo {
+        name: "touchpad_disable_while_typing",
+        variant: "TouchpadEvent::DisableWhileTyping",
+        payload: "Option<bool>",
+        description: "Ignore the device while typing.",
+    },
+    EventInfo {
+        name: "touchpad_left_handed",
+        variant: "TouchpadEvent::LeftHanded",
+        payload: "Option<bool>",
+        description: "Swap button mapping for left-handed use.",
+    },
+    EventInfo {
+        name: "touchpad_middle_button_emulation",
+        variant: "TouchpadEvent::MiddleButtonEmulation",
+        payload: "Option<bool>",
+        description: "Emulate middle click with left+right click.",
+    },
+    EventInfo {
+        name: "touchpad_rotation_angle",
+        variant: "TouchpadEvent::RotationAngle",
+        payload: "Option<u32>",
+        description: "Rotation angle in degrees.",
+    },
+    EventInfo {
+        name: "touchpad_scroll_config",
+        variant: "TouchpadEvent::ScrollConfig",
+        payload: "Option<ScrollConfig>",
+        description: "Whole scroll configuration, when it is set or cleared as a whole; the fine-grained scroll events follow.",
+    },
+    EventInfo {
+        name: "touchpad_scroll_method",
+        variant: "TouchpadEvent::ScrollMethod",
+        payload: "Option<ScrollMethod>",
+        description: "Scroll method (NoScroll | TwoFinger | Edge | OnButtonDown).",
+    },
+    EventInfo {
+        name: "touchpad_natural_scroll",
+        variant: "TouchpadEvent::NaturalScroll",
+        payload: "Option<bool>",
+        description: "Natural (content follows fingers) scrolling.",
+    },
+    EventInfo {
+        name: "touchpad_scroll_factor",
+        variant: "TouchpadEvent::ScrollFactor",
+        payload: "Option<f64>",
+        description: "Scroll speed multiplier.",
+    },
+    EventInfo {
+        name: "touchpad_scroll_button",
+        variant: "TouchpadEvent::ScrollButton",
+        payload: "Option<u32>",
+        description: "Button used for OnButtonDown scrolling.",
+    },
+    EventInfo {
+        name: "touchpad_tap_config",
+        variant: "TouchpadEvent::TapConfig",
+        payload: "Option<TapConfig>",
+        description: "Whole tap configuration, when it is set or cleared as a whole; the fine-grained tap events follow.",
+    },
+    EventInfo {
+        name: "touchpad_map_to_output",
+        variant: "TouchpadEvent::MapToOutput",
+        payload: "Option<String>",
+        description: "Output name the device is mapped to.",
+    },
+    EventInfo {
+        name: "touchpad_tap_enabled",
+        variant: "TouchpadEvent::TapEnabled",
+        payload: "bool",
+        description: "Tap-to-click.",
+    },
+    EventInfo {
+        name: "touchpad_tap_button_map",
+        variant: "TouchpadEvent::TapButtonMap",
+        payload: "Option<TapButtonMap>",
+        description: "Tap button map (LeftRightMiddle | LeftMiddleRight).",
+    },
+    EventInfo {
+        name: "touchpad_tap_drag",
+        variant: "TouchpadEvent::TapDrag",
+        payload: "bool",
+        description: "Tap-and-drag.",
+    },
+    EventInfo {
+        name: "touchpad_tap_drag_lock",
+        variant: "TouchpadEvent::TapDragLock",
+        payload: "bool",
+        description: "Drag lock after tap-and-drag.",
+    },
+    EventInfo {
+        name: "mouse_state",
+        variant: "MouseEvent::State",
+        payload: "DeviceState",
+        description: "Device enable state (Enabled | Disabled | DisabledOnExternalMouse).",
+    },
+    EventInfo {
+        name: "mouse_acceleration",
+        variant: "MouseEvent::Acceleration",
+        payload: "Option<AccelConfig>",
+        description: "Whole acceleration config, when it is set or cleared as a whole; the fine-grained accel events follow.",
+    },
+    EventInfo {
+        name: "mouse_accel_speed",
+        variant: "MouseEvent::AccelSpeed",
+        payload: "f64",
+        description: "Acceleration speed in -1.0..=1.0.",
+    },
+    EventInfo {
+        name: "mouse_accel_profile",
+        variant: "MouseEvent::AccelProfile",
+        payload: "Option<AccelProfile>",
+        description: "Acceleration profile (Flat | Adaptive).",
+    },
+    EventInfo {
+        name: "mouse_calibration",
+        variant: "MouseEvent::Calibration",
+        payload: "Option<[f32; 6]>",
+        description: "Calibration matrix for device coordinates.",
+    },
+    EventInfo {
+        name: "mouse_click_method",
+        variant: "MouseEvent::ClickMethod",
+        payload: "Option<ClickMethod>",
+        description: "Click method (ButtonAreas | Clickfinger).",
+    },
+    EventInfo {
+        name: "mouse_disable_while_typing",
+        variant: "MouseEvent::DisableWhileTyping",
+        payload: "Option<bool>",
+        description: "Ignore the device while typing.",
+    },
+    EventInfo {
+        name: "mouse_left_handed",
+        variant: "MouseEvent::LeftHanded",
+        payload: "Option<bool>",
+        description: "Swap button mapping for left-handed use.",
+    },
+    EventInfo {
+        name: "mouse_middle_button_emulation",
+        variant: "MouseEvent::MiddleButtonEmulation",
+        payload: "Option<bool>",
+        description: "Emulate middle click with left+right click.",
+    },
+    EventInfo {
+        name: "mouse_rotation_angle",
+        variant: "MouseEvent::RotationAngle",
+        payload: "Option<u32>",
+        description: "Rotation angle in degrees.",
+    },
+    EventInfo {
+        name: "mouse_scroll_config",
+        variant: "MouseEvent::ScrollConfig",
+        payload: "Option<ScrollConfig>",
+        description: "Whole scroll configuration, when it is set or cleared as a whole; the fine-grained scroll events follow.",
+    },
+    EventInfo {
+        name: "mouse_scroll_method",
+        variant: "MouseEvent::ScrollMethod",
+        payload: "Option<ScrollMethod>",
+        description: "Scroll method (NoScroll | TwoFinger | Edge | OnButtonDown).",
+    },
+    EventInfo {
+        name: "mouse_natural_scroll",
+        variant: "MouseEvent::NaturalScroll",
+        payload: "Option<bool>",
+        description: "Natural (content follows fingers) scrolling.",
+    },
+    EventInfo {
+        name: "mouse_scroll_factor",
+        variant: "MouseEvent::ScrollFactor",
+        payload: "Option<f64>",
+        description: "Scroll speed multiplier.",
+    },
+    EventInfo {
+        name: "mouse_scroll_button",
+        variant: "MouseEvent::ScrollButton",
+        payload: "Option<u32>",
+        description: "Button used for OnButtonDown scrolling.",
+    },
+    EventInfo {
+        name: "mouse_tap_config",
+        variant: "MouseEvent::TapConfig",
+        payload: "Option<TapConfig>",
+        description: "Whole tap configuration.",
+    },
+    EventInfo {
+        name: "mouse_map_to_output",
+        variant: "MouseEvent::MapToOutput",
+        payload: "Option<String>",
+        description: "Output name the device is mapped to.",
+    },
+    EventInfo {
+        name: "add_shortcut",
+        variant: "ShortcutEvent::Add",
+        payload: "Shortcut, Binding",
+        description: "Bind a COSMIC shortcut action to a key combination.",
+    },
+    EventInfo {
+        name: "remove_shortcut",
+        variant: "ShortcutEvent::Remove",
+        payload: "Shortcut, Binding",
+        description: "Remove a previously bound key combination.",
+    },
+    EventInfo {
+        name: "workspace_count",
+        variant: "WorkspaceEvent::Count",
+        payload: "u32",
+        description: "Number of workspaces that should exist.",
+    },
+    EventInfo {
+        name: "workspace_names",
+        variant: "WorkspaceEvent::Names",
+        payload: "Vec<String>",
+        description: "Workspace names, starting at workspace 1.",
+    },
+    EventInfo {
+        name: "theme_mode",
+        variant: "ThemeEvent::Mode",
+        payload: "ColorScheme",
+        description: "Light or dark application style.",
+    },
+    EventInfo {
+        name: "cursor_theme",
+        variant: "CursorEvent::Theme",
+        payload: "String",
+        description: "XCursor theme name.",
+    },
+    EventInfo {
+        name: "cursor_size",
+        variant: "CursorEvent::Size",
+        payload: "u32",
+        description: "Cursor size in pixels.",
+    },
+    EventInfo {
+        name: "color_temp",
+        variant: "ColorTempEvent",
+        payload: "temperature: u32, enabled: bool",
+        description: "Night light on or off, and its color temperature in Kelvin.",
+    },
+];
+
+/// Look up the catalog entry for an event name.
+pub fn describe(name: &str) -> Option<&'static EventInfo> {
+    EVENTS.iter().find(|info| info.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use cosmic_comp_config::NumlockState;
+    use cosmic_comp_config::input::DeviceState;
+
+    use super::*;
+    use crate::event::color_temp::ColorTempEvent;
+    use crate::event::cursor::CursorEvent;
+    use crate::event::input::{InputEvent, KeyboardEvent, MouseEvent, TouchpadEvent};
+    use crate::event::shortcuts::ShortcutEvent;
+    use crate::event::theme::{ColorScheme, ThemeEvent};
+    use crate::event::workspaces::WorkspaceEvent;
+
+    // Exhaustive on purpose: a new variant stops this from compiling until it is added
+    // to `samples()` (and therefore checked against `EVENTS`).
+    #[allow(dead_code)]
+    fn covered(
+        event: &InputEvent,
+        shortcut: &ShortcutEvent,
+        workspace: &WorkspaceEvent,
+        theme: &ThemeEvent,
+        cursor: &CursorEvent,
+        color_temp: &ColorTempEvent,
+    ) {
+        match event {
+            InputEvent::Keyboard(ev) => match ev {
+                KeyboardEvent::Rules(_)
+                | KeyboardEvent::Model(_)
+                | KeyboardEvent::Layout(_)
+                | KeyboardEvent::Variant(_)
+                | KeyboardEvent::Options(_)
+                | KeyboardEvent::RepeatDelay(_)
+                | KeyboardEvent::RepeatRate(_)
+                | KeyboardEvent::NumLock(_) => {}
+            },
+            InputEvent::TouchPad { event: ev, .. } => match ev {
+                TouchpadEvent::State(_)
+                | TouchpadEvent::Acceleration(_)
+                | TouchpadEvent::AccelSpeed(_)
+                | TouchpadEvent::AccelProfile(_)
+                | TouchpadEvent::Calibration(_)
+                | TouchpadEvent::ClickMethod(_)
+                | TouchpadEvent::DisableWhileTyping(_)
+                | TouchpadEvent::LeftHanded(_)
+                | TouchpadEvent::MiddleButtonEmulation(_)
+                | TouchpadEvent::RotationAngle(_)
+                | TouchpadEvent::ScrollConfig(_)
+                | TouchpadEvent::TapConfig(_)
+                | TouchpadEvent::MapToOutput(_)
+                | TouchpadEvent::ScrollMethod(_)
+                | TouchpadEvent::NaturalScroll(_)
+                | TouchpadEvent::ScrollFactor(_)
+                | TouchpadEvent::ScrollButton(_)
+                | TouchpadEvent::TapEnabled(_)
+                | TouchpadEvent::TapButtonMap(_)
+                | TouchpadEvent::TapDrag(_)
+                | TouchpadEvent::TapDragLock(_) => {}
+            },
+            InputEvent::Mouse { event: ev, .. } => match ev {
+                MouseEvent::State(_)
+                | MouseEvent::Acceleration(_)
+                | MouseEvent::AccelSpeed(_)
+                | MouseEvent::AccelProfile(_)
+                | MouseEvent::Calibration(_)
+                | MouseEvent::ClickMethod(_)
+                | MouseEvent::DisableWhileTyping(_)
+                | MouseEvent::LeftHanded(_)
+                | MouseEvent::MiddleButtonEmulation(_)
+                | MouseEvent::RotationAngle(_)
+                | MouseEvent::ScrollConfig(_)
+                | MouseEvent::TapConfig(_)
+                | MouseEvent::MapToOutput(_)
+                | MouseEvent::ScrollMethod(_)
+                | MouseEvent::NaturalScroll(_)
+                | MouseEvent::ScrollFactor(_)
+                | MouseEvent::ScrollButton(_) => {}
+            },
+        }
+        match shortcut {
+            ShortcutEvent::Add { .. } | ShortcutEvent::Remove { .. } => {}
+        }
+        match workspace {
+            WorkspaceEvent::Count(_) | WorkspaceEvent::Names(_) => {}
+        }
+        match theme {
+            ThemeEvent::Mode(_) => {}
+        }
+        match cursor {
+            CursorEvent::Theme(_) | CursorEvent::Size(_) => {}
+        }
+        let ColorTempEvent { .. } = color_temp;
+    }
+
+    fn workspace_samples() -> Vec<WorkspaceEvent> {
+        vec![WorkspaceEvent::Count(0), WorkspaceEvent::Names(Vec::new())]
+    }
+
+    fn theme_samples() -> Vec<ThemeEvent> {
+        vec![ThemeEvent::Mode(ColorScheme::Dark)]
+    }
+
+    fn cursor_samples() -> Vec<CursorEvent> {
+        vec![CursorEvent::Theme(String::new()), CursorEvent::Size(0)]
+    }
+
+    fn color_temp_sample() -> ColorTempEvent {
+        ColorTempEvent {
+            temperature: 0,
+            enabled: false,
+        }
+    }
+
+    fn samples() -> Vec<InputEvent> {
+        vec![
+            InputEvent::Keyboard(KeyboardEvent::Rules(String::new())),
+            InputEvent::Keyboard(KeyboardEvent::Model(String::new())),
+            InputEvent::Keyboard(KeyboardEvent::Layout(String::new())),
+            InputEvent::Keyboard(KeyboardEvent::Variant(String::new())),
+            InputEvent::Keyboard(KeyboardEvent::Options(None)),
+            InputEvent::Keyboard(KeyboardEvent::RepeatDelay(0)),
+            InputEvent::Keyboard(KeyboardEvent::RepeatRate(0)),
+            InputEvent::Keyboard(KeyboardEvent::NumLock(NumlockState::BootOff)),
+            InputEvent::touchpad(TouchpadEvent::State(DeviceState::Enabled)),
+            InputEvent::touchpad(TouchpadEvent::Acceleration(None)),
+            InputEvent::touchpad(TouchpadEvent::AccelSpeed(0.0)),
+            InputEvent::touchpad(TouchpadEvent::AccelProfile(None)),
+            InputEvent::touchpad(TouchpadEvent::Calibration(None)),
+            InputEvent::touchpad(TouchpadEvent::ClickMethod(None)),
+            InputEvent::touchpad(TouchpadEvent::DisableWhileTyping(None)),
+            InputEvent::touchpad(TouchpadEvent::LeftHanded(None)),
+            InputEvent::touchpad(TouchpadEvent::MiddleButtonEmulation(None)),
+            InputEvent::touchpad(TouchpadEvent::RotationAngle(None)),
+            InputEvent::touchpad(TouchpadEvent::ScrollConfig(None)),
+            InputEvent::touchpad(TouchpadEvent::TapConfig(None)),
+            InputEvent::touchpad(TouchpadEvent::MapToOutput(None)),
+            InputEvent::touchpad(TouchpadEvent::ScrollMethod(None)),
+            InputEvent::touchpad(TouchpadEvent::NaturalScroll(None)),
+            InputEvent::touchpad(TouchpadEvent::ScrollFactor(None)),
+            InputEvent::touchpad(TouchpadEvent::ScrollButton(None)),
+            InputEvent::touchpad(TouchpadEvent::TapEnabled(false)),
+            InputEvent::touchpad(TouchpadEvent::TapButtonMap(None)),
+            InputEvent::touchpad(TouchpadEvent::TapDrag(false)),
+            InputEvent::touchpad(TouchpadEvent::TapDragLock(false)),
+            InputEvent::mouse(MouseEvent::State(DeviceState::Enabled)),
+            InputEvent::mouse(MouseEvent::Acceleration(None)),
+            InputEvent::mouse(MouseEvent::AccelSpeed(0.0)),
+            InputEvent::mouse(MouseEvent::AccelProfile(None)),
+            InputEvent::mouse(MouseEvent::Calibration(None)),
+            InputEvent::mouse(MouseEvent::ClickMethod(None)),
+            InputEvent::mouse(MouseEvent::DisableWhileTyping(None)),
+            InputEvent::mouse(MouseEvent::LeftHanded(None)),
+            InputEvent::mouse(MouseEvent::MiddleButtonEmulation(None)),
+            InputEvent::mouse(MouseEvent::RotationAngle(None)),
+            InputEvent::mouse(MouseEvent::ScrollConfig(None)),
+            InputEvent::mouse(MouseEvent::TapConfig(None)),
+            InputEvent::mouse(MouseEvent::MapToOutput(None)),
+            InputEvent::mouse(MouseEvent::ScrollMethod(None)),
+            InputEvent::mouse(MouseEvent::NaturalScroll(None)),
+            InputEvent::mouse(MouseEvent::ScrollFactor(None)),
+            InputEvent::mouse(MouseEvent::ScrollButton(None)),
+        ]
+    }
+
+    #[test]
+    fn every_variant_is_described() {
+        for event in samples() {
+            let info = describe(event.name());
+            assert!(info.is_some(), "missing catalog entry for {}", event.name());
+            assert!(!info.unwrap().description.is_empty());
+        }
+        assert!(describe("add_shortcut").is_some());
+        assert!(describe("remove_shortcut").is_some());
+        for event in workspace_samples() {
+            assert!(describe(event.name()).is_some(), "missing catalog entry for {}", event.name());
+        }
+        for event in theme_samples() {
+            assert!(describe(event.name()).is_some(), "missing catalog entry for {}", event.name());
+        }
+        for event in cursor_samples() {
+            assert!(describe(event.name()).is_some(), "missing catalog entry for {}", event.name());
+        }
+        assert!(describe(color_temp_sample().name()).is_some());
+    }
+
+    #[test]
+    fn catalog_has_no_stale_or_duplicate_entries() {
+        let mut names: HashSet<&str> = samples().iter().map(|event| event.name()).collect();
+        names.insert("add_shortcut");
+        names.insert("remove_shortcut");
+        names.extend(workspace_samples().iter().map(|event| event.name()));
+        names.extend(theme_samples().iter().map(|event| event.name()));
+        names.extend(cursor_samples().iter().map(|event| event.name()));
+        names.insert(color_temp_sample().name());
+
+        let mut seen = HashSet::new();
+        for info in EVENTS {
+            assert!(seen.insert(info.name), "duplicate catalog entry {}", info.name);
+            assert!(names.contains(info.name), "stale catalog entry {}", info.name);
+        }
+    }
+}
@@ -0,0 +1,17 @@
+/// Night light settings from `com.system76.CosmicNightLight`.
+///
+/// Enabling and the temperature arrive together: a backend that starts a night light
+/// needs the temperature anyway, and one that turns it off ignores it.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColorTempEvent {
+    /// Color temperature in Kelvin while night light is on.
+    pub temperature: u32,
+    pub enabled: bool,
+}
+
+impl ColorTempEvent {
+    pub fn name(&self) -> &'static str {
+        "color_temp"
+    }
+}
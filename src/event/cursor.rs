@@ -0,0 +1,21 @@
+/// Cursor settings from `com.system76.CosmicTk`.
+///
+/// Theme and size are separate events, but some backends can only set both at once;
+/// they keep the last value of each (see `compositor::theme::Cursor`).
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CursorEvent {
+    /// XCursor theme name, e.g. `Pop` or `Adwaita`.
+    Theme(String),
+    /// Nominal cursor size in pixels.
+    Size(u32),
+}
+
+impl CursorEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CursorEvent::Theme(_) => "cursor_theme",
+            CursorEvent::Size(_) => "cursor_size",
+        }
+    }
+}
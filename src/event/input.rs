@@ -1,18 +1,33 @@
 use cosmic_comp_config::{XkbConfig, KeyboardConfig, NumlockState};
 use cosmic_comp_config::input::{
-    AccelConfig, ClickMethod, DeviceState, InputConfig, ScrollConfig, ScrollMethod, TapButtonMap,
+    AccelConfig, AccelProfile, ClickMethod, DeviceState, InputConfig, ScrollConfig, ScrollMethod, TapButtonMap,
     TapConfig,
 };
+use tracing::warn;
 
 use super::Event;
 
+#[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Clone, Debug, PartialEq)]
 pub enum InputEvent {
-    TouchPad(TouchpadEvent),
-    Mouse(MouseEvent),
+    TouchPad {
+        event: TouchpadEvent,
+        /// Device to apply to (a Sway input identifier or Hyprland device name); `None`
+        /// applies to every touchpad. COSMIC only has per-class settings today, so the
+        /// watcher always sends `None`. Backends without device targeting ignore it.
+        #[serde(default)]
+        device: Option<String>,
+    },
+    Mouse {
+        event: MouseEvent,
+        /// Same as for `TouchPad`, for mice.
+        #[serde(default)]
+        device: Option<String>,
+    },
     Keyboard(KeyboardEvent),
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Clone, Debug, PartialEq)]
 pub enum KeyboardEvent {
     /// XKB rules file.
@@ -24,6 +39,8 @@ pub enum KeyboardEvent {
     /// Keyboard variant(s).
     Variant(String),
     /// XKB options.
+    /// Always the full option set: backends replace what is configured, and `None` or an
+    /// empty string clears every option.
     Options(Option<String>),
     /// Key repeat delay in ms.
     RepeatDelay(u32),
@@ -34,6 +51,7 @@ pub enum KeyboardEvent {
     NumLock(NumlockState),
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Clone, Debug, PartialEq)]
 pub enum TouchpadEvent {
     /// Touchpad enable state.
@@ -41,6 +59,10 @@ pub enum TouchpadEvent {
     State(DeviceState),
     /// Acceleration settings.
     /// profile: AccelProfile::Flat | AccelProfile::Adaptive.
+    ///
+    /// Only sent when the config is set or cleared as a whole, and then alongside
+    /// AccelSpeed/AccelProfile for the fields that differ from libinput's defaults;
+    /// changes to an existing config arrive as those alone.
     Acceleration(Option<AccelConfig>),
     /// Calibration matrix for touchpad coordinates.
     Calibration(Option<[f32; 6]>),
@@ -61,18 +83,26 @@ pub enum TouchpadEvent {
     /// Scroll configuration.
     /// ScrollMethod::NoScroll | TwoFinger | Edge | OnButtonDown.
     ///
-    /// TODO: Redundant when all sub-field events (ScrollMethod/NaturalScroll/ScrollFactor/ScrollButton)
-    /// are emitted. IPC handlers should ignore this if equivalent fine-grained events are present.
+    /// Only sent when the config is set or cleared as a whole, and then alongside
+    /// ScrollMethod/NaturalScroll/ScrollFactor/ScrollButton for the fields that differ
+    /// from unset; changes to an existing config arrive as those alone.
     ScrollConfig(Option<ScrollConfig>),
     /// Tap configuration.
     /// TapButtonMap::LeftRightMiddle | LeftMiddleRight.
     ///
-    /// TODO: Redundant when all sub-field events (TapEnabled/TapButtonMap/TapDrag/TapDragLock)
-    /// are emitted. IPC handlers should ignore this if equivalent fine-grained events are present.
+    /// Only sent when the config is set or cleared as a whole, and then alongside
+    /// TapEnabled/TapButtonMap/TapDrag/TapDragLock for the fields that differ from
+    /// libinput's defaults; changes to an existing config arrive as those alone.
     TapConfig(Option<TapConfig>),
     /// Map to output name (display ID).
     MapToOutput(Option<String>),
 
+    /// Acceleration speed only, in libinput's -1.0..=1.0 range.
+    AccelSpeed(f64),
+    /// Acceleration profile only.
+    /// AccelProfile::Flat | AccelProfile::Adaptive.
+    AccelProfile(Option<AccelProfile>),
+
     /// Scroll method only.
     ScrollMethod(Option<ScrollMethod>),
     /// Natural scroll.
@@ -97,6 +127,7 @@ pub enum TouchpadEvent {
     TapDragLock(bool),
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Clone, Debug, PartialEq)]
 pub enum MouseEvent {
     /// Mouse enable state.
@@ -104,6 +135,10 @@ pub enum MouseEvent {
     State(DeviceState),
     /// Acceleration settings.
     /// profile: AccelProfile::Flat | AccelProfile::Adaptive.
+    ///
+    /// Only sent when the config is set or cleared as a whole, and then alongside
+    /// AccelSpeed/AccelProfile for the fields that differ from libinput's defaults;
+    /// changes to an existing config arrive as those alone.
     Acceleration(Option<AccelConfig>),
     /// Calibration matrix for mouse coordinates.
     Calibration(Option<[f32; 6]>),
@@ -124,18 +159,22 @@ pub enum MouseEvent {
     /// Scroll configuration.
     /// ScrollMethod::NoScroll | TwoFinger | Edge | OnButtonDown.
     ///
-    /// TODO: Redundant when all sub-field events (ScrollMethod/NaturalScroll/ScrollFactor/ScrollButton)
-    /// are emitted. IPC handlers should ignore this if equivalent fine-grained events are present.
+    /// Only sent when the config is set or cleared as a whole, and then alongside
+    /// ScrollMethod/NaturalScroll/ScrollFactor/ScrollButton for the fields that differ
+    /// from unset; changes to an existing config arrive as those alone.
     ScrollConfig(Option<ScrollConfig>),
     /// Tap configuration.
     /// TapButtonMap::LeftRightMiddle | LeftMiddleRight.
-    ///
-    /// TODO: Redundant when all sub-field events are emitted. IPC handlers should ignore this
-    /// if equivalent fine-grained events are present.
     TapConfig(Option<TapConfig>),
     /// Map to output name (display ID).
     MapToOutput(Option<String>),
 
+    /// Acceleration speed only, in libinput's -1.0..=1.0 range.
+    AccelSpeed(f64),
+    /// Acceleration profile only.
+    /// AccelProfile::Flat | AccelProfile::Adaptive.
+    AccelProfile(Option<AccelProfile>),
+
     /// Scroll method only.
     ScrollMethod(Option<ScrollMethod>),
     /// Natural scroll.
@@ -147,6 +186,110 @@ pub enum MouseEvent {
     ScrollButton(Option<u32>),
 }
 
+impl InputEvent {
+    /// A touchpad event for every touchpad.
+    pub fn touchpad(event: TouchpadEvent) -> InputEvent {
+        InputEvent::TouchPad {
+            event,
+            device: None,
+        }
+    }
+
+    /// A mouse event for every mouse.
+    pub fn mouse(event: MouseEvent) -> InputEvent {
+        InputEvent::Mouse {
+            event,
+            device: None,
+        }
+    }
+
+    /// The device the event is limited to, if any.
+    pub fn device(&self) -> Option<&str> {
+        match self {
+            InputEvent::TouchPad { device, .. } | InputEvent::Mouse { device, .. } => {
+                device.as_deref()
+            }
+            InputEvent::Keyboard(_) => None,
+        }
+    }
+
+    /// Stable name of the event, matching the `Input` handler it is routed to.
+    pub fn name(&self) -> &'static str {
+        match self {
+            InputEvent::TouchPad { event: ev, .. } => ev.name(),
+            InputEvent::Mouse { event: ev, .. } => ev.name(),
+            InputEvent::Keyboard(ev) => ev.name(),
+        }
+    }
+}
+
+impl KeyboardEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeyboardEvent::Rules(_) => "keyboard_rules",
+            KeyboardEvent::Model(_) => "keyboard_model",
+            KeyboardEvent::Layout(_) => "keyboard_layout",
+            KeyboardEvent::Variant(_) => "keyboard_variant",
+            KeyboardEvent::Options(_) => "keyboard_options",
+            KeyboardEvent::RepeatDelay(_) => "keyboard_repeat_delay",
+            KeyboardEvent::RepeatRate(_) => "keyboard_repeat_rate",
+            KeyboardEvent::NumLock(_) => "numslock_state",
+        }
+    }
+}
+
+impl TouchpadEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TouchpadEvent::State(_) => "touchpad_state",
+            TouchpadEvent::Acceleration(_) => "touchpad_acceleration",
+            TouchpadEvent::Calibration(_) => "touchpad_calibration",
+            TouchpadEvent::ClickMethod(_) => "touchpad_click_method",
+            TouchpadEvent::DisableWhileTyping(_) => "touchpad_disable_while_typing",
+            TouchpadEvent::LeftHanded(_) => "touchpad_left_handed",
+            TouchpadEvent::MiddleButtonEmulation(_) => "touchpad_middle_button_emulation",
+            TouchpadEvent::RotationAngle(_) => "touchpad_rotation_angle",
+            TouchpadEvent::ScrollConfig(_) => "touchpad_scroll_config",
+            TouchpadEvent::TapConfig(_) => "touchpad_tap_config",
+            TouchpadEvent::MapToOutput(_) => "touchpad_map_to_output",
+            TouchpadEvent::AccelSpeed(_) => "touchpad_accel_speed",
+            TouchpadEvent::AccelProfile(_) => "touchpad_accel_profile",
+            TouchpadEvent::ScrollMethod(_) => "touchpad_scroll_method",
+            TouchpadEvent::NaturalScroll(_) => "touchpad_natural_scroll",
+            TouchpadEvent::ScrollFactor(_) => "touchpad_scroll_factor",
+            TouchpadEvent::ScrollButton(_) => "touchpad_scroll_button",
+            TouchpadEvent::TapEnabled(_) => "touchpad_tap_enabled",
+            TouchpadEvent::TapButtonMap(_) => "touchpad_tap_button_map",
+            TouchpadEvent::TapDrag(_) => "touchpad_tap_drag",
+            TouchpadEvent::TapDragLock(_) => "touchpad_tap_drag_lock",
+        }
+    }
+}
+
+impl MouseEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MouseEvent::State(_) => "mouse_state",
+            MouseEvent::Acceleration(_) => "mouse_acceleration",
+            MouseEvent::Calibration(_) => "mouse_calibration",
+            MouseEvent::ClickMethod(_) => "mouse_click_method",
+            MouseEvent::DisableWhileTyping(_) => "mouse_disable_while_typing",
+            MouseEvent::LeftHanded(_) => "mouse_left_handed",
+            MouseEvent::MiddleButtonEmulation(_) => "mouse_middle_button_emulation",
+            MouseEvent::RotationAngle(_) => "mouse_rotation_angle",
+            MouseEvent::ScrollConfig(_) => "mouse_scroll_config",
+            MouseEvent::TapConfig(_) => "mouse_tap_config",
+            MouseEvent::MapToOutput(_) => "mouse_map_to_output",
+            MouseEvent::AccelSpeed(_) => "mouse_accel_speed",
+            MouseEvent::AccelProfile(_) => "mouse_accel_profile",
+            MouseEvent::ScrollMethod(_) => "mouse_scroll_method",
+            MouseEvent::NaturalScroll(_) => "mouse_natural_scroll",
+            MouseEvent::ScrollFactor(_) => "mouse_scroll_factor",
+            MouseEvent::ScrollButton(_) => "mouse_scroll_button",
+        }
+    }
+}
+
 impl TouchpadEvent {
     // #todo: convert it to a &self methods pub fn from(&self, new: InputConfig) -> Vec<Event> where &self is the old config
     // I am unable to decide good name so leaving it :)
@@ -158,136 +301,158 @@ impl TouchpadEvent {
         let mut events = Vec::new();
 
         if old.state != new.state {
-            // Unreachable: cosmic-settings currently does not produce this event
-            let event = Event::Input(InputEvent::TouchPad(TouchpadEvent::State(new.state)));
+            // cosmic-settings rarely changes this, but the startup sync emits any stored
+            // state that differs from the default.
+            let event = Event::Input(InputEvent::touchpad(TouchpadEvent::State(new.state)));
             events.push(event);
         }
         if old.acceleration != new.acceleration {
-            let event = Event::Input(InputEvent::TouchPad(TouchpadEvent::Acceleration(
-                new.acceleration.clone(),
-            )));
-            events.push(event);
+            if old.acceleration.is_none() || new.acceleration.is_none() {
+                // Set or cleared as a whole, as for the scroll config: a backend that
+                // rewrites the profile from it must not get it for a speed-only change.
+                let event = Event::Input(InputEvent::touchpad(TouchpadEvent::Acceleration(
+                    new.acceleration.clone(),
+                )));
+                events.push(event);
+            }
+
+            // COSMIC's "Acceleration" toggle writes the profile (see
+            // `libinput::AccelProfile::from_enabled`), often into a config that had no
+            // acceleration yet; compare against libinput's default so it still arrives
+            // as a profile change.
+            let old_accel = accel_or_default(old.acceleration);
+            let new_accel = accel_or_default(new.acceleration.clone());
+            if old_accel.speed != new_accel.speed {
+                let event = Event::Input(InputEvent::touchpad(TouchpadEvent::AccelSpeed(
+                    new_accel.speed,
+                )));
+                events.push(event);
+            }
+            if old_accel.profile != new_accel.profile {
+                let event = Event::Input(InputEvent::touchpad(TouchpadEvent::AccelProfile(
+                    new_accel.profile,
+                )));
+                events.push(event);
+            }
         }
         if old.calibration != new.calibration {
             // Unreachable: cosmic-settings currently does not produce this event
-            let event = Event::Input(InputEvent::TouchPad(TouchpadEvent::Calibration(
+            let event = Event::Input(InputEvent::touchpad(TouchpadEvent::Calibration(
                 new.calibration,
             )));
             events.push(event);
         }
         if old.click_method != new.click_method {
-            let event = Event::Input(InputEvent::TouchPad(TouchpadEvent::ClickMethod(
+            let event = Event::Input(InputEvent::touchpad(TouchpadEvent::ClickMethod(
                 new.click_method,
             )));
             events.push(event);
         }
         if old.disable_while_typing != new.disable_while_typing {
-            let event = Event::Input(InputEvent::TouchPad(TouchpadEvent::DisableWhileTyping(
+            let event = Event::Input(InputEvent::touchpad(TouchpadEvent::DisableWhileTyping(
                 new.disable_while_typing,
             )));
             events.push(event);
         }
         if old.left_handed != new.left_handed {
-            let event = Event::Input(InputEvent::TouchPad(TouchpadEvent::LeftHanded(
+            let event = Event::Input(InputEvent::touchpad(TouchpadEvent::LeftHanded(
                 new.left_handed,
             )));
             events.push(event);
         }
         if old.middle_button_emulation != new.middle_button_emulation {
             // Unreachable: cosmic-settings currently does not produce this event
-            let event = Event::Input(InputEvent::TouchPad(TouchpadEvent::MiddleButtonEmulation(
+            let event = Event::Input(InputEvent::touchpad(TouchpadEvent::MiddleButtonEmulation(
                 new.middle_button_emulation,
             )));
             events.push(event);
         }
         if old.rotation_angle != new.rotation_angle {
             // Unreachable: cosmic-settings currently does not produce this event
-            let event = Event::Input(InputEvent::TouchPad(TouchpadEvent::RotationAngle(
+            let event = Event::Input(InputEvent::touchpad(TouchpadEvent::RotationAngle(
                 new.rotation_angle,
             )));
             events.push(event);
         }
         if old.scroll_config != new.scroll_config {
-            let event = Event::Input(InputEvent::TouchPad(TouchpadEvent::ScrollConfig(
-                new.scroll_config.clone(),
-            )));
-
-            // TODO: Redundant when all sub-field events are emitted. IPC handlers should ignore this
-            // if equivalent fine-grained events are present.
-            events.push(event);
-
-            if let (Some(old_scroll), Some(new_scroll)) =
-                (old.scroll_config, new.scroll_config.clone())
-            {
-                if old_scroll.method != new_scroll.method {
-                    let event = Event::Input(InputEvent::TouchPad(TouchpadEvent::ScrollMethod(
-                        new_scroll.method,
-                    )));
-                    events.push(event);
-                }
-                if old_scroll.natural_scroll != new_scroll.natural_scroll {
-                    let event = Event::Input(InputEvent::TouchPad(TouchpadEvent::NaturalScroll(
-                        new_scroll.natural_scroll,
-                    )));
-                    events.push(event);
-                }
-                if old_scroll.scroll_button != new_scroll.scroll_button {
-                    // Unreachable: cosmic-settings currently does not produce this event
-                    let event = Event::Input(InputEvent::TouchPad(TouchpadEvent::ScrollButton(
-                        new_scroll.scroll_button,
-                    )));
-                    events.push(event);
-                }
-                if old_scroll.scroll_factor != new_scroll.scroll_factor {
-                    let event = Event::Input(InputEvent::TouchPad(TouchpadEvent::ScrollFactor(
-                        new_scroll.scroll_factor,
-                    )));
-                    events.push(event);
-                }
+            if old.scroll_config.is_none() || new.scroll_config.is_none() {
+                // Set or cleared as a whole: backends that take the whole config get it
+                // here, the fields below reach the ones that only handle single settings.
+                let event = Event::Input(InputEvent::touchpad(TouchpadEvent::ScrollConfig(
+                    new.scroll_config.clone(),
+                )));
+                events.push(event);
+            }
+            let old_scroll = scroll_or_unset(old.scroll_config);
+            let new_scroll = scroll_or_unset(new.scroll_config.clone());
+            if old_scroll.method != new_scroll.method {
+                let event = Event::Input(InputEvent::touchpad(TouchpadEvent::ScrollMethod(
+                    new_scroll.method,
+                )));
+                events.push(event);
+            }
+            if old_scroll.natural_scroll != new_scroll.natural_scroll {
+                let event = Event::Input(InputEvent::touchpad(TouchpadEvent::NaturalScroll(
+                    new_scroll.natural_scroll,
+                )));
+                events.push(event);
+            }
+            if old_scroll.scroll_button != new_scroll.scroll_button {
+                // Unreachable: cosmic-settings currently does not produce this event
+                let event = Event::Input(InputEvent::touchpad(TouchpadEvent::ScrollButton(
+                    new_scroll.scroll_button,
+                )));
+                events.push(event);
+            }
+            if old_scroll.scroll_factor != new_scroll.scroll_factor {
+                let event = Event::Input(InputEvent::touchpad(TouchpadEvent::ScrollFactor(
+                    new_scroll.scroll_factor,
+                )));
+                events.push(event);
             }
         }
 
         if old.tap_config != new.tap_config {
-            let event = Event::Input(InputEvent::TouchPad(TouchpadEvent::TapConfig(
-                new.tap_config.clone(),
-            )));
-
-            // TODO: Redundant when all sub-field events are emitted. IPC handlers should ignore this
-            // if equivalent fine-grained events are present.
-            events.push(event);
-
-            if let (Some(old_tap), Some(new_tap)) = (old.tap_config, new.tap_config.clone()) {
-                if old_tap.enabled != new_tap.enabled {
-                    let event = Event::Input(InputEvent::TouchPad(TouchpadEvent::TapEnabled(
-                        new_tap.enabled,
-                    )));
-                    events.push(event);
-                }
-                if old_tap.button_map != new_tap.button_map {
-                    // Unreachable: cosmic-settings currently does not produce this event
-                    let event = Event::Input(InputEvent::TouchPad(TouchpadEvent::TapButtonMap(
-                        new_tap.button_map,
-                    )));
-                    events.push(event);
-                }
-                if old_tap.drag != new_tap.drag {
-                    // Unreachable: cosmic-settings currently does not produce this event
-                    let event =
-                        Event::Input(InputEvent::TouchPad(TouchpadEvent::TapDrag(new_tap.drag)));
-                    events.push(event);
-                }
-                if old_tap.drag_lock != new_tap.drag_lock {
-                    // Unreachable: cosmic-settings currently does not produce this event
-                    let event = Event::Input(InputEvent::TouchPad(TouchpadEvent::TapDragLock(
-                        new_tap.drag_lock,
-                    )));
-                    events.push(event);
-                }
+            if old.tap_config.is_none() || new.tap_config.is_none() {
+                // Set or cleared as a whole: backends that take the whole config get it
+                // here, the fields below reach the ones that only handle single settings.
+                let event = Event::Input(InputEvent::touchpad(TouchpadEvent::TapConfig(
+                    new.tap_config.clone(),
+                )));
+                events.push(event);
+            }
+            let old_tap = tap_or_default(old.tap_config);
+            let new_tap = tap_or_default(new.tap_config.clone());
+            if old_tap.enabled != new_tap.enabled {
+                let event = Event::Input(InputEvent::touchpad(TouchpadEvent::TapEnabled(
+                    new_tap.enabled,
+                )));
+                events.push(event);
+            }
+            if old_tap.button_map != new_tap.button_map {
+                // Unreachable: cosmic-settings currently does not produce this event
+                let event = Event::Input(InputEvent::touchpad(TouchpadEvent::TapButtonMap(
+                    new_tap.button_map,
+                )));
+                events.push(event);
+            }
+            if old_tap.drag != new_tap.drag {
+                // Unreachable: cosmic-settings currently does not produce this event
+                let event =
+                    Event::Input(InputEvent::touchpad(TouchpadEvent::TapDrag(new_tap.drag)));
+                events.push(event);
+            }
+            if old_tap.drag_lock != new_tap.drag_lock {
+                // Unreachable: cosmic-settings currently does not produce this event
+                let event = Event::Input(InputEvent::touchpad(TouchpadEvent::TapDragLock(
+                    new_tap.drag_lock,
+                )));
+                events.push(event);
             }
         }
         if old.map_to_output != new.map_to_output {
             // Unreachable: cosmic-settings currently does not produce this event
-            let event = Event::Input(InputEvent::TouchPad(TouchpadEvent::MapToOutput(
+            let event = Event::Input(InputEvent::touchpad(TouchpadEvent::MapToOutput(
                 new.map_to_output,
             )));
             events.push(event);
@@ -308,104 +473,120 @@ impl MouseEvent {
         let mut events = Vec::new();
 
         if old.state != new.state {
-            // Unreachable: cosmic-settings currently does not produce this event
-            let event = Event::Input(InputEvent::Mouse(MouseEvent::State(new.state)));
+            // cosmic-settings rarely changes this, but the startup sync emits any stored
+            // state that differs from the default.
+            let event = Event::Input(InputEvent::mouse(MouseEvent::State(new.state)));
             events.push(event);
         }
         if old.acceleration != new.acceleration {
-            let event = Event::Input(InputEvent::Mouse(MouseEvent::Acceleration(
-                new.acceleration.clone(),
-            )));
-            events.push(event);
+            if old.acceleration.is_none() || new.acceleration.is_none() {
+                // Set or cleared as a whole, as for the scroll config: a backend that
+                // rewrites the profile from it must not get it for a speed-only change.
+                let event = Event::Input(InputEvent::mouse(MouseEvent::Acceleration(
+                    new.acceleration.clone(),
+                )));
+                events.push(event);
+            }
+
+            // Unset acceleration is libinput's default, as for touchpads.
+            let old_accel = accel_or_default(old.acceleration);
+            let new_accel = accel_or_default(new.acceleration.clone());
+            if old_accel.speed != new_accel.speed {
+                let event = Event::Input(InputEvent::mouse(MouseEvent::AccelSpeed(
+                    new_accel.speed,
+                )));
+                events.push(event);
+            }
+            if old_accel.profile != new_accel.profile {
+                let event = Event::Input(InputEvent::mouse(MouseEvent::AccelProfile(
+                    new_accel.profile,
+                )));
+                events.push(event);
+            }
         }
         if old.calibration != new.calibration {
             // Unreachable: cosmic-settings currently does not produce this event
-            let event = Event::Input(InputEvent::Mouse(MouseEvent::Calibration(new.calibration)));
+            let event = Event::Input(InputEvent::mouse(MouseEvent::Calibration(new.calibration)));
             events.push(event);
         }
         if old.click_method != new.click_method {
             // Unreachable: cosmic-settings currently does not produce this event
-            let event = Event::Input(InputEvent::Mouse(MouseEvent::ClickMethod(new.click_method)));
+            let event = Event::Input(InputEvent::mouse(MouseEvent::ClickMethod(new.click_method)));
             events.push(event);
         }
         if old.disable_while_typing != new.disable_while_typing {
             // Unreachable: cosmic-settings currently does not produce this event
-            let event = Event::Input(InputEvent::Mouse(MouseEvent::DisableWhileTyping(
+            let event = Event::Input(InputEvent::mouse(MouseEvent::DisableWhileTyping(
                 new.disable_while_typing,
             )));
             events.push(event);
         }
         if old.left_handed != new.left_handed {
-            let event = Event::Input(InputEvent::Mouse(MouseEvent::LeftHanded(new.left_handed)));
+            let event = Event::Input(InputEvent::mouse(MouseEvent::LeftHanded(new.left_handed)));
             events.push(event);
         }
         if old.middle_button_emulation != new.middle_button_emulation {
             // Unreachable: cosmic-settings currently does not produce this event
-            let event = Event::Input(InputEvent::Mouse(MouseEvent::MiddleButtonEmulation(
+            let event = Event::Input(InputEvent::mouse(MouseEvent::MiddleButtonEmulation(
                 new.middle_button_emulation,
             )));
             events.push(event);
         }
         if old.rotation_angle != new.rotation_angle {
             // Unreachable: cosmic-settings currently does not produce this event
-            let event = Event::Input(InputEvent::Mouse(MouseEvent::RotationAngle(
+            let event = Event::Input(InputEvent::mouse(MouseEvent::RotationAngle(
                 new.rotation_angle,
             )));
             events.push(event);
         }
         if old.scroll_config != new.scroll_config {
-            let event = Event::Input(InputEvent::Mouse(MouseEvent::ScrollConfig(
-                new.scroll_config.clone(),
-            )));
-
-            // TODO: Redundant when all sub-field events are emitted. IPC handlers should ignore this
-            // if equivalent fine-grained events are present.
-            events.push(event);
-
-            if let (Some(old_scroll), Some(new_scroll)) =
-                (old.scroll_config, new.scroll_config.clone())
-            {
-                if old_scroll.method != new_scroll.method {
-                    // Unreachable: cosmic-settings currently does not produce this event
-                    let event = Event::Input(InputEvent::Mouse(MouseEvent::ScrollMethod(
-                        new_scroll.method,
-                    )));
-                    events.push(event);
-                }
-                if old_scroll.natural_scroll != new_scroll.natural_scroll {
-                    let event = Event::Input(InputEvent::Mouse(MouseEvent::NaturalScroll(
-                        new_scroll.natural_scroll,
-                    )));
-                    events.push(event);
-                }
-                if old_scroll.scroll_button != new_scroll.scroll_button {
-                    // Unreachable: cosmic-settings currently does not produce this event
-                    let event = Event::Input(InputEvent::Mouse(MouseEvent::ScrollButton(
-                        new_scroll.scroll_button,
-                    )));
-                    events.push(event);
-                }
-                if old_scroll.scroll_factor != new_scroll.scroll_factor {
-                    let event = Event::Input(InputEvent::Mouse(MouseEvent::ScrollFactor(
-                        new_scroll.scroll_factor,
-                    )));
-                    events.push(event);
-                }
+            if old.scroll_config.is_none() || new.scroll_config.is_none() {
+                // Set or cleared as a whole: backends that take the whole config get it
+                // here, the fields below reach the ones that only handle single settings.
+                let event = Event::Input(InputEvent::mouse(MouseEvent::ScrollConfig(
+                    new.scroll_config.clone(),
+                )));
+                events.push(event);
+            }
+            let old_scroll = scroll_or_unset(old.scroll_config);
+            let new_scroll = scroll_or_unset(new.scroll_config.clone());
+            if old_scroll.method != new_scroll.method {
+                // Unreachable: cosmic-settings currently does not produce this event
+                let event = Event::Input(InputEvent::mouse(MouseEvent::ScrollMethod(
+                    new_scroll.method,
+                )));
+                events.push(event);
+            }
+            if old_scroll.natural_scroll != new_scroll.natural_scroll {
+                let event = Event::Input(InputEvent::mouse(MouseEvent::NaturalScroll(
+                    new_scroll.natural_scroll,
+                )));
+                events.push(event);
+            }
+            if old_scroll.scroll_button != new_scroll.scroll_button {
+                // Unreachable: cosmic-settings currently does not produce this event
+                let event = Event::Input(InputEvent::mouse(MouseEvent::ScrollButton(
+                    new_scroll.scroll_button,
+                )));
+                events.push(event);
+            }
+            if old_scroll.scroll_factor != new_scroll.scroll_factor {
+                let event = Event::Input(InputEvent::mouse(MouseEvent::ScrollFactor(
+                    new_scroll.scroll_factor,
+                )));
+                events.push(event);
             }
         }
         if old.tap_config != new.tap_config {
             // Unreachable: cosmic-settings currently does not produce this event
-            let event = Event::Input(InputEvent::Mouse(MouseEvent::TapConfig(
+            let event = Event::Input(InputEvent::mouse(MouseEvent::TapConfig(
                 new.tap_config.clone(),
             )));
-
-            // TODO: Redundant when all sub-field events are emitted. IPC handlers should ignore this
-            // if equivalent fine-grained events are present.
             events.push(event);
         }
         if old.map_to_output != new.map_to_output {
             // Unreachable: cosmic-settings currently does not produce this event
-            let event = Event::Input(InputEvent::Mouse(MouseEvent::MapToOutput(
+            let event = Event::Input(InputEvent::mouse(MouseEvent::MapToOutput(
                 new.map_to_output,
             )));
             events.push(event);
@@ -437,7 +618,11 @@ impl KeyboardEvent {
             )));
             events.push(event);
         }
-        if old.layout != new.layout {
+        if old.layout != new.layout && new.layout.trim().is_empty() {
+            // Sway and Hyprland treat an empty xkb_layout as "reset the keymap", which
+            // drops the user's layout. COSMIC can write one transiently; skip it.
+            warn!("Ignoring empty keyboard layout (was {:?})", old.layout);
+        } else if old.layout != new.layout {
             let event = Event::Input(InputEvent::Keyboard(KeyboardEvent::Layout(
                 new.layout.clone(),
             )));
@@ -472,6 +657,104 @@ impl KeyboardEvent {
     }
 }
 
+/// Coarse events, by name, with the fine-grained events that carry their fields one by one.
+const COARSE_EVENTS: &[(&str, &[&str])] = &[
+    (
+        "touchpad_acceleration",
+        &["touchpad_accel_speed", "touchpad_accel_profile"],
+    ),
+    (
+        "touchpad_scroll_config",
+        &[
+            "touchpad_scroll_method",
+            "touchpad_natural_scroll",
+            "touchpad_scroll_factor",
+            "touchpad_scroll_button",
+        ],
+    ),
+    (
+        "touchpad_tap_config",
+        &[
+            "touchpad_tap_enabled",
+            "touchpad_tap_button_map",
+            "touchpad_tap_drag",
+            "touchpad_tap_drag_lock",
+        ],
+    ),
+    (
+        "mouse_acceleration",
+        &["mouse_accel_speed", "mouse_accel_profile"],
+    ),
+    (
+        "mouse_scroll_config",
+        &[
+            "mouse_scroll_method",
+            "mouse_natural_scroll",
+            "mouse_scroll_factor",
+            "mouse_scroll_button",
+        ],
+    ),
+];
+
+/// Whether `event` is a coarse event (`Acceleration`, `ScrollConfig`, `TapConfig`) whose
+/// fields `batch` also sends as fine-grained events for the same device, all of them in
+/// `capabilities`. The backend then gets every field from those, and the coarse event
+/// would only apply them again, past any `[overrides]` of the fine-grained ones. A coarse
+/// event without fine-grained ones, e.g. a config set with every field unset, is kept.
+pub fn is_redundant<'a>(
+    event: &Event,
+    batch: impl IntoIterator<Item = &'a Event>,
+    capabilities: &[&str],
+) -> bool {
+    let Event::Input(input) = event else {
+        return false;
+    };
+    let Some((_, fine)) = COARSE_EVENTS
+        .iter()
+        .find(|(coarse, _)| *coarse == input.name())
+    else {
+        return false;
+    };
+    let fields: Vec<_> = batch
+        .into_iter()
+        .filter_map(|other| match other {
+            Event::Input(other) if other.device() == input.device() => Some(other.name()),
+            _ => None,
+        })
+        .filter(|name| fine.contains(name))
+        .collect();
+    !fields.is_empty() && fields.iter().all(|name| capabilities.contains(name))
+}
+
+/// `accel`, or libinput's default (adaptive profile, speed 0) when it is unset.
+fn accel_or_default(accel: Option<AccelConfig>) -> AccelConfig {
+    accel.unwrap_or(AccelConfig {
+        profile: None,
+        speed: 0.0,
+    })
+}
+
+/// `scroll`, or a config with every field unset when there is none.
+fn scroll_or_unset(scroll: Option<ScrollConfig>) -> ScrollConfig {
+    scroll.unwrap_or(ScrollConfig {
+        method: None,
+        natural_scroll: None,
+        scroll_button: None,
+        scroll_factor: None,
+    })
+}
+
+/// `tap`, or libinput's default (tapping off, tap-and-drag on, drag lock off) when it is
+/// unset.
+fn tap_or_default(tap: Option<TapConfig>) -> TapConfig {
+    tap.unwrap_or(TapConfig {
+        enabled: false,
+        button_map: None,
+        drag: true,
+        drag_lock: false,
+    })
+}
+
 impl KeyboardEvent {
     pub fn from_keyboard_config(old: KeyboardConfig, new: KeyboardConfig) -> Vec<Event> {
         if old == new {
@@ -489,4 +772,287 @@ impl KeyboardEvent {
 
         events
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(
+        acceleration: Option<AccelConfig>,
+        scroll: Option<ScrollConfig>,
+        tap: Option<TapConfig>,
+    ) -> InputConfig {
+        InputConfig {
+            acceleration,
+            scroll_config: scroll,
+            tap_config: tap,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn empty_layout_is_not_dispatched() {
+        let old = XkbConfig {
+            layout: "de".to_string(),
+            ..Default::default()
+        };
+        let new = XkbConfig {
+            layout: " ".to_string(),
+            repeat_rate: old.repeat_rate + 5,
+            ..old.clone()
+        };
+
+        let events = KeyboardEvent::from(old, new);
+        assert!(!events.iter().any(|event| event.name() == "keyboard_layout"));
+        // Other changes in the same update still go through.
+        assert!(events.iter().any(|event| event.name() == "keyboard_repeat_rate"));
+    }
+
+    #[test]
+    fn acceleration_toggle_on_an_unset_config_sends_the_profile() {
+        let old = config(None, None, None);
+        let new = config(
+            Some(AccelConfig {
+                profile: Some(AccelProfile::Flat),
+                speed: 0.0,
+            }),
+            None,
+            None,
+        );
+
+        let events = MouseEvent::from(old, new);
+        assert!(events.iter().any(|event| matches!(
+            event,
+            Event::Input(InputEvent::Mouse {
+                event: MouseEvent::AccelProfile(Some(AccelProfile::Flat)),
+                ..
+            })
+        )));
+        // The speed did not change from libinput's default.
+        assert!(!events.iter().any(|event| event.name() == "mouse_accel_speed"));
+    }
+
+    // Changing the speed while clearing a `flat` profile must reset the profile too,
+    // not leave `flat` in place.
+    #[test]
+    fn speed_change_with_cleared_profile_resets_profile() {
+        let accel = |profile, speed| Some(AccelConfig { profile, speed });
+        let old = config(accel(Some(AccelProfile::Flat), 0.2), None, None);
+        let new = config(accel(None, 0.5), None, None);
+
+        let events = TouchpadEvent::from(old, new);
+        assert!(events.iter().any(|event| matches!(
+            event,
+            Event::Input(InputEvent::TouchPad {
+                event: TouchpadEvent::AccelSpeed(speed),
+                ..
+            }) if *speed == 0.5
+        )));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            Event::Input(InputEvent::TouchPad {
+                event: TouchpadEvent::AccelProfile(None),
+                ..
+            })
+        )));
+    }
+
+    #[test]
+    fn changed_acceleration_is_sent_as_fine_grained_events_only() {
+        let accel = |speed| AccelConfig {
+            profile: Some(AccelProfile::Flat),
+            speed,
+        };
+        let old = config(Some(accel(0.0)), None, None);
+        let new = config(Some(accel(0.5)), None, None);
+
+        // A speed-only drag must not resend the profile.
+        let names: Vec<_> = TouchpadEvent::from(old.clone(), new.clone())
+            .iter()
+            .map(Event::name)
+            .collect();
+        assert_eq!(names, ["touchpad_accel_speed"]);
+        let names: Vec<_> = MouseEvent::from(old, new).iter().map(Event::name).collect();
+        assert_eq!(names, ["mouse_accel_speed"]);
+    }
+
+    #[test]
+    fn coarse_events_are_redundant_next_to_supported_fine_grained_ones() {
+        let unset = config(None, None, None);
+        let set = config(
+            Some(AccelConfig {
+                profile: Some(AccelProfile::Flat),
+                speed: 0.5,
+            }),
+            Some(ScrollConfig {
+                method: None,
+                natural_scroll: Some(true),
+                scroll_button: None,
+                scroll_factor: None,
+            }),
+            Some(TapConfig {
+                enabled: true,
+                button_map: None,
+                drag: true,
+                drag_lock: false,
+            }),
+        );
+        let batch = TouchpadEvent::from(unset, set);
+        let kept = |capabilities: &[&str]| -> Vec<&str> {
+            batch
+                .iter()
+                .filter(|event| !is_redundant(event, &batch, capabilities))
+                .map(Event::name)
+                .collect()
+        };
+
+        let fine = [
+            "touchpad_accel_speed",
+            "touchpad_accel_profile",
+            "touchpad_natural_scroll",
+            "touchpad_tap_enabled",
+        ];
+        assert_eq!(kept(&fine[..]), fine);
+        // Without the handler for one of the fields, the coarse event still carries it.
+        let without_natural_scroll = ["touchpad_accel_speed", "touchpad_accel_profile"];
+        assert_eq!(
+            kept(&without_natural_scroll[..]),
+            [
+                "touchpad_accel_speed",
+                "touchpad_accel_profile",
+                "touchpad_scroll_config",
+                "touchpad_natural_scroll",
+                "touchpad_tap_config",
+                "touchpad_tap_enabled",
+            ]
+        );
+    }
+
+    #[test]
+    fn changed_scroll_config_is_sent_as_fine_grained_events_only() {
+        let scroll = ScrollConfig {
+            method: Some(ScrollMethod::TwoFinger),
+            natural_scroll: Some(false),
+            scroll_button: None,
+            scroll_factor: Some(1.0),
+        };
+        let tap = TapConfig {
+            enabled: true,
+            button_map: Some(TapButtonMap::LeftRightMiddle),
+            drag: true,
+            drag_lock: false,
+        };
+        let old = config(None, Some(scroll.clone()), Some(tap.clone()));
+        let new = config(
+            None,
+            Some(ScrollConfig {
+                natural_scroll: Some(true),
+                ..scroll
+            }),
+            Some(TapConfig {
+                enabled: false,
+                ..tap
+            }),
+        );
+
+        let names: Vec<_> = TouchpadEvent::from(old, new)
+            .iter()
+            .map(Event::name)
+            .collect();
+        assert_eq!(names, ["touchpad_natural_scroll", "touchpad_tap_enabled"]);
+    }
+
+    #[test]
+    fn setting_an_unset_config_also_sends_the_fields_that_differ_from_unset() {
+        let scroll = ScrollConfig {
+            method: None,
+            natural_scroll: Some(true),
+            scroll_button: None,
+            scroll_factor: Some(1.5),
+        };
+        // Tap-and-drag on is libinput's default, so only tapping itself changes.
+        let tap = TapConfig {
+            enabled: true,
+            button_map: None,
+            drag: true,
+            drag_lock: false,
+        };
+        let old = config(None, None, None);
+        let new = config(None, Some(scroll.clone()), Some(tap));
+
+        let names: Vec<_> = TouchpadEvent::from(old.clone(), new.clone())
+            .iter()
+            .map(Event::name)
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "touchpad_scroll_config",
+                "touchpad_natural_scroll",
+                "touchpad_scroll_factor",
+                "touchpad_tap_config",
+                "touchpad_tap_enabled",
+            ]
+        );
+
+        // Mice have no fine-grained tap events, so tap stays coarse.
+        let names: Vec<_> = MouseEvent::from(old, new).iter().map(Event::name).collect();
+        assert_eq!(
+            names,
+            [
+                "mouse_scroll_config",
+                "mouse_natural_scroll",
+                "mouse_scroll_factor",
+                "mouse_tap_config",
+            ]
+        );
+    }
+
+    #[test]
+    fn clearing_a_config_resets_the_fields_that_were_set() {
+        let old = config(
+            None,
+            Some(ScrollConfig {
+                method: Some(ScrollMethod::Edge),
+                natural_scroll: None,
+                scroll_button: None,
+                scroll_factor: None,
+            }),
+            Some(TapConfig {
+                enabled: true,
+                button_map: None,
+                drag: false,
+                drag_lock: false,
+            }),
+        );
+        let new = config(None, None, None);
+
+        let events = TouchpadEvent::from(old, new);
+        let names: Vec<_> = events.iter().map(Event::name).collect();
+        assert_eq!(
+            names,
+            [
+                "touchpad_scroll_config",
+                "touchpad_scroll_method",
+                "touchpad_tap_config",
+                "touchpad_tap_enabled",
+                "touchpad_tap_drag",
+            ]
+        );
+        // Back to libinput's defaults rather than left as they were.
+        assert!(events.iter().any(|event| matches!(
+            event,
+            Event::Input(InputEvent::TouchPad {
+                event: TouchpadEvent::TapEnabled(false),
+                ..
+            })
+        )));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            Event::Input(InputEvent::TouchPad {
+                event: TouchpadEvent::TapDrag(true),
+                ..
+            })
+        )));
+    }
+}
@@ -1,14 +1,86 @@
+pub mod catalog;
 pub mod input;
-pub use input::InputEvent;
+pub use input::{InputEvent, KeyboardEvent, MouseEvent, TouchpadEvent};
 
 pub mod shortcuts;
 pub use shortcuts::ShortcutEvent;
 
+pub mod workspaces;
+pub use workspaces::WorkspaceEvent;
+
+pub mod theme;
+pub use theme::ThemeEvent;
+
+pub mod cursor;
+pub use cursor::CursorEvent;
+
+pub mod color_temp;
+pub use color_temp::ColorTempEvent;
+
 #[allow(dead_code)]
+#[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Debug, Clone)]
 pub enum Event {
     Input(InputEvent),
     Shortcut(ShortcutEvent),
+    Workspace(WorkspaceEvent),
+    Theme(ThemeEvent),
+    Cursor(CursorEvent),
+    ColorTemp(ColorTempEvent),
+}
+
+/// The COSMIC config key an event was derived from, for logs and error messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Source {
+    pub namespace: &'static str,
+    /// `None` when the watcher reads the namespace as a whole.
+    pub key: Option<&'static str>,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.key {
+            Some(key) => write!(f, "{}/{key}", self.namespace),
+            None => f.write_str(self.namespace),
+        }
+    }
+}
+
+/// An event as sent by a watcher, tagged with where it came from.
+#[derive(Debug, Clone)]
+pub struct SourcedEvent {
+    pub event: Event,
+    pub source: Source,
+}
+
+impl SourcedEvent {
+    pub fn new(event: Event, source: Source) -> Self {
+        Self { event, source }
+    }
+}
+
+impl Event {
+    /// Stable name of the event, matching the compositor handler it is routed to.
+    /// Backends list these names in `Compositor::capabilities`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Event::Input(ev) => ev.name(),
+            Event::Shortcut(ShortcutEvent::Add { .. }) => "add_shortcut",
+            Event::Shortcut(ShortcutEvent::Remove { .. }) => "remove_shortcut",
+            Event::Workspace(ev) => ev.name(),
+            Event::Theme(ev) => ev.name(),
+            Event::Cursor(ev) => ev.name(),
+            Event::ColorTemp(ev) => ev.name(),
+        }
+    }
+
+    /// The input device the event is limited to, if any (see `InputEvent::device`).
+    pub fn device(&self) -> Option<&str> {
+        match self {
+            Event::Input(ev) => ev.device(),
+            _ => None,
+        }
+    }
 }
 
 // impl InputEvent {
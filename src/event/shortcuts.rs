@@ -3,12 +3,15 @@ use cosmic_settings_config::shortcuts::{
     action::{Direction as CosmicDirection, FocusDirection as CosmicFocusDirection, System as CosmicSystem},
 };
 
+#[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FocusDirection { Left, Right, Up, Down }
 
+#[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Direction { Left, Right, Up, Down }
 
+#[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SystemAction {
     Launcher,
@@ -33,6 +36,7 @@ pub enum SystemAction {
     Unknown,
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Shortcut {
     Close,
@@ -92,6 +96,7 @@ impl From<CosmicAction> for Shortcut {
     }
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Debug, Clone)]
 pub enum ShortcutEvent {
     Add { shortcut: Shortcut, binding: Binding },
@@ -0,0 +1,35 @@
+/// Appearance settings from `com.system76.CosmicTheme.Mode`.
+///
+/// Only light/dark is synced. COSMIC's colors and accents have no counterpart that GTK,
+/// Qt and the other desktops would all understand.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ThemeEvent {
+    /// Whether applications should use their light or dark style.
+    Mode(ColorScheme),
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+
+impl ColorScheme {
+    pub fn from_dark(is_dark: bool) -> ColorScheme {
+        if is_dark {
+            ColorScheme::Dark
+        } else {
+            ColorScheme::Light
+        }
+    }
+}
+
+impl ThemeEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ThemeEvent::Mode(_) => "theme_mode",
+        }
+    }
+}
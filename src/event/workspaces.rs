@@ -0,0 +1,21 @@
+/// Workspace configuration from `com.system76.CosmicWorkspaces`.
+///
+/// Only the number of workspaces and their names are synced. Tiling, output binding and
+/// per-workspace layout differ too much between compositors to map meaningfully.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkspaceEvent {
+    /// Number of workspaces that should exist.
+    Count(u32),
+    /// Workspace names, indexed from workspace 1. Empty names keep the compositor default.
+    Names(Vec<String>),
+}
+
+impl WorkspaceEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WorkspaceEvent::Count(_) => "workspace_count",
+            WorkspaceEvent::Names(_) => "workspace_names",
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use std::env;
+use std::process::Command;
 
 #[allow(dead_code)]
 #[derive(Debug)]
@@ -8,6 +9,9 @@ use std::env;
 pub enum Desktop {
     Hyprland,
     Sway,
+    Labwc,
+    Wayfire,
+    Weston,
     Gnome,
     Kde,
     Plasma,
@@ -37,43 +41,137 @@ pub fn get_current_session() -> Desktop {
     if env::var("SWAYSOCK").is_ok() {
         return Desktop::Sway;
     }
+    if env::var("LABWC_PID").is_ok() {
+        return Desktop::Labwc;
+    }
+    if env::var("WAYFIRE_SOCKET").is_ok() {
+        return Desktop::Wayfire;
+    }
+    // Weston exports the config file it loaded to its clients.
+    if env::var("WESTON_CONFIG_FILE").is_ok() && env::var("WAYLAND_DISPLAY").is_ok() {
+        return Desktop::Weston;
+    }
     let candidates = [
         env::var("XDG_CURRENT_DESKTOP").ok(),
         env::var("XDG_SESSION_DESKTOP").ok(),
         env::var("DESKTOP_SESSION").ok(),
     ];
 
-    for value in candidates.into_iter().flatten() {
-        let lower = value.to_lowercase();
+    if let Some(desktop) = candidates.iter().flatten().find_map(|value| parse_desktop(value)) {
+        return desktop;
+    }
+
+    if env::var("WAYLAND_DISPLAY").is_ok() {
+        return Desktop::Wayland;
+    }
+    if env::var("DISPLAY").is_ok() {
+        return Desktop::X11;
+    }
+    // Services started outside the session (e.g. by the system manager) have none of the
+    // variables above; logind still knows which session the user is in.
+    if let Some(desktop) = logind_session() {
+        return desktop;
+    }
+
+    Desktop::Unknown("Not Detected".into())
+}
+
+/// Ask logind for the type and desktop of `$XDG_SESSION_ID`, or of the user's display
+/// session when that is unset. `None` when loginctl is missing or fails.
+fn logind_session() -> Option<Desktop> {
+    let session = env::var("XDG_SESSION_ID").unwrap_or_else(|_| "auto".to_string());
+    let output = Command::new("loginctl")
+        .args(["show-session", &session, "-p", "Type", "-p", "Desktop"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    parse_logind(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// `Type=` and `Desktop=` lines from `loginctl show-session`. The desktop wins; the type
+/// alone still tells a TTY from a graphical session.
+fn parse_logind(output: &str) -> Option<Desktop> {
+    let property = |name: &str| {
+        output
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix('='))
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+    if let Some(desktop) = property("Desktop").and_then(parse_desktop) {
+        return Some(desktop);
+    }
+    match property("Type")?.to_lowercase().as_str() {
+        "tty" => Some(Desktop::Tty),
+        "wayland" => Some(Desktop::Wayland),
+        "x11" => Some(Desktop::X11),
+        _ => None,
+    }
+}
+
+/// The desktop named by an `XDG_CURRENT_DESKTOP`-style value. That is a colon-separated
+/// list in priority order (`ubuntu:GNOME`), so the first recognised entry wins.
+fn parse_desktop(value: &str) -> Option<Desktop> {
+    for component in value.split(':') {
+        let lower = component.trim().to_lowercase();
         if lower.contains("hyprland") {
-            return Desktop::Hyprland;
+            return Some(Desktop::Hyprland);
         }
         if lower.contains("sway") {
-            return Desktop::Sway;
+            return Some(Desktop::Sway);
+        }
+        if lower.contains("labwc") {
+            return Some(Desktop::Labwc);
+        }
+        if lower.contains("wayfire") {
+            return Some(Desktop::Wayfire);
+        }
+        if lower.contains("weston") {
+            return Some(Desktop::Weston);
         }
         if lower.contains("gnome") {
-            return Desktop::Gnome;
+            return Some(Desktop::Gnome);
         }
         if lower.contains("kde") {
-            return Desktop::Kde;
+            return Some(Desktop::Kde);
         }
         if lower.contains("plasma") {
-            return Desktop::Plasma;
+            return Some(Desktop::Plasma);
         }
         if lower.contains("xfce") {
-            return Desktop::Xfce;
+            return Some(Desktop::Xfce);
         }
         if lower.contains("cosmic") {
-            return Desktop::Cosmic;
+            return Some(Desktop::Cosmic);
         }
     }
+    None
+}
 
-    if env::var("WAYLAND_DISPLAY").is_ok() {
-        return Desktop::Wayland;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(value: &str) -> String {
+        format!("{:?}", parse_desktop(value))
     }
-    if env::var("DISPLAY").is_ok() {
-        return Desktop::X11;
+
+    #[test]
+    fn desktop_list_is_read_in_priority_order() {
+        assert_eq!(parsed("pop:COSMIC"), "Some(Cosmic)");
+        assert_eq!(parsed("ubuntu:GNOME"), "Some(Gnome)");
+        assert_eq!(parsed("sway"), "Some(Sway)");
+        assert_eq!(parsed("sway:wlroots"), "Some(Sway)");
+        assert_eq!(parsed("KDE:GNOME"), "Some(Kde)");
+        assert_eq!(parsed("ubuntu"), "None");
     }
 
-    Desktop::Unknown("Not Detected".into())
+    #[test]
+    fn logind_desktop_wins_over_session_type() {
+        let logind = |output: &str| format!("{:?}", parse_logind(output));
+        assert_eq!(logind("Type=wayland\nDesktop=sway\n"), "Some(Sway)");
+        assert_eq!(logind("Type=x11\nDesktop=\n"), "Some(X11)");
+        assert_eq!(logind("Type=tty\n"), "Some(Tty)");
+        assert_eq!(logind("Type=unspecified\nDesktop=\n"), "None");
+    }
 }
@@ -0,0 +1,337 @@
+//! JSON-RPC 2.0 on the control socket (`jsonrpc` feature), one request per line.
+//!
+//! ```text
+//! $ echo '{"jsonrpc":"2.0","id":1,"method":"status"}' | socat - UNIX-CONNECT:$XDG_RUNTIME_DIR/cosmolith.sock
+//! {"jsonrpc":"2.0","id":1,"result":{"backend":"Sway","groups":{"keyboard":true,...}}}
+//! ```
+//!
+//! Methods:
+//!
+//! - `status`: the backend name and which groups are synced.
+//! - `sync`: queue every input setting currently stored in COSMIC. Returns `{"queued": n}`.
+//! - `apply` with `{"event": <Event>}`: queue one event, in the serde form of `Event`.
+//! - `set_filter` with `{"group": <group or "all">, "enabled": bool}`: returns like `status`.
+//! - `subscribe`: from then on the connection also receives an `applied` notification
+//!   `{"backend", "name", "source", "event", "error"}` for every event sent to a backend.
+//!
+//! Queued events go through the debounce window and the sync filter like watcher events,
+//! so `sync` and `apply` only report that the events were queued.
+
+use std::os::unix::net::UnixStream;
+
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::compositor::CompositorResult;
+use crate::control::{Control, Group};
+use crate::event::{Event, Source, SourcedEvent};
+use crate::watcher::input::current_input_events;
+
+/// Source of events queued over the socket.
+pub const SOURCE: Source = Source {
+    namespace: "control",
+    key: None,
+};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+#[derive(Deserialize)]
+struct Request {
+    jsonrpc: String,
+    method: String,
+    #[serde(default)]
+    params: Value,
+    /// Absent for notifications.
+    id: Option<Value>,
+}
+
+#[derive(Deserialize)]
+struct ApplyParams {
+    event: Event,
+}
+
+#[derive(Deserialize)]
+struct FilterParams {
+    group: String,
+    enabled: bool,
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// Handle one request line and return the response line, or `None` for a notification.
+/// `client` is the connection the request came from, kept by `subscribe`.
+pub fn handle(line: &str, control: &Control, client: &UnixStream) -> Option<String> {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(err) => {
+            return Some(error_response(
+                Value::Null,
+                RpcError::new(PARSE_ERROR, err.to_string()),
+            ));
+        }
+    };
+    let request: Request = match serde_json::from_value(request) {
+        Ok(request) if request.jsonrpc == "2.0" => request,
+        Ok(_) => {
+            let err = RpcError::new(INVALID_REQUEST, "jsonrpc must be \"2.0\"");
+            return Some(error_response(Value::Null, err));
+        }
+        Err(err) => {
+            let err = RpcError::new(INVALID_REQUEST, err.to_string());
+            return Some(error_response(Value::Null, err));
+        }
+    };
+
+    let result = call(&request.method, request.params, control, client);
+    let id = request.id?;
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }).to_string(),
+        Err(err) => error_response(id, err),
+    })
+}
+
+fn call(
+    method: &str,
+    params: Value,
+    control: &Control,
+    client: &UnixStream,
+) -> Result<Value, RpcError> {
+    match method {
+        "status" => Ok(status(control)),
+        "sync" => {
+            let events = current_input_events()
+                .map_err(|err| RpcError::new(INTERNAL_ERROR, err.to_string()))?;
+            let queued = events.len();
+            for sourced in events {
+                queue(control, SourcedEvent::new(sourced.event, SOURCE))?;
+            }
+            Ok(json!({ "queued": queued }))
+        }
+        "apply" => {
+            let ApplyParams { event } = params_of(params)?;
+            queue(control, SourcedEvent::new(event, SOURCE))?;
+            Ok(json!({ "queued": 1 }))
+        }
+        "set_filter" => {
+            let FilterParams { group, enabled } = params_of(params)?;
+            let groups = match (group.as_str(), Group::from_name(&group)) {
+                ("all", _) => Group::ALL.to_vec(),
+                (_, Some(group)) => vec![group],
+                (_, None) => {
+                    return Err(RpcError::new(
+                        INVALID_PARAMS,
+                        format!("unknown group `{group}`"),
+                    ));
+                }
+            };
+            let mut filter = control.filter.lock().unwrap_or_else(|err| err.into_inner());
+            for group in groups {
+                filter.set(group, enabled);
+            }
+            drop(filter);
+            Ok(status(control))
+        }
+        "subscribe" => {
+            client
+                .try_clone()
+                .and_then(|stream| control.subscribers.add(stream))
+                .map_err(|err| RpcError::new(INTERNAL_ERROR, err.to_string()))?;
+            Ok(Value::Bool(true))
+        }
+        _ => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("unknown method `{method}`"),
+        )),
+    }
+}
+
+fn params_of<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|err| RpcError::new(INVALID_PARAMS, err.to_string()))
+}
+
+fn queue(control: &Control, sourced: SourcedEvent) -> Result<(), RpcError> {
+    let sender = control.events.lock().unwrap_or_else(|err| err.into_inner());
+    sender
+        .send(sourced)
+        .map_err(|_| RpcError::new(INTERNAL_ERROR, "cosmolith is shutting down"))
+}
+
+fn status(control: &Control) -> Value {
+    let filter = control.filter.lock().unwrap_or_else(|err| err.into_inner());
+    let groups: serde_json::Map<String, Value> = Group::ALL
+        .iter()
+        .map(|group| {
+            (
+                group.name().to_string(),
+                Value::Bool(filter.enabled(*group)),
+            )
+        })
+        .collect();
+    json!({ "backend": control.backend(), "groups": groups })
+}
+
+fn error_response(id: Value, err: RpcError) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": err.code, "message": err.message },
+    })
+    .to_string()
+}
+
+/// The `applied` notification sent to subscribers after `backend` handled `sourced`.
+pub fn applied_notification(
+    backend: &str,
+    sourced: &SourcedEvent,
+    result: &CompositorResult,
+) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "applied",
+        "params": {
+            "backend": backend,
+            "name": sourced.event.name(),
+            "source": sourced.source.to_string(),
+            "event": sourced.event,
+            "error": result.as_ref().err().map(|err| err.to_string()),
+        },
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader};
+    use std::sync::mpsc::{self, Receiver};
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::control::SyncFilter;
+    use crate::event::InputEvent;
+    use crate::event::input::KeyboardEvent;
+
+    fn control() -> (Control, Receiver<SourcedEvent>) {
+        let (tx, rx) = mpsc::channel();
+        let control = Control {
+            filter: Arc::new(Mutex::new(SyncFilter::default())),
+            events: Arc::new(Mutex::new(tx)),
+            backend: Arc::new(Mutex::new(Some("Sway"))),
+            subscribers: Default::default(),
+        };
+        (control, rx)
+    }
+
+    fn request(control: &Control, client: &UnixStream, line: &str) -> Value {
+        serde_json::from_str(&handle(line, control, client).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn set_filter_and_status() {
+        let (control, _rx) = control();
+        let (client, _peer) = UnixStream::pair().unwrap();
+        let reply = request(
+            &control,
+            &client,
+            r#"{"jsonrpc":"2.0","id":7,"method":"set_filter","params":{"group":"mouse","enabled":false}}"#,
+        );
+        assert_eq!(reply["id"], 7);
+        assert_eq!(reply["result"]["backend"], "Sway");
+        assert_eq!(reply["result"]["groups"]["mouse"], false);
+        assert_eq!(reply["result"]["groups"]["keyboard"], true);
+        assert!(!control.filter.lock().unwrap().enabled(Group::Mouse));
+    }
+
+    #[test]
+    fn status_follows_the_backend_after_a_redetect() {
+        let (control, _rx) = control();
+        let (client, _peer) = UnixStream::pair().unwrap();
+        let backend = || {
+            let status = r#"{"jsonrpc":"2.0","id":1,"method":"status"}"#;
+            request(&control, &client, status)["result"]["backend"].clone()
+        };
+        *control.backend.lock().unwrap() = Some("KDE Plasma");
+        assert_eq!(backend(), "KDE Plasma");
+        *control.backend.lock().unwrap() = None;
+        assert!(backend().is_null());
+    }
+
+    #[test]
+    fn apply_queues_the_event() {
+        let (control, rx) = control();
+        let (client, _peer) = UnixStream::pair().unwrap();
+        let event = Event::Input(InputEvent::Keyboard(KeyboardEvent::RepeatRate(30)));
+        let line =
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "apply", "params": { "event": event } });
+        let reply = request(&control, &client, &line.to_string());
+        assert_eq!(reply["result"]["queued"], 1);
+
+        let queued = rx.try_recv().unwrap();
+        assert_eq!(queued.event.name(), "keyboard_repeat_rate");
+        assert_eq!(queued.source, SOURCE);
+    }
+
+    #[test]
+    fn errors_use_the_standard_codes() {
+        let (control, _rx) = control();
+        let (client, _peer) = UnixStream::pair().unwrap();
+        assert_eq!(
+            request(&control, &client, "{nope")["error"]["code"],
+            PARSE_ERROR
+        );
+        let unknown = request(
+            &control,
+            &client,
+            r#"{"jsonrpc":"2.0","id":1,"method":"reboot"}"#,
+        );
+        assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+        let bad_group = request(
+            &control,
+            &client,
+            r#"{"jsonrpc":"2.0","id":1,"method":"set_filter","params":{"group":"trackball","enabled":true}}"#,
+        );
+        assert_eq!(bad_group["error"]["code"], INVALID_PARAMS);
+        assert!(handle(r#"{"jsonrpc":"2.0","method":"status"}"#, &control, &client).is_none());
+    }
+
+    #[test]
+    fn subscribers_receive_applied_notifications() {
+        let (control, _rx) = control();
+        let (client, peer) = UnixStream::pair().unwrap();
+        request(
+            &control,
+            &client,
+            r#"{"jsonrpc":"2.0","id":1,"method":"subscribe"}"#,
+        );
+
+        let sourced = SourcedEvent::new(
+            Event::Input(InputEvent::Keyboard(KeyboardEvent::RepeatRate(30))),
+            SOURCE,
+        );
+        control
+            .subscribers
+            .broadcast(&applied_notification("Sway", &sourced, &Ok(())));
+
+        let mut line = String::new();
+        BufReader::new(peer).read_line(&mut line).unwrap();
+        let notification: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(notification["method"], "applied");
+        assert_eq!(notification["params"]["name"], "keyboard_repeat_rate");
+        assert_eq!(notification["params"]["error"], Value::Null);
+    }
+}
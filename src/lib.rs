@@ -1,5 +1,24 @@
+//! Sync COSMIC input, shortcut and workspace settings to other Wayland compositors.
+//!
+//! [`Session`] is the stable public API; see the [`session`] module.
+
+pub mod audit;
+pub mod config;
+pub mod control;
+pub mod debounce;
+#[cfg(feature = "emit")]
+pub mod emit;
 pub mod error;
 pub mod event;
 pub mod watcher;
 pub mod compositor;
-pub mod identifier;
\ No newline at end of file
+pub mod identifier;
+#[cfg(feature = "jsonrpc")]
+pub mod jsonrpc;
+pub mod overrides;
+pub mod recording;
+pub mod session;
+pub mod state;
+pub mod units;
+
+pub use session::Session;
@@ -2,68 +2,687 @@
 use cosmic_config::Config;
 use std::{
     error::Error,
-    sync::{Arc, Mutex, mpsc},
-    time::Duration,
+    path::PathBuf,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    },
+    time::{Duration, Instant},
 };
 
-mod watcher;
-use watcher::input::{send_initial_input_events, start_input_watcher};
-mod event;
-use event::Event;
+use clap::Parser;
+use tracing::level_filters::LevelFilter;
+use tracing::{debug, error, info, warn};
+use tracing_subscriber::EnvFilter;
 
-mod identifier;
-use identifier::get_current_session;
+use cosmolith::audit::AuditLog;
+use cosmolith::config::CosmolithConfig;
+use cosmolith::control::{self, Control, Subscribers, SyncFilter};
+use cosmolith::compositor::reconnect::ReconnectPolicy;
+use cosmolith::compositor::trace;
+use cosmolith::compositor::xinput::Xinput;
+use cosmolith::Session;
+use cosmolith::recording::{Recorded, Recording};
+use cosmolith::state::AppliedState;
+use cosmolith::compositor::{self, Compositor, CompositorResult};
+use cosmolith::debounce::Debouncer;
+use cosmolith::error::Error as CosmolithError;
+use cosmolith::event::SourcedEvent;
+use cosmolith::event::input::is_redundant;
+use cosmolith::identifier;
+use cosmolith::overrides;
+use cosmolith::watcher::color_temp::start_color_temp_watcher;
+use cosmolith::watcher::cursor::start_cursor_watcher;
+use cosmolith::watcher::device_monitor::{DeviceMonitor, start_device_monitor};
+use cosmolith::watcher::input::{
+    current_input_events, send_initial_input_events, start_input_watcher,
+};
+use cosmolith::watcher::shortcuts::start_shortcuts_watcher;
+use cosmolith::watcher::theme::start_theme_watcher;
+use cosmolith::watcher::workspaces::start_workspaces_watcher;
+
+mod cli;
+mod oneshot;
+mod replay;
+mod reset;
+mod selftest;
+use cli::{Cli, Command};
 
-mod compositor;
-use compositor::init_compositor;
+/// How often the input watcher is checked for missed changes.
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(30);
 
-use watcher::shortcuts::start_shortcuts_watcher;
+/// Longest the main loop waits for an event, so SIGINT, SIGTERM and SIGHUP are noticed
+/// promptly.
+const SIGNAL_POLL: Duration = Duration::from_millis(250);
 
 fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    init_logging(&cli);
+    if cli.list_events {
+        let backend = cli
+            .compositor
+            .as_deref()
+            .or_else(|| compositor::backend_for(&identifier::get_current_session()).ok());
+        cli::print_event_list(backend);
+        return Ok(());
+    }
+    if cli.matrix {
+        cli::print_support_matrix();
+        return Ok(());
+    }
+    compositor::trace::set_enabled(cli.trace_ipc);
+    compositor::trace::set_dry_run(cli.dry_run);
+    let config = match CosmolithConfig::default_path() {
+        Some(path) => CosmolithConfig::load(&path).map_err(|err| err as Box<dyn Error>)?,
+        None => CosmolithConfig::default(),
+    };
+    compositor::numlock::set_default_when_unknown(config.numlock.default_when_unknown);
+    compositor::reconnect::set_policy(config.reconnect);
+    overrides::set_overrides(config.overrides);
+    let debounce = cli.debounce_config(config.debounce);
+    if let Some(max_attempts) = cli.reconnect_attempts {
+        compositor::reconnect::set_policy(ReconnectPolicy {
+            max_attempts,
+            ..compositor::reconnect::policy()
+        });
+    }
+    match &cli.command {
+        Some(Command::Apply) => return oneshot::run(cli.compositor.as_deref()),
+        Some(Command::SelfTest) => return selftest::run(cli.compositor.as_deref()),
+        Some(Command::Replay { file }) => {
+            return replay::replay_recording(file, cli.compositor.as_deref());
+        }
+        Some(Command::CaptureConfig { dir }) => return replay::capture(dir),
+        Some(Command::ReplayConfig { dir, backend }) => {
+            return replay::replay(dir, backend, debounce);
+        }
+        None => {}
+    }
+    if cli.reset_backend {
+        return reset::run(cli.yes, cli.compositor.as_deref());
+    }
+
+    if cli.startup_delay > 0 {
+        info!("Waiting {} ms before starting…", cli.startup_delay);
+        std::thread::sleep(Duration::from_millis(cli.startup_delay));
+    }
+
     let _config = Config::new("com.system76.CosmicComp", 1)?;
     // Channel used to receive change notifications from the watcher callback.
-    let (tx, rx) = mpsc::channel::<Event>();
+    let (tx, rx) = mpsc::channel::<SourcedEvent>();
     let tx = Arc::new(Mutex::new(tx));
 
-    let _watcher = start_input_watcher(&tx)?;
+    let mut input_watcher = start_input_watcher(&tx)?;
     let _shortcuts_watcher = start_shortcuts_watcher(&tx)?;
-    send_initial_input_events(&tx)?;
+    // Workspace, theme, cursor and night light sync are best-effort; the daemon is still
+    // useful without them.
+    let _workspaces_watcher = start_workspaces_watcher(&tx)
+        .map_err(|err| warn!("Workspace sync unavailable: {err}"))
+        .ok();
+    let _theme_watcher = start_theme_watcher(&tx)
+        .map_err(|err| warn!("Theme sync unavailable: {err}"))
+        .ok();
+    let _cursor_watcher = start_cursor_watcher(&tx)
+        .map_err(|err| warn!("Cursor sync unavailable: {err}"))
+        .ok();
+    let _color_temp_watcher = start_color_temp_watcher(&tx)
+        .map_err(|err| warn!("Night light sync unavailable: {err}"))
+        .ok();
+    let device_monitor = start_device_monitor(&tx)
+        .map_err(|err| warn!("External mouse detection unavailable: {err}"))
+        .ok();
+    // A dry run applies nothing, so it must not be saved as applied.
+    let mut snapshot = Snapshot::load(!cli.dry_run);
 
-    println!("Watching for configuration changes…");
+    info!("Watching for configuration changes…");
 
-    let session = get_current_session();
-    println!("You are currently running: {:?}", session);
+    let forced = cli.compositor.as_deref();
+    let mut session = match cli::open_session(forced) {
+        Ok(session) => {
+            info!("You are currently running: {}", session.desktop());
+            Some(session)
+        }
+        // A forced backend that cannot connect is a configuration error, not a reason
+        // to run without one.
+        Err(err) if forced.is_some() => return Err(err as Box<dyn Error>),
+        Err(err) => {
+            error!("Failed to initialize the compositor backend: {err}");
+            if let Some(hint) = cli::init_hint(err.as_ref()) {
+                warn!("{hint}");
+            }
+            warn!("No supported compositor detected. Events will be logged only.");
+            None
+        }
+    };
+    activate(device_monitor.as_ref(), session.as_ref());
+    if !cli.no_sync_on_start {
+        let applied = snapshot.saved.as_ref().filter(|saved| {
+            session
+                .as_ref()
+                .is_some_and(|session| saved.applies_to(session.backend()))
+        });
+        send_initial_input_events(&tx, applied)?;
+    }
+
+    // Secondary apply for X11 clients; the main backend is unaffected if this fails.
+    // Plain X11 sessions already use xinput as the main backend.
+    let xinput_is_main =
+        session.as_ref().map(Session::backend_name) == Some(Xinput::new().name());
+    let x11 = if cli.also_x11 && !xinput_is_main {
+        let mut xinput = Xinput::new();
+        match xinput.init() {
+            Ok(()) => Some(xinput),
+            Err(err) => {
+                warn!("X11 side channel unavailable: {err}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let audit = match &cli.audit_log {
+        Some(path) => {
+            let path = path.clone().or_else(AuditLog::default_path);
+            let path = path.ok_or("no state directory for the audit log; pass a path")?;
+            let log = AuditLog::open(path)?;
+            info!("Recording applied settings to {}", log.path().display());
+            Some(log)
+        }
+        None => None,
+    };
+    let recording = match &cli.record {
+        Some(path) => {
+            let recording = Recording::open(path)?;
+            info!("Recording sent events to {}", recording.path().display());
+            Some(recording)
+        }
+        None => None,
+    };
 
-    let compositor = init_compositor(session);
-    if compositor.is_none() {
-        eprintln!("No supported compositor detected. Events will be logged only.");
+    // The control socket is best-effort, like workspace sync.
+    let filter = Arc::new(Mutex::new(SyncFilter::default()));
+    let backend = Arc::new(Mutex::new(session.as_ref().map(Session::backend_name)));
+    let subscribers = Subscribers::default();
+    let control = Control {
+        filter: Arc::clone(&filter),
+        events: Arc::clone(&tx),
+        backend: Arc::clone(&backend),
+        subscribers: subscribers.clone(),
+    };
+    match cli.control_socket.clone().or_else(control::default_path) {
+        Some(path) => match control::serve(&path, control) {
+            Ok(()) => info!("Control socket at {}", path.display()),
+            Err(err) => warn!("Control socket unavailable: {err}"),
+        },
+        None => warn!("Control socket unavailable: XDG_RUNTIME_DIR is not set"),
     }
+    #[cfg(feature = "emit")]
+    let emitter = match &cli.emit_socket {
+        Some(path) => {
+            let path = path.clone().or_else(cosmolith::emit::default_path);
+            let path = path.ok_or("XDG_RUNTIME_DIR is not set; pass a path for --emit-socket")?;
+            let emitter = cosmolith::emit::serve(&path)?;
+            info!("Broadcasting events on {}", path.display());
+            Some(emitter)
+        }
+        None => None,
+    };
+
+    let shutdown = Arc::new(AtomicBool::new(false));
+    for signal in [signal_hook::consts::SIGINT, signal_hook::consts::SIGTERM] {
+        signal_hook::flag::register(signal, Arc::clone(&shutdown))?;
+    }
+    let reload = Arc::new(AtomicBool::new(false));
+    signal_hook::flag::register(signal_hook::consts::SIGHUP, Arc::clone(&reload))?;
+
+    let mut debouncer = Debouncer::new(debounce);
+    let mut last_watchdog = Instant::now();
 
     loop {
-        match rx.recv_timeout(Duration::from_secs(5)) {
+        if shutdown.load(Ordering::Relaxed) {
+            info!("Shutting down…");
+            let events = debouncer.flush();
+            snapshot.note(apply(
+                &filter,
+                session.as_ref(),
+                x11.as_ref(),
+                audit.as_ref(),
+                recording.as_ref(),
+                &subscribers,
+                events,
+            ));
+            break;
+        }
+        if reload.swap(false, Ordering::Relaxed) {
+            session = redetect(session, forced);
+            *backend.lock().unwrap_or_else(|err| err.into_inner()) =
+                session.as_ref().map(Session::backend_name);
+            activate(device_monitor.as_ref(), session.as_ref());
+            match current_input_events() {
+                Ok(events) => snapshot.note_resync(apply(
+                    &filter,
+                    session.as_ref(),
+                    x11.as_ref(),
+                    audit.as_ref(),
+                    recording.as_ref(),
+                    &subscribers,
+                    events
+                        .into_iter()
+                        .map(|event| resolve(device_monitor.as_ref(), event))
+                        .collect(),
+                )),
+                Err(err) => error!("Failed to read the current COSMIC input config: {err}"),
+            }
+        }
+
+        // Wake up for the next debounced event, or to check for a signal.
+        let timeout = debouncer
+            .next_deadline()
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+            .unwrap_or(SIGNAL_POLL)
+            .min(SIGNAL_POLL);
+
+        match rx.recv_timeout(timeout) {
             Ok(event) => {
-                println!("Recieved: {:?}", event);
-                if let Some(ref comp) = compositor {
-                    match comp.apply_event(event) {
-                        Ok(()) => {
-                            // println!("successfull.");
-                        }
-                        Err(err) => {
-                            eprintln!("Failed to apply event: {err}");
-                        }
+                // One COSMIC change arrives as several events; take everything already
+                // queued so the immediate ones are applied as one batch.
+                let now = Instant::now();
+                let mut ready = Vec::new();
+                for event in std::iter::once(event).chain(rx.try_iter()) {
+                    debug!("Received from {}: {:?}", event.source, event.event);
+                    #[cfg(feature = "emit")]
+                    if let Some(emitter) = &emitter {
+                        emitter.emit(&event);
                     }
+                    let event = resolve(device_monitor.as_ref(), event);
+                    ready.extend(debouncer.push(event, now));
                 }
+                snapshot.note(apply(
+                    &filter,
+                    session.as_ref(),
+                    x11.as_ref(),
+                    audit.as_ref(),
+                    recording.as_ref(),
+                    &subscribers,
+                    ready,
+                ));
             }
-            Err(mpsc::RecvTimeoutError::Timeout) => {
-                // optional heartbeat to keep the loop responsive to Ctrl+C
-                continue;
-            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
             Err(mpsc::RecvTimeoutError::Disconnected) => {
-                eprintln!("Watcher channel closed; exiting.");
+                error!("Watcher channel closed; exiting.");
+                let events = debouncer.flush();
+                snapshot.note(apply(
+                    &filter,
+                    session.as_ref(),
+                    x11.as_ref(),
+                    audit.as_ref(),
+                    recording.as_ref(),
+                    &subscribers,
+                    events,
+                ));
                 break;
             }
         }
+
+        let due = debouncer.drain_due(Instant::now());
+        snapshot.note(apply(
+            &filter,
+            session.as_ref(),
+            x11.as_ref(),
+            audit.as_ref(),
+            recording.as_ref(),
+            &subscribers,
+            due,
+        ));
+
+        // Only snapshot once nothing is held back, so the saved config was really applied.
+        if debouncer.next_deadline().is_none() {
+            snapshot.save_if_changed(session.as_ref());
+        }
+
+        if last_watchdog.elapsed() >= WATCHDOG_INTERVAL
+            || input_watcher.retry_due(Instant::now())
+        {
+            last_watchdog = Instant::now();
+            if let Err(err) = input_watcher.watchdog() {
+                error!("Input watcher watchdog failed: {err}");
+            }
+        }
+    }
+
+    snapshot.save_if_changed(session.as_ref());
+
+    // Backends may hold IPC connections or child processes; the watchers are dropped on
+    // return.
+    if let Some(Err(err)) = session.as_ref().map(|session| session.backend().shutdown()) {
+        error!("Compositor shutdown failed: {err}");
+    }
+    if let Some(Err(err)) = x11.as_ref().map(Xinput::shutdown) {
+        error!("X11 side channel shutdown failed: {err}");
     }
 
     Ok(())
 }
+
+/// Log to stderr at the `RUST_LOG` levels. Without it the daemon logs from `info` up,
+/// and the one-shot commands, which print their own report, from `warn`. `--debug`
+/// lowers cosmolith's own level to `debug`, which includes every event the watchers send.
+fn init_logging(cli: &Cli) {
+    let level = if cli.command.is_some() || cli.reset_backend {
+        LevelFilter::WARN
+    } else {
+        LevelFilter::INFO
+    };
+    let mut filter = EnvFilter::builder()
+        .with_default_directive(level.into())
+        .from_env_lossy();
+    if cli.debug {
+        filter = filter.add_directive("cosmolith=debug".parse().expect("valid directive"));
+    }
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .init();
+}
+
+/// Shut down the current backend and detect the desktop again, or reconnect the one
+/// forced with `--compositor`, for SIGHUP.
+fn redetect(previous: Option<Session>, forced: Option<&str>) -> Option<Session> {
+    if let Some(Err(err)) = previous.as_ref().map(|session| session.backend().shutdown()) {
+        error!("Compositor shutdown failed: {err}");
+    }
+    drop(previous);
+    match cli::open_session(forced) {
+        Ok(session) => {
+            info!("Reloaded; you are currently running: {}", session.desktop());
+            Some(session)
+        }
+        Err(err) => {
+            error!("Failed to re-detect the compositor: {err}");
+            warn!("No supported compositor detected. Events will be logged only.");
+            None
+        }
+    }
+}
+
+/// Have the device monitor stand in for `DisabledOnExternalMouse` when the session's
+/// backend cannot apply it.
+fn activate(monitor: Option<&DeviceMonitor>, session: Option<&Session>) {
+    if let Some(monitor) = monitor {
+        monitor.set_active(session.is_some_and(|session| {
+            !session.backend().disables_touchpad_on_external_mouse()
+        }));
+    }
+}
+
+/// `sourced` with `DisabledOnExternalMouse` resolved (see `DeviceMonitor::resolve`).
+fn resolve(monitor: Option<&DeviceMonitor>, sourced: SourcedEvent) -> SourcedEvent {
+    match monitor {
+        Some(monitor) => monitor.resolve(sourced),
+        None => sourced,
+    }
+}
+
+/// `sourced` as the config's `[overrides]` have it for `backend`, or `None` when they
+/// disable it there.
+fn overridden(backend: &dyn Compositor, sourced: &SourcedEvent) -> Option<SourcedEvent> {
+    let event = overrides::apply(backend.name(), sourced.event.clone());
+    if event.is_none() {
+        let (name, source) = (sourced.event.name(), sourced.source);
+        info!(
+            "Skipping {name} from {source} on {}: disabled in [overrides]",
+            backend.name()
+        );
+    }
+    event.map(|event| SourcedEvent::new(event, sourced.source))
+}
+
+/// Apply a batch of events, in order, to the session's backend in one
+/// `Compositor::apply_events` call. Paused, disabled and unsupported events are left out,
+/// the unsupported ones with a warning, and so are coarse events a backend gets field by
+/// field (see `is_redundant`); the X11 side channel still gets each event on its own.
+/// Overrides are applied per backend, so the side channel can be configured apart.
+///
+/// Returns whether the batch was fully applied, or `None` when nothing was sent.
+fn apply(
+    filter: &Mutex<SyncFilter>,
+    session: Option<&Session>,
+    x11: Option<&Xinput>,
+    audit: Option<&AuditLog>,
+    recording: Option<&Recording>,
+    subscribers: &Subscribers,
+    batch: Vec<SourcedEvent>,
+) -> Option<bool> {
+    let mut events = Vec::new();
+    let mut paused = false;
+    // Checked per backend, before the overrides, so a disabled fine-grained event is not
+    // applied anyway through its coarse one.
+    let applies = |backend: &dyn Compositor, sourced: &SourcedEvent| {
+        let batch = batch.iter().map(|sourced| &sourced.event);
+        let redundant = is_redundant(&sourced.event, batch, backend.capabilities());
+        if redundant {
+            let (name, backend) = (sourced.event.name(), backend.name());
+            debug!("Skipping {name} on {backend}: its fields are sent one by one");
+        }
+        !redundant
+    };
+    for sourced in &batch {
+        let name = sourced.event.name();
+        let source = sourced.source;
+        let allowed = filter
+            .lock()
+            .map(|filter| filter.allows(&sourced.event))
+            .unwrap_or(true);
+        if !allowed {
+            info!("Skipping {name} from {source}: syncing is paused over the control socket");
+            paused = true;
+            continue;
+        }
+        let side_channel = x11
+            .filter(|x11| x11.supports(&sourced.event) && applies(*x11, sourced))
+            .and_then(|x11| Some((x11, overridden(x11, sourced)?)));
+        if let Some((x11, sourced)) = side_channel {
+            let event = sourced.event.clone();
+            let result = trace::dispatch(x11.name(), name, || x11.apply_event(event));
+            notify(subscribers, x11.name(), &sourced, &result);
+            remember(recording, x11.name(), &sourced, &result);
+            if result.is_ok() {
+                record(audit, x11.name(), &sourced);
+            }
+        }
+        match session {
+            Some(session) if session.supports(&sourced.event) => {
+                if applies(session.backend(), sourced) {
+                    events.extend(overridden(session.backend(), sourced));
+                }
+            }
+            // Not a failure: the backend has no mapping for it. Logged so a setting that
+            // never reaches the compositor is visible.
+            Some(session) => warn!(
+                "{}",
+                CosmolithError::UnsupportedEvent {
+                    compositor: session.backend_name(),
+                    event: name,
+                }
+            ),
+            None => {}
+        }
+    }
+
+    let Some(session) = session else {
+        return None;
+    };
+    if events.is_empty() {
+        return paused.then_some(false);
+    }
+    let result =
+        session.apply_events(events.iter().map(|sourced| sourced.event.clone()).collect());
+    for sourced in &events {
+        notify(subscribers, session.backend_name(), sourced, &result);
+        remember(recording, session.backend_name(), sourced, &result);
+    }
+    // `Session::apply_events` has already logged a failure.
+    if result.is_err() {
+        return Some(false);
+    }
+    for sourced in &events {
+        record(audit, session.backend_name(), sourced);
+    }
+    Some(!paused)
+}
+
+/// The COSMIC config cosmolith last applied, saved for the next start (see `AppliedState`).
+struct Snapshot {
+    path: Option<PathBuf>,
+    saved: Option<AppliedState>,
+    // Something was applied since the last save.
+    dirty: bool,
+    // A batch failed or was paused since the last full resync, so the compositor may not
+    // have the current COSMIC config whatever was applied after it.
+    stale: bool,
+}
+
+impl Snapshot {
+    /// Read the saved snapshot; `save` is false to never write a new one.
+    fn load(save: bool) -> Self {
+        let path = AppliedState::default_path();
+        let saved = path.as_deref().and_then(|path| {
+            AppliedState::load(path)
+                .map_err(|err| warn!("Ignoring the saved state: {err}"))
+                .ok()
+                .flatten()
+        });
+        Self {
+            path: path.filter(|_| save),
+            saved,
+            dirty: false,
+            stale: false,
+        }
+    }
+
+    /// Track the outcome of `apply`. A failed or paused batch means the current COSMIC
+    /// config is not what the compositor has, so nothing is saved until a full resync
+    /// succeeds (see `note_resync`).
+    fn note(&mut self, applied: Option<bool>) {
+        match applied {
+            Some(true) => self.dirty = true,
+            Some(false) => self.stale = true,
+            None => {}
+        }
+    }
+
+    /// Track the outcome of applying the whole current COSMIC config, as on SIGHUP.
+    fn note_resync(&mut self, applied: Option<bool>) {
+        if applied == Some(true) {
+            self.stale = false;
+        }
+        self.note(applied);
+    }
+
+    fn should_save(&self) -> bool {
+        self.dirty && !self.stale
+    }
+
+    /// Save the current COSMIC config as applied to `session`'s backend.
+    fn save_if_changed(&mut self, session: Option<&Session>) {
+        let Some(path) = self.path.as_deref().filter(|_| self.should_save()) else {
+            return;
+        };
+        let Some(session) = session else {
+            return;
+        };
+        self.dirty = false;
+        let current = match AppliedState::current() {
+            Ok(current) => current.applied_to(session.backend_name()),
+            Err(err) => {
+                error!("Failed to read the COSMIC input config: {err}");
+                return;
+            }
+        };
+        if self.saved.as_ref() == Some(&current) {
+            return;
+        }
+        match current.save(path) {
+            Ok(()) => self.saved = Some(current),
+            Err(err) => error!("Failed to save the applied state to {}: {err}", path.display()),
+        }
+    }
+}
+
+fn record(audit: Option<&AuditLog>, backend: &str, sourced: &SourcedEvent) {
+    let Some(audit) = audit else {
+        return;
+    };
+    if let Err(err) = audit.record(backend, sourced) {
+        error!("Failed to write audit log {}: {err}", audit.path().display());
+    }
+}
+
+/// Add an event sent to `backend` to the --record file, whether or not it was applied.
+fn remember(
+    recording: Option<&Recording>,
+    backend: &str,
+    sourced: &SourcedEvent,
+    result: &CompositorResult,
+) {
+    let Some(recording) = recording else {
+        return;
+    };
+    if let Err(err) = recording.record(&Recorded::new(backend, sourced, result)) {
+        error!("Failed to write recording {}: {err}", recording.path().display());
+    }
+}
+
+/// Tell JSON-RPC subscribers about an event sent to `backend`.
+fn notify(
+    subscribers: &Subscribers,
+    backend: &str,
+    sourced: &SourcedEvent,
+    result: &CompositorResult,
+) {
+    #[cfg(feature = "jsonrpc")]
+    if !subscribers.is_empty() {
+        let notification = cosmolith::jsonrpc::applied_notification(backend, sourced, result);
+        subscribers.broadcast(&notification);
+    }
+    #[cfg(not(feature = "jsonrpc"))]
+    let _ = (subscribers, backend, sourced, result);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> Snapshot {
+        Snapshot {
+            path: None,
+            saved: None,
+            dirty: false,
+            stale: false,
+        }
+    }
+
+    #[test]
+    fn a_failed_batch_is_not_saved_over_by_a_later_success() {
+        let mut snapshot = snapshot();
+        snapshot.note(Some(false));
+        snapshot.note(Some(true));
+        assert!(!snapshot.should_save());
+        snapshot.note(None);
+        assert!(!snapshot.should_save());
+
+        snapshot.note_resync(Some(true));
+        assert!(snapshot.should_save());
+    }
+
+    #[test]
+    fn a_failed_resync_keeps_the_snapshot_stale() {
+        let mut snapshot = snapshot();
+        snapshot.note(Some(true));
+        assert!(snapshot.should_save());
+        snapshot.note_resync(Some(false));
+        assert!(!snapshot.should_save());
+    }
+}
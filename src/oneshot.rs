@@ -0,0 +1,42 @@
+// `cosmolith apply`: push the current COSMIC input settings once and exit, for login
+// scripts and setups without a resident daemon.
+
+use std::error::Error;
+
+use tracing::warn;
+
+use cosmolith::compositor::trace;
+use cosmolith::state::AppliedState;
+
+use crate::cli::open_session;
+
+/// Apply every supported input setting currently stored in COSMIC, the same events the
+/// daemon sends on SIGHUP. Fails when any of them could not be applied.
+pub fn run(forced: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let session = open_session(forced).map_err(|err| err as Box<dyn Error>)?;
+    println!("Applying the current COSMIC settings to {}:", session.backend_name());
+
+    let mut failures = 0;
+    for (name, result) in session.sync_current().map_err(|err| err as Box<dyn Error>)? {
+        match result {
+            Ok(()) => println!("  {name:36} applied"),
+            Err(err) => {
+                failures += 1;
+                println!("  {name:36} FAILED: {err}");
+            }
+        }
+    }
+
+    if failures > 0 {
+        return Err(format!("{failures} setting(s) could not be applied").into());
+    }
+    // A daemon started later only has to send what changes after this.
+    if let Some(path) = AppliedState::default_path().filter(|_| !trace::dry_run()) {
+        let state = AppliedState::current().map_err(|err| err as Box<dyn Error>)?;
+        let state = state.applied_to(session.backend_name());
+        if let Err(err) = state.save(&path) {
+            warn!("Failed to save the applied state to {}: {err}", path.display());
+        }
+    }
+    Ok(())
+}
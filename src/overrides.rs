@@ -0,0 +1,296 @@
+//! Per-event overrides from the `[overrides]` section of the cosmolith config: leave an
+//! event out, or send a fixed value instead of COSMIC's, on every backend or only some.
+//!
+//! ```toml
+//! # Keep Hyprland's own natural scroll setting.
+//! [overrides.touchpad_natural_scroll]
+//! disable = true
+//! backends = ["Hyprland"]
+//!
+//! # Tap-to-click stays off, whatever COSMIC says.
+//! [overrides.touchpad_tap_enabled]
+//! value = false
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::compositor::BACKENDS;
+use crate::event::Event;
+use crate::event::catalog::EVENTS;
+
+/// One `[overrides.<event>]` table. Exactly one of `disable` and `value` is set.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Override {
+    /// Never send the event.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub disable: bool,
+    /// Send this instead of COSMIC's value. Written as the event's payload (see
+    /// `--list-events`): `false` for a `bool`, `"Flat"` for an `AccelProfile`, ...
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<toml::Value>,
+    /// Backend names, as in `--matrix`, the override is limited to. Empty for all.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub backends: Vec<String>,
+}
+
+impl Override {
+    fn applies_to(&self, backend: &str) -> bool {
+        self.backends.is_empty()
+            || self
+                .backends
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(backend))
+    }
+}
+
+/// The `[overrides]` section, keyed by event name (see `Event::name`).
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "BTreeMap<String, Override>")]
+pub struct Overrides(BTreeMap<String, Override>);
+
+impl TryFrom<BTreeMap<String, Override>> for Overrides {
+    type Error = String;
+
+    /// Reject unknown events and backends, so a typo does not silently do nothing.
+    fn try_from(overrides: BTreeMap<String, Override>) -> Result<Self, String> {
+        for (name, rule) in &overrides {
+            if !EVENTS.iter().any(|info| info.name == *name) {
+                return Err(format!("unknown event {name:?} (see --list-events)"));
+            }
+            if rule.disable == rule.value.is_some() {
+                return Err(format!("{name}: set either `disable = true` or a `value`"));
+            }
+            let unknown = rule.backends.iter().find(|backend| {
+                !BACKENDS
+                    .iter()
+                    .any(|(known, _)| known.eq_ignore_ascii_case(backend))
+            });
+            if let Some(backend) = unknown {
+                return Err(format!(
+                    "{name}: unknown backend {backend:?} (see --matrix)"
+                ));
+            }
+        }
+        Ok(Overrides(overrides))
+    }
+}
+
+impl Overrides {
+    pub const fn new() -> Self {
+        Overrides(BTreeMap::new())
+    }
+
+    /// `event` as `backend` should get it: `None` when it is disabled there, with the
+    /// configured value when there is one. A value that does not fit the event is logged
+    /// and COSMIC's is kept.
+    pub fn rewrite(&self, backend: &str, event: Event) -> Option<Event> {
+        let name = event.name();
+        let Some(rule) = self.0.get(name).filter(|rule| rule.applies_to(backend)) else {
+            return Some(event);
+        };
+        if rule.disable {
+            return None;
+        }
+        let Some(value) = &rule.value else {
+            return Some(event);
+        };
+        match with_value(&event, value) {
+            Ok(overridden) => {
+                debug!("Overriding {name} for {backend}: {overridden:?}");
+                Some(overridden)
+            }
+            Err(err) => {
+                warn!("Ignoring the {name} override: {err}");
+                Some(event)
+            }
+        }
+    }
+}
+
+static OVERRIDES: RwLock<Overrides> = RwLock::new(Overrides::new());
+
+/// Set the overrides every event goes through, from the cosmolith config.
+pub fn set_overrides(overrides: Overrides) {
+    *OVERRIDES.write().unwrap_or_else(|err| err.into_inner()) = overrides;
+}
+
+/// `Overrides::rewrite` with the overrides from the config.
+pub fn apply(backend: &str, event: Event) -> Option<Event> {
+    OVERRIDES
+        .read()
+        .unwrap_or_else(|err| err.into_inner())
+        .rewrite(backend, event)
+}
+
+/// `event` with its payload replaced by `value`, through the serde representation that
+/// recordings use.
+fn with_value(event: &Event, value: &toml::Value) -> Result<Event, String> {
+    let mut json = serde_json::to_value(event).map_err(|err| err.to_string())?;
+    let payload = payload_mut(&mut json).ok_or("the event has no value to override")?;
+    *payload = serde_json::to_value(value).map_err(|err| err.to_string())?;
+    serde_json::from_value(json).map_err(|err| format!("{value} does not fit: {err}"))
+}
+
+/// The payload inside a serialized `Event`: `{"Input": {"TouchPad": {"event": {"TapEnabled":
+/// true}, ..}}}` has `true`. `None` for unit variants, which carry nothing.
+fn payload_mut(event: &mut serde_json::Value) -> Option<&mut serde_json::Value> {
+    let (kind, inner) = single_entry(event)?;
+    let leaf = match kind.as_str() {
+        // A plain struct, not an enum of settings.
+        "ColorTemp" => return Some(inner),
+        "Input" => {
+            let (device, inner) = single_entry(inner)?;
+            if device == "Keyboard" {
+                inner
+            } else {
+                inner.get_mut("event")?
+            }
+        }
+        _ => inner,
+    };
+    single_entry(leaf).map(|(_, payload)| payload)
+}
+
+/// The variant name and content of an externally tagged enum value.
+fn single_entry(value: &mut serde_json::Value) -> Option<(&String, &mut serde_json::Value)> {
+    let object = value.as_object_mut().filter(|object| object.len() == 1)?;
+    object.iter_mut().next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmic_comp_config::input::ScrollConfig;
+
+    use crate::event::color_temp::ColorTempEvent;
+    use crate::event::input::{InputEvent, KeyboardEvent, TouchpadEvent, is_redundant};
+
+    fn parse(contents: &str) -> Result<Overrides, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    fn tap(enabled: bool) -> Event {
+        Event::Input(InputEvent::touchpad(TouchpadEvent::TapEnabled(enabled)))
+    }
+
+    fn tap_enabled(event: Option<Event>) -> Option<bool> {
+        match event? {
+            Event::Input(InputEvent::TouchPad {
+                event: TouchpadEvent::TapEnabled(enabled),
+                ..
+            }) => Some(enabled),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn overrides_round_trip() {
+        let overrides = parse(
+            "[touchpad_natural_scroll]\n\
+             disable = true\n\
+             backends = [\"Hyprland\"]\n\
+             \n\
+             [touchpad_accel_speed]\n\
+             value = -0.25\n",
+        )
+        .unwrap();
+        let serialized = toml::to_string(&overrides).unwrap();
+        assert_eq!(parse(&serialized).unwrap(), overrides);
+        assert!(overrides.0["touchpad_natural_scroll"].disable);
+        assert_eq!(
+            overrides.0["touchpad_accel_speed"].value,
+            Some(toml::Value::Float(-0.25))
+        );
+    }
+
+    #[test]
+    fn typos_are_rejected() {
+        assert!(parse("[touchpad_natural_scrol]\ndisable = true\n").is_err());
+        let typo = "[touchpad_tap_enabled]\nvalue = false\nbackends = [\"Hyperland\"]\n";
+        assert!(parse(typo).is_err());
+        assert!(parse("[touchpad_tap_enabled]\ndisable = true\nvalue = false\n").is_err());
+        assert!(parse("[touchpad_tap_enabled]\n").is_err());
+        assert!(parse("[touchpad_tap_enabled]\ndisabled = true\n").is_err());
+    }
+
+    #[test]
+    fn disable_is_limited_to_its_backends() {
+        let overrides =
+            parse("[touchpad_tap_enabled]\ndisable = true\nbackends = [\"hyprland\"]\n").unwrap();
+        assert!(overrides.rewrite("Hyprland", tap(true)).is_none());
+        assert!(overrides.rewrite("Sway", tap(true)).is_some());
+    }
+
+    #[test]
+    fn value_replaces_the_payload() {
+        let overrides = parse(
+            "[touchpad_tap_enabled]\nvalue = false\n\n\
+             [keyboard_repeat_rate]\nvalue = 40\n\n\
+             [color_temp]\nvalue = { temperature = 3500, enabled = true }\n",
+        )
+        .unwrap();
+        assert_eq!(
+            tap_enabled(overrides.rewrite("Sway", tap(true))),
+            Some(false)
+        );
+
+        let rate = Event::Input(InputEvent::Keyboard(KeyboardEvent::RepeatRate(25)));
+        let Some(Event::Input(InputEvent::Keyboard(rate))) = overrides.rewrite("Sway", rate) else {
+            panic!("not a keyboard event");
+        };
+        assert_eq!(rate, KeyboardEvent::RepeatRate(40));
+
+        let night = Event::ColorTemp(ColorTempEvent {
+            temperature: 4500,
+            enabled: false,
+        });
+        let Some(Event::ColorTemp(night)) = overrides.rewrite("Sway", night) else {
+            panic!("not a night light event");
+        };
+        assert_eq!(night.temperature, 3500);
+        assert!(night.enabled);
+    }
+
+    #[test]
+    fn a_disabled_field_is_not_applied_through_its_whole_config() {
+        let overrides =
+            parse("[touchpad_natural_scroll]\ndisable = true\nbackends = [\"Hyprland\"]\n")
+                .unwrap();
+        let (_, capabilities) = BACKENDS
+            .iter()
+            .find(|(backend, _)| *backend == "Hyprland")
+            .unwrap();
+        // Setting a scroll config sends it whole and field by field.
+        let scroll = ScrollConfig {
+            method: None,
+            natural_scroll: Some(true),
+            scroll_button: None,
+            scroll_factor: None,
+        };
+        let touchpad = |event| Event::Input(InputEvent::touchpad(event));
+        let batch = [
+            touchpad(TouchpadEvent::ScrollConfig(Some(scroll))),
+            touchpad(TouchpadEvent::NaturalScroll(Some(true))),
+        ];
+        let sent: Vec<_> = batch
+            .iter()
+            .filter(|event| !is_redundant(event, &batch, capabilities))
+            .filter_map(|event| overrides.rewrite("Hyprland", event.clone()))
+            .collect();
+        assert!(sent.is_empty(), "natural scroll still sent: {sent:?}");
+    }
+
+    #[test]
+    fn a_value_of_the_wrong_type_keeps_cosmics() {
+        let overrides = parse("[touchpad_tap_enabled]\nvalue = \"off\"\n").unwrap();
+        assert_eq!(
+            tap_enabled(overrides.rewrite("Sway", tap(true))),
+            Some(true)
+        );
+    }
+}
@@ -0,0 +1,131 @@
+// Machine-readable record of every event sent to a backend, for `cosmolith replay`.
+
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, SecondsFormat};
+use serde::{Deserialize, Serialize};
+
+use crate::compositor::CompositorResult;
+use crate::event::{Event, SourcedEvent};
+
+/// One line of a recording: an event as it was sent to a backend, and how that went.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recorded {
+    /// RFC 3339 local time the backend returned.
+    pub at: String,
+    pub backend: String,
+    pub source: String,
+    pub event: Event,
+    /// The backend's error, `None` when the event was applied.
+    pub error: Option<String>,
+}
+
+impl Recorded {
+    pub fn new(backend: &str, sourced: &SourcedEvent, result: &CompositorResult) -> Self {
+        Self {
+            at: Local::now().to_rfc3339_opts(SecondsFormat::Millis, false),
+            backend: backend.to_string(),
+            source: sourced.source.to_string(),
+            event: sourced.event.clone(),
+            error: result.as_ref().err().map(|err| err.to_string()),
+        }
+    }
+}
+
+/// JSON-lines file of `Recorded` events, failed ones included, unlike `AuditLog`.
+///
+/// Opened in append mode for every entry, like `AuditLog`, so it can be rotated freely.
+#[derive(Debug, Clone)]
+pub struct Recording {
+    path: PathBuf,
+}
+
+impl Recording {
+    /// Create the parent directory so the first write does not fail.
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+        Ok(Self { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn record(&self, entry: &Recorded) -> io::Result<()> {
+        let line = serde_json::to_string(entry).map_err(io::Error::other)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{line}")
+    }
+
+    /// Every entry in the file at `path`, in the order they were recorded.
+    pub fn read(path: &Path) -> Result<Vec<Recorded>, Box<dyn Error + Send + Sync>> {
+        parse(&fs::read_to_string(path)?)
+            .map_err(|err| format!("{}: {err}", path.display()).into())
+    }
+}
+
+fn parse(contents: &str) -> Result<Vec<Recorded>, String> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line).map_err(|err| format!("line {}: {err}", index + 1))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use crate::event::Source;
+    use crate::event::input::{InputEvent, TouchpadEvent};
+
+    #[test]
+    fn entries_round_trip_with_their_result() {
+        let sourced = SourcedEvent::new(
+            Event::Input(InputEvent::touchpad(TouchpadEvent::AccelSpeed(0.5))),
+            Source {
+                namespace: "com.system76.CosmicComp",
+                key: Some("input_touchpad"),
+            },
+        );
+        let failed: CompositorResult = Err(Error::NotImplemented {
+            handler: "touchpad_accel_speed",
+        });
+        let lines = [
+            Recorded::new("Sway", &sourced, &Ok(())),
+            Recorded::new("Sway", &sourced, &failed),
+        ]
+        .iter()
+        .map(|entry| serde_json::to_string(entry).unwrap())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+        let entries = parse(&lines).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].event.name(), "touchpad_accel_speed");
+        assert_eq!(entries[0].source, "com.system76.CosmicComp/input_touchpad");
+        assert_eq!(entries[0].error, None);
+        assert!(entries[1].error.as_deref().unwrap().contains("not implemented"));
+    }
+
+    #[test]
+    fn a_bad_line_is_reported_by_number() {
+        let err = parse("\n{}\n").unwrap_err();
+        assert!(err.starts_with("line 2:"), "{err}");
+    }
+}
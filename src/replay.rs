@@ -0,0 +1,188 @@
+// Troubleshooting: capture a user's cosmic-config settings and replay them locally.
+//
+// `capture-config <dir>` copies the namespaces cosmolith reads into `<dir>/cosmic/...`,
+// the same layout as `$XDG_CONFIG_HOME`. `replay-config <dir>` copies that snapshot to a
+// temporary directory, points `XDG_CONFIG_HOME` at it and runs the watchers, debouncer and
+// a dry-run backend against it, so no compositor or real config is touched.
+//
+// `replay <file>` instead applies the events of a `--record` file, in order, to a real
+// compositor, to reproduce a user's exact sequence.
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::Instant;
+
+use tracing::warn;
+
+use cosmolith::compositor::Compositor;
+use cosmolith::compositor::dry_run::DryRun;
+use cosmolith::debounce::{DebounceConfig, Debouncer};
+use cosmolith::event::SourcedEvent;
+use cosmolith::recording::Recording;
+use cosmolith::watcher::color_temp::{self, start_color_temp_watcher};
+use cosmolith::watcher::cursor::{self, start_cursor_watcher};
+use cosmolith::watcher::files::namespace_dir;
+use cosmolith::watcher::input::{self, current_input_events};
+use cosmolith::watcher::shortcuts::{self, start_shortcuts_watcher};
+use cosmolith::watcher::theme::{self, start_theme_watcher};
+use cosmolith::watcher::workspaces::{self, start_workspaces_watcher};
+
+use crate::cli::open_session;
+
+/// Every namespace cosmolith reads, with its config version.
+const NAMESPACES: &[(&str, u64)] = &[
+    (input::INPUTNAMESPACE, input::VERSION),
+    (shortcuts::SHORTCUTS_NAMESPACE, shortcuts::VERSION),
+    (workspaces::WORKSPACES_NAMESPACE, workspaces::VERSION),
+    (theme::THEME_MODE_NAMESPACE, theme::VERSION),
+    (cursor::COSMIC_TK_NAMESPACE, cursor::VERSION),
+    (color_temp::NIGHT_LIGHT_NAMESPACE, color_temp::VERSION),
+];
+
+/// Copy the user's cosmic-config files for every namespace into `dir`.
+pub fn capture(dir: &Path) -> Result<(), Box<dyn Error>> {
+    for (namespace, version) in NAMESPACES {
+        let from = namespace_dir(namespace, *version).ok_or("no config directory")?;
+        if !from.is_dir() {
+            println!("  {namespace}: not set, skipped");
+            continue;
+        }
+        let copied = copy_dir(&from, &snapshot_dir(dir, namespace, *version))?;
+        println!("  {namespace}: {copied} key(s)");
+    }
+    println!("Captured to {}", dir.display());
+    Ok(())
+}
+
+/// Run the snapshot in `dir` through the pipeline, printing what `backend` would receive.
+///
+/// Must run before any other thread starts, since it changes `XDG_CONFIG_HOME`.
+pub fn replay(dir: &Path, backend: &str, debounce: DebounceConfig) -> Result<(), Box<dyn Error>> {
+    let dry_run =
+        DryRun::emulating(backend).ok_or_else(|| format!("unknown backend `{backend}`"))?;
+    if !dir.join("cosmic").is_dir() {
+        return Err(format!(
+            "{} has no cosmic/ directory; see capture-config",
+            dir.display()
+        )
+        .into());
+    }
+
+    // Work on a copy: cosmic-config may create or write files under the config root.
+    let root = std::env::temp_dir().join(format!("cosmolith-replay-{}", std::process::id()));
+    copy_dir(&dir.join("cosmic"), &root.join("cosmic"))?;
+    // SAFETY: replay runs on the main thread before any other thread is started.
+    unsafe { std::env::set_var("XDG_CONFIG_HOME", &root) };
+
+    let result = run_pipeline(&dry_run, debounce);
+    if let Err(err) = fs::remove_dir_all(&root) {
+        warn!("Failed to remove {}: {err}", root.display());
+    }
+    result
+}
+
+/// Apply every event recorded in `file` to the detected or `forced` backend, printing
+/// each result next to the one recorded. Fails when any event could not be applied.
+pub fn replay_recording(file: &Path, forced: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let entries = Recording::read(file).map_err(|err| err as Box<dyn Error>)?;
+    let session = open_session(forced).map_err(|err| err as Box<dyn Error>)?;
+    println!(
+        "Replaying {} event(s) against {}:",
+        entries.len(),
+        session.backend_name()
+    );
+
+    let mut failures = 0;
+    for entry in entries {
+        let name = entry.event.name();
+        let was = match &entry.error {
+            Some(err) => format!("failed on {}: {err}", entry.backend),
+            None => format!("applied on {}", entry.backend),
+        };
+        match session.apply(entry.event) {
+            Ok(()) => println!("  {name:36} applied (recorded {}, {was})", entry.at),
+            Err(err) => {
+                failures += 1;
+                println!("  {name:36} FAILED: {err} (recorded {}, {was})", entry.at);
+            }
+        }
+    }
+
+    if failures > 0 {
+        return Err(format!("{failures} event(s) could not be applied").into());
+    }
+    Ok(())
+}
+
+fn run_pipeline(dry_run: &DryRun, debounce: DebounceConfig) -> Result<(), Box<dyn Error>> {
+    let (tx, rx) = mpsc::channel::<SourcedEvent>();
+    let tx = Arc::new(Mutex::new(tx));
+
+    // The watchers send the current shortcuts, workspaces, theme, cursor and night light when
+    // they start; input settings are read the same way `Session::sync_current` does.
+    let _shortcuts_watcher = start_shortcuts_watcher(&tx)?;
+    let _workspaces_watcher = start_workspaces_watcher(&tx)?;
+    let _theme_watcher = start_theme_watcher(&tx)?;
+    let _cursor_watcher = start_cursor_watcher(&tx)?;
+    let _color_temp_watcher = start_color_temp_watcher(&tx)?;
+    let input_events = current_input_events().map_err(|err| err as Box<dyn Error>)?;
+
+    let mut debouncer = Debouncer::new(debounce);
+    let now = Instant::now();
+    let mut events: Vec<SourcedEvent> = input_events
+        .into_iter()
+        .chain(rx.try_iter())
+        .filter_map(|event| debouncer.push(event, now))
+        .collect();
+    events.extend(debouncer.flush());
+
+    println!(
+        "Replaying {} event(s) against {}:",
+        events.len(),
+        dry_run.backend()
+    );
+    let mut unsupported = Vec::new();
+    for sourced in events {
+        if dry_run.supports(&sourced.event) {
+            dry_run.apply_event(sourced.event)?;
+        } else {
+            unsupported.push(format!(
+                "{} (from {})",
+                sourced.event.name(),
+                sourced.source
+            ));
+        }
+    }
+    if !unsupported.is_empty() {
+        println!("Not supported by {}:", dry_run.backend());
+        for name in unsupported {
+            println!("  {name}");
+        }
+    }
+    Ok(())
+}
+
+fn snapshot_dir(dir: &Path, namespace: &str, version: u64) -> PathBuf {
+    dir.join("cosmic")
+        .join(namespace)
+        .join(format!("v{version}"))
+}
+
+/// Recursively copy `from` into `to`, returning the number of files copied.
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<usize> {
+    fs::create_dir_all(to)?;
+    let mut copied = 0;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copied += copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+            copied += 1;
+        }
+    }
+    Ok(copied)
+}
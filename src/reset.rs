@@ -0,0 +1,96 @@
+// Troubleshooting: put every setting cosmolith manages back to its libinput/XKB default.
+
+use std::error::Error;
+
+use cosmic_comp_config::input::{DeviceState, ScrollMethod, TapButtonMap};
+use cosmic_comp_config::{NumlockState, XkbConfig};
+
+use cosmolith::event::Event;
+use cosmolith::event::input::{InputEvent, KeyboardEvent, MouseEvent, TouchpadEvent};
+
+use crate::cli::open_session;
+
+/// Apply the default of every setting the detected backend supports, so the compositor's
+/// native behaviour can be tested without cosmolith's overrides.
+///
+/// Without `confirmed` only the plan is printed, since this rewrites the running config.
+pub fn run(confirmed: bool, forced: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let session = open_session(forced).map_err(|err| err as Box<dyn Error>)?;
+
+    let events: Vec<Event> = default_events()
+        .into_iter()
+        .filter(|event| session.supports(event))
+        .collect();
+
+    if !confirmed {
+        println!("Would reset on {}:", session.backend_name());
+        for event in &events {
+            println!("  {event:?}");
+        }
+        return Err("refusing to reset without --yes".into());
+    }
+
+    let mut failures = 0;
+    for event in events {
+        let name = event.name();
+        match session.apply(event) {
+            Ok(()) => println!("  {name:36} reset"),
+            Err(err) => {
+                failures += 1;
+                println!("  {name:36} FAILED: {err}");
+            }
+        }
+    }
+
+    if failures > 0 {
+        return Err(format!("{failures} setting(s) could not be reset").into());
+    }
+    Ok(())
+}
+
+/// libinput and XKB defaults for every setting, as fine-grained events. The composite
+/// events (`Acceleration`, `ScrollConfig`, `TapConfig`) are covered by their parts.
+fn default_events() -> Vec<Event> {
+    let xkb = XkbConfig::default();
+    let keyboard = [
+        KeyboardEvent::Rules(xkb.rules),
+        KeyboardEvent::Model(xkb.model),
+        KeyboardEvent::Layout(xkb.layout),
+        KeyboardEvent::Variant(xkb.variant),
+        KeyboardEvent::Options(None),
+        KeyboardEvent::RepeatDelay(xkb.repeat_delay),
+        KeyboardEvent::RepeatRate(xkb.repeat_rate),
+        KeyboardEvent::NumLock(NumlockState::BootOff),
+    ];
+    let touchpad = [
+        TouchpadEvent::State(DeviceState::Enabled),
+        TouchpadEvent::AccelSpeed(0.0),
+        TouchpadEvent::AccelProfile(None),
+        TouchpadEvent::DisableWhileTyping(Some(true)),
+        TouchpadEvent::LeftHanded(Some(false)),
+        TouchpadEvent::MiddleButtonEmulation(Some(false)),
+        TouchpadEvent::ScrollMethod(Some(ScrollMethod::TwoFinger)),
+        TouchpadEvent::NaturalScroll(Some(false)),
+        TouchpadEvent::ScrollFactor(Some(1.0)),
+        TouchpadEvent::TapEnabled(false),
+        TouchpadEvent::TapButtonMap(Some(TapButtonMap::LeftRightMiddle)),
+        TouchpadEvent::TapDrag(true),
+        TouchpadEvent::TapDragLock(false),
+    ];
+    let mouse = [
+        MouseEvent::State(DeviceState::Enabled),
+        MouseEvent::AccelSpeed(0.0),
+        MouseEvent::AccelProfile(None),
+        MouseEvent::LeftHanded(Some(false)),
+        MouseEvent::MiddleButtonEmulation(Some(false)),
+        MouseEvent::NaturalScroll(Some(false)),
+        MouseEvent::ScrollFactor(Some(1.0)),
+    ];
+
+    keyboard
+        .into_iter()
+        .map(|ev| Event::Input(InputEvent::Keyboard(ev)))
+        .chain(touchpad.into_iter().map(|ev| Event::Input(InputEvent::touchpad(ev))))
+        .chain(mouse.into_iter().map(|ev| Event::Input(InputEvent::mouse(ev))))
+        .collect()
+}
@@ -0,0 +1,185 @@
+// Diagnostic: exercise the detected backend's handlers against the live compositor.
+
+use std::error::Error;
+
+use cosmic_comp_config::input::InputConfig;
+use cosmic_comp_config::XkbConfig;
+use cosmic_config::Config;
+use tracing::warn;
+
+use cosmolith::compositor::Compositor;
+use cosmolith::event::Event;
+use cosmolith::event::input::{InputEvent, KeyboardEvent, MouseEvent, TouchpadEvent};
+use cosmolith::watcher::input::{INPUTNAMESPACE, InputClass, VERSION, read_class, read_xkb};
+
+use crate::cli::open_session;
+
+/// A known-safe change for one setting and the event that puts the current value back.
+struct Probe {
+    probe: Event,
+    restore: Event,
+}
+
+enum Outcome {
+    /// The backend does not list the event in its capabilities.
+    Unsupported,
+    /// The handler returned an error.
+    Failed(String),
+    /// The handler succeeded and the read-back value changed.
+    Confirmed,
+    /// The handler succeeded but the read-back value did not change.
+    NoEffect,
+    /// The handler succeeded and the backend cannot read the value back.
+    Applied,
+}
+
+/// Apply and revert a probe value for every supported setting, then print what took effect.
+///
+/// The values currently stored in COSMIC are the snapshot: every probe is followed by its
+/// restore event, whether or not the probe succeeded.
+pub fn run(forced: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let session = open_session(forced).map_err(|err| err as Box<dyn Error>)?;
+    let compositor = session.backend();
+
+    let config = Config::new(INPUTNAMESPACE, VERSION)?;
+    let probes = probes(
+        read_class(&config, InputClass::Touchpad),
+        read_class(&config, InputClass::Mouse),
+        read_xkb(&config),
+    );
+
+    println!("Self-test on {}:", compositor.name());
+    let mut failures = 0;
+    for probe in probes {
+        let name = probe.probe.name();
+        let outcome = run_probe(compositor, probe);
+        let line = match &outcome {
+            Outcome::Unsupported => "unsupported".to_string(),
+            Outcome::Failed(err) => format!("FAILED: {err}"),
+            Outcome::Confirmed => "ok (read back)".to_string(),
+            Outcome::NoEffect => "applied, but read-back value did not change".to_string(),
+            Outcome::Applied => "applied (no read-back)".to_string(),
+        };
+        if matches!(outcome, Outcome::Failed(_) | Outcome::NoEffect) {
+            failures += 1;
+        }
+        println!("  {name:36} {line}");
+    }
+
+    if failures > 0 {
+        return Err(format!("{failures} handler(s) did not take effect").into());
+    }
+    Ok(())
+}
+
+fn run_probe(compositor: &dyn Compositor, probe: Probe) -> Outcome {
+    if !compositor.supports(&probe.probe) {
+        return Outcome::Unsupported;
+    }
+
+    let name = probe.probe.name();
+    let before = compositor.read_back(name);
+    let applied = compositor.apply_event(probe.probe);
+    let after = compositor.read_back(name);
+
+    if let Err(err) = compositor.apply_event(probe.restore) {
+        warn!("Failed to restore {name}: {err}");
+    }
+
+    match (applied, before, after) {
+        (Err(err), _, _) => Outcome::Failed(err.to_string()),
+        (Ok(()), Some(before), Some(after)) if before != after => Outcome::Confirmed,
+        (Ok(()), Some(_), Some(_)) => Outcome::NoEffect,
+        (Ok(()), _, _) => Outcome::Applied,
+    }
+}
+
+/// Build probes from the current COSMIC settings. Booleans are flipped, numbers nudged;
+/// settings that are unset in COSMIC are skipped since there is nothing to restore.
+fn probes(
+    touchpad: Option<InputConfig>,
+    mouse: Option<InputConfig>,
+    keyboard: Option<XkbConfig>,
+) -> Vec<Probe> {
+    let mut probes = Vec::new();
+
+    if let Some(tp) = touchpad {
+        let ev = |ev| Event::Input(InputEvent::touchpad(ev));
+        let mut flip = |current: Option<bool>, make: fn(Option<bool>) -> TouchpadEvent| {
+            if let Some(current) = current {
+                probes.push(Probe {
+                    probe: ev(make(Some(!current))),
+                    restore: ev(make(Some(current))),
+                });
+            }
+        };
+        flip(tp.disable_while_typing, TouchpadEvent::DisableWhileTyping);
+        flip(tp.left_handed, TouchpadEvent::LeftHanded);
+        flip(tp.middle_button_emulation, TouchpadEvent::MiddleButtonEmulation);
+        flip(
+            tp.scroll_config.as_ref().and_then(|scroll| scroll.natural_scroll),
+            TouchpadEvent::NaturalScroll,
+        );
+        if let Some(tap) = &tp.tap_config {
+            for (current, make) in [
+                (tap.enabled, TouchpadEvent::TapEnabled as fn(bool) -> TouchpadEvent),
+                (tap.drag, TouchpadEvent::TapDrag),
+                (tap.drag_lock, TouchpadEvent::TapDragLock),
+            ] {
+                probes.push(Probe {
+                    probe: ev(make(!current)),
+                    restore: ev(make(current)),
+                });
+            }
+        }
+        if let Some(accel) = &tp.acceleration {
+            probes.push(Probe {
+                probe: ev(TouchpadEvent::AccelSpeed(nudge(accel.speed))),
+                restore: ev(TouchpadEvent::AccelSpeed(accel.speed)),
+            });
+        }
+    }
+
+    if let Some(mouse) = mouse {
+        let ev = |ev| Event::Input(InputEvent::mouse(ev));
+        let mut flip = |current: Option<bool>, make: fn(Option<bool>) -> MouseEvent| {
+            if let Some(current) = current {
+                probes.push(Probe {
+                    probe: ev(make(Some(!current))),
+                    restore: ev(make(Some(current))),
+                });
+            }
+        };
+        flip(mouse.left_handed, MouseEvent::LeftHanded);
+        flip(mouse.middle_button_emulation, MouseEvent::MiddleButtonEmulation);
+        flip(
+            mouse.scroll_config.as_ref().and_then(|scroll| scroll.natural_scroll),
+            MouseEvent::NaturalScroll,
+        );
+        if let Some(accel) = &mouse.acceleration {
+            probes.push(Probe {
+                probe: ev(MouseEvent::AccelSpeed(nudge(accel.speed))),
+                restore: ev(MouseEvent::AccelSpeed(accel.speed)),
+            });
+        }
+    }
+
+    if let Some(kb) = keyboard {
+        let ev = |ev| Event::Input(InputEvent::Keyboard(ev));
+        probes.push(Probe {
+            probe: ev(KeyboardEvent::RepeatDelay(kb.repeat_delay + 10)),
+            restore: ev(KeyboardEvent::RepeatDelay(kb.repeat_delay)),
+        });
+        probes.push(Probe {
+            probe: ev(KeyboardEvent::RepeatRate(kb.repeat_rate + 1)),
+            restore: ev(KeyboardEvent::RepeatRate(kb.repeat_rate)),
+        });
+    }
+
+    probes
+}
+
+/// A small step towards the middle of libinput's -1.0..=1.0 speed range.
+fn nudge(speed: f64) -> f64 {
+    if speed > 0.0 { speed - 0.1 } else { speed + 0.1 }
+}
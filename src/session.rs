@@ -0,0 +1,149 @@
+//! The stable entry point of the library: detect the running desktop, initialize its
+//! backend and apply COSMIC settings to it.
+//!
+//! ```no_run
+//! use cosmolith::session::Session;
+//!
+//! let session = Session::detect()?;
+//! println!("Syncing to {}", session.backend_name());
+//! for (name, result) in session.sync_current()? {
+//!     if let Err(err) = result {
+//!         eprintln!("{name}: {err}");
+//!     }
+//! }
+//! # Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
+//! ```
+//!
+//! The modules behind it (`compositor`, `watcher`, `debounce`, ...) are public for the
+//! binary and may change between releases; `Session` is what external programs should use.
+
+use std::error::Error as StdError;
+
+use crate::compositor::trace;
+use crate::compositor::{Compositor, CompositorResult, desktop_for, init_compositor};
+use crate::error::Error;
+use crate::event::Event;
+use crate::event::input::is_redundant;
+use crate::identifier::{Desktop, get_current_session};
+use crate::overrides;
+use crate::watcher::input::current_input_events;
+
+/// A detected desktop with its initialized backend.
+pub struct Session {
+    desktop: String,
+    backend: Box<dyn Compositor>,
+}
+
+impl Session {
+    /// Detect the running desktop and initialize its backend.
+    ///
+    /// Fails with `Error::DetectionFailed` when cosmolith has no backend for the desktop,
+    /// and with the backend's own `init` error when it could not connect.
+    pub fn detect() -> Result<Session, Box<dyn StdError + Send + Sync>> {
+        Self::for_desktop(get_current_session())
+    }
+
+    /// Initialize the backend for a known desktop, skipping detection.
+    pub fn for_desktop(desktop: Desktop) -> Result<Session, Box<dyn StdError + Send + Sync>> {
+        let name = format!("{desktop:?}");
+        match init_compositor(desktop)? {
+            Some(backend) => Ok(Session {
+                desktop: name,
+                backend,
+            }),
+            None => Err(Error::DetectionFailed(format!("no backend for {name}")).into()),
+        }
+    }
+
+    /// Wrap an already initialized backend, e.g. a `MockCompositor` in tests.
+    pub fn with_backend(desktop: impl Into<String>, backend: Box<dyn Compositor>) -> Session {
+        Session {
+            desktop: desktop.into(),
+            backend,
+        }
+    }
+
+    /// Initialize the `BACKENDS` entry called `backend` (see `--matrix`), skipping
+    /// detection.
+    ///
+    /// There is no fallback: fails with `Error::CompositorNotRunning` when the backend is
+    /// unknown or cannot connect, typically because that compositor is not running.
+    pub fn for_backend(backend: &str) -> Result<Session, Box<dyn StdError + Send + Sync>> {
+        let not_running = |reason: String| Error::CompositorNotRunning {
+            backend: backend.to_string(),
+            reason,
+        };
+        let desktop = desktop_for(backend).ok_or_else(|| not_running("unknown backend".into()))?;
+        Self::for_desktop(desktop).map_err(|err| not_running(err.to_string()).into())
+    }
+
+    /// The detected desktop, as reported by `identifier`.
+    pub fn desktop(&self) -> &str {
+        &self.desktop
+    }
+
+    /// Human-readable name of the backend, e.g. "Hyprland".
+    pub fn backend_name(&self) -> &'static str {
+        self.backend.name()
+    }
+
+    /// Names of the events the backend handles (see `Event::name`).
+    pub fn capabilities(&self) -> &'static [&'static str] {
+        self.backend.capabilities()
+    }
+
+    pub fn supports(&self, event: &Event) -> bool {
+        self.backend.supports(event)
+    }
+
+    /// Apply one event. Events outside `capabilities` fail with `Error::NotImplemented`
+    /// instead of reaching the backend. The result is logged (see `trace::dispatch`).
+    pub fn apply(&self, event: Event) -> CompositorResult {
+        trace::dispatch(self.backend_name(), event.name(), || {
+            if !self.supports(&event) {
+                let handler = event.name();
+                return Err(Error::NotImplemented { handler });
+            }
+            self.backend.apply_event(event)
+        })
+    }
+
+    /// Apply a batch of events in order, in one IPC transaction where the backend
+    /// supports it (see `Compositor::apply_events`). Fails with `Error::NotImplemented`
+    /// before anything is applied when an event is outside `capabilities`.
+    pub fn apply_events(&self, events: Vec<Event>) -> CompositorResult {
+        let names: Vec<_> = events.iter().map(Event::name).collect();
+        trace::dispatch(self.backend_name(), &names.join(","), || {
+            if let Some(event) = events.iter().find(|event| !self.supports(event)) {
+                let handler = event.name();
+                return Err(Error::NotImplemented { handler });
+            }
+            self.backend.apply_events(events)
+        })
+    }
+
+    /// Apply every supported input setting currently stored in COSMIC, continuing past
+    /// failures, as the config's `[overrides]` have them. Coarse events the backend gets
+    /// field by field are left out (see `is_redundant`). Returns each applied event's
+    /// name with its result.
+    pub fn sync_current(
+        &self,
+    ) -> Result<Vec<(&'static str, CompositorResult)>, Box<dyn StdError + Send + Sync>> {
+        let events: Vec<_> = current_input_events()?
+            .into_iter()
+            .map(|sourced| sourced.event)
+            .collect();
+        Ok(events
+            .iter()
+            .filter(|event| self.supports(event))
+            .filter(|event| !is_redundant(event, &events, self.capabilities()))
+            .filter_map(|event| overrides::apply(self.backend_name(), event.clone()))
+            .map(|event| (event.name(), self.apply(event)))
+            .collect())
+    }
+
+    /// The backend itself, for the lower-level hooks such as `Compositor::read_back`.
+    pub fn backend(&self) -> &dyn Compositor {
+        self.backend.as_ref()
+    }
+}
@@ -0,0 +1,140 @@
+// Snapshot of the COSMIC input config cosmolith last applied, kept across restarts.
+
+use std::env;
+use std::error::Error;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use cosmic_comp_config::input::InputConfig;
+use cosmic_comp_config::{KeyboardConfig, XkbConfig};
+use cosmic_config::Config;
+use serde::{Deserialize, Serialize};
+
+use crate::compositor::Compositor;
+use crate::compositor::config_file::{atomic_write, read_or};
+use crate::watcher::input::{INPUTNAMESPACE, InputClass, VERSION, read_class, read_xkb};
+
+/// Environment variables that identify a running compositor instance. A new value means
+/// the compositor was restarted since the snapshot was taken.
+const INSTANCE_VARIABLES: &[&str] = &["HYPRLAND_INSTANCE_SIGNATURE", "SWAYSOCK"];
+
+/// The keyboard, touchpad and mouse config as of the last fully applied change.
+///
+/// At startup the current COSMIC config is diffed against this instead of the defaults,
+/// so only settings that changed while cosmolith was not running are sent again. That
+/// only holds for the backend and compositor instance it was applied to (see
+/// `applies_to`).
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppliedState {
+    /// Name of the backend the config was applied to.
+    #[serde(default)]
+    pub backend: Option<String>,
+    /// The compositor instance it was applied to, from `INSTANCE_VARIABLES`.
+    #[serde(default)]
+    pub instance: Option<String>,
+    pub keyboard: Option<XkbConfig>,
+    pub touchpad: Option<InputConfig>,
+    pub mouse: Option<InputConfig>,
+    /// `keyboard_config`. Sent at every start whatever was applied before, since
+    /// restoring numlock only matters at the start of a session.
+    #[serde(default)]
+    pub numlock: Option<KeyboardConfig>,
+}
+
+impl AppliedState {
+    /// `$XDG_STATE_HOME/cosmolith/state.json`, falling back to `~/.local/state`.
+    pub fn default_path() -> Option<PathBuf> {
+        Some(dirs::state_dir()?.join("cosmolith").join("state.json"))
+    }
+
+    /// The config currently stored in COSMIC.
+    pub fn current() -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let config = Config::new(INPUTNAMESPACE, VERSION)?;
+        Ok(Self {
+            backend: None,
+            instance: None,
+            keyboard: read_xkb(&config),
+            touchpad: read_class(&config, InputClass::Touchpad),
+            mouse: read_class(&config, InputClass::Mouse),
+            numlock: read_class(&config, InputClass::Numlock),
+        })
+    }
+
+    /// This state, marked as applied to `backend` in the running compositor instance.
+    pub fn applied_to(self, backend: &str) -> Self {
+        Self {
+            backend: Some(backend.to_string()),
+            instance: compositor_instance(),
+            ..self
+        }
+    }
+
+    /// Whether the compositor still has this state, so the startup sync can be a diff
+    /// against it. Not when it was applied to another backend or compositor instance, or
+    /// when `backend` does not keep settings across compositor restarts.
+    pub fn applies_to(&self, backend: &dyn Compositor) -> bool {
+        backend.keeps_settings()
+            && self.backend.as_deref() == Some(backend.name())
+            && self.instance == compositor_instance()
+    }
+
+    /// Read the snapshot at `path`, or `None` when nothing was saved yet.
+    pub fn load(path: &Path) -> Result<Option<Self>, Box<dyn Error + Send + Sync>> {
+        let contents = read_or(path, "")?;
+        if contents.trim().is_empty() {
+            return Ok(None);
+        }
+        serde_json::from_str(&contents)
+            .map(Some)
+            .map_err(|err| format!("{}: {err}", path.display()).into())
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let contents = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        atomic_write(path, &contents)
+    }
+}
+
+/// The first of `INSTANCE_VARIABLES` that is set.
+fn compositor_instance() -> Option<String> {
+    INSTANCE_VARIABLES
+        .iter()
+        .find_map(|name| env::var(name).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compositor::mock::MockCompositor;
+
+    #[test]
+    fn round_trips_through_json() {
+        let state = AppliedState {
+            keyboard: Some(XkbConfig {
+                layout: "de,us".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+        .applied_to("Sway");
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(serde_json::from_str::<AppliedState>(&json).unwrap(), state);
+        // Snapshots saved before the backend was recorded still load.
+        let old: AppliedState = serde_json::from_str(r#"{"keyboard":null}"#).unwrap();
+        assert_eq!(old.backend, None);
+    }
+
+    #[test]
+    fn a_snapshot_only_applies_to_its_backend() {
+        let mock = MockCompositor::new(&[]);
+        let state = AppliedState::default();
+        assert!(!state.applies_to(&mock));
+        assert!(state.clone().applied_to("mock").applies_to(&mock));
+        assert!(!state.clone().applied_to("Sway").applies_to(&mock));
+        let restarted = AppliedState {
+            instance: Some("a compositor that exited".to_string()),
+            ..state.applied_to("mock")
+        };
+        assert!(!restarted.applies_to(&mock));
+    }
+}
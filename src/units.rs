@@ -0,0 +1,42 @@
+//! Conversions from COSMIC's setting scales to the ones each backend expects.
+//!
+//! Backends should route values through here instead of passing raw numbers, so the
+//! same COSMIC setting feels the same on every compositor.
+
+/// Scroll speed. COSMIC stores it as a multiplier of the device's native scroll
+/// distance, where 1.0 leaves scrolling unchanged.
+pub mod scroll {
+    /// The scroll multiplier every supported backend takes. Like cosmic-comp, they all
+    /// multiply the scroll deltas libinput reports, so COSMIC's value carries over as is:
+    ///
+    /// - Sway: `input <id> scroll_factor`, see sway-input(5).
+    /// - Hyprland: `input:scroll_factor` (mice) and `input:touchpad:scroll_factor`.
+    /// - labwc: `<libinput><device><scrollFactor>`, see labwc-config(5).
+    /// - KDE: the per-device `ScrollFactor` in kcminputrc, as Plasma's "Scrolling speed"
+    ///   slider writes it.
+    ///
+    /// Negative or non-finite values are rejected by those compositors, so they fall back
+    /// to the neutral 1.0 or clamp to 0.
+    pub fn multiplier(factor: f64) -> f64 {
+        if factor.is_finite() { factor.max(0.0) } else { 1.0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::scroll;
+
+    #[test]
+    fn multiplier_passes_through_cosmic_scale() {
+        assert_eq!(scroll::multiplier(1.0), 1.0);
+        assert_eq!(scroll::multiplier(0.5), 0.5);
+        assert_eq!(scroll::multiplier(2.5), 2.5);
+    }
+
+    #[test]
+    fn multiplier_rejects_invalid_values() {
+        assert_eq!(scroll::multiplier(-1.0), 0.0);
+        assert_eq!(scroll::multiplier(f64::NAN), 1.0);
+        assert_eq!(scroll::multiplier(f64::INFINITY), 1.0);
+    }
+}
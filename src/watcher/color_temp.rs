@@ -0,0 +1,143 @@
+// Watch COSMIC Night Light Changes
+
+use std::error::Error;
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+
+use cosmic_config::{Config, ConfigGet};
+use tracing::error;
+
+use crate::event::color_temp::ColorTempEvent;
+use crate::event::{Event, Source, SourcedEvent};
+
+pub const NIGHT_LIGHT_NAMESPACE: &str = "com.system76.CosmicNightLight";
+pub const VERSION: u64 = 1;
+
+const ENABLED_KEY: &str = "enabled";
+const TEMPERATURE_KEY: &str = "temperature";
+
+/// Temperature used while COSMIC has none set.
+pub const DEFAULT_TEMPERATURE: u32 = 4500;
+
+// Both keys make up one event.
+const SOURCE: Source = Source {
+    namespace: NIGHT_LIGHT_NAMESPACE,
+    key: None,
+};
+
+#[derive(Debug, Default)]
+pub struct ColorTempState {
+    enabled: Option<bool>,
+    temperature: Option<u32>,
+}
+
+impl ColorTempState {
+    fn read(config: &Config) -> Self {
+        Self {
+            enabled: config.get(ENABLED_KEY).ok(),
+            temperature: config.get(TEMPERATURE_KEY).ok(),
+        }
+    }
+
+    /// The event for this state; `None` until `enabled` is set.
+    fn event(&self) -> Option<ColorTempEvent> {
+        Some(ColorTempEvent {
+            temperature: self.temperature.unwrap_or(DEFAULT_TEMPERATURE),
+            enabled: self.enabled?,
+        })
+    }
+
+    /// Events needed to go from `self` to `new`. A missing `enabled` key is left alone
+    /// rather than treated as off.
+    pub fn diff(&self, new: &ColorTempState) -> Vec<SourcedEvent> {
+        match new.event() {
+            Some(event) if self.event() != Some(event) => {
+                vec![SourcedEvent::new(Event::ColorTemp(event), SOURCE)]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+pub fn start_color_temp_watcher(
+    tx: &Arc<Mutex<Sender<SourcedEvent>>>,
+) -> Result<Box<dyn std::any::Any + Send>, Box<dyn Error>> {
+    let config = Config::new(NIGHT_LIGHT_NAMESPACE, VERSION)?;
+
+    let initial = ColorTempState::read(&config);
+    if let Ok(sender) = tx.lock() {
+        for event in ColorTempState::default().diff(&initial) {
+            let _ = sender.send(event);
+        }
+    }
+    let state = Arc::new(Mutex::new(initial));
+
+    let watcher = config.watch({
+        let tx = Arc::clone(tx);
+        let state = Arc::clone(&state);
+        move |cfg: &Config, _keys| {
+            let (Ok(sender), Ok(mut state)) = (tx.lock(), state.lock()) else {
+                return;
+            };
+            let new_state = ColorTempState::read(cfg);
+            for event in state.diff(&new_state) {
+                if let Err(err) = sender.send(event) {
+                    error!("Failed to send night light event: {err}");
+                }
+            }
+            *state = new_state;
+        }
+    })?;
+
+    Ok(Box::new(watcher))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(events: &[SourcedEvent]) -> Option<ColorTempEvent> {
+        match events {
+            [SourcedEvent {
+                event: Event::ColorTemp(event),
+                ..
+            }] => Some(*event),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn a_change_to_either_key_sends_one_event() {
+        let on = ColorTempState {
+            enabled: Some(true),
+            temperature: None,
+        };
+        assert_eq!(
+            event(&ColorTempState::default().diff(&on)),
+            Some(ColorTempEvent {
+                temperature: DEFAULT_TEMPERATURE,
+                enabled: true,
+            })
+        );
+
+        let warmer = ColorTempState {
+            enabled: Some(true),
+            temperature: Some(3500),
+        };
+        assert_eq!(event(&on.diff(&warmer)).map(|event| event.temperature), Some(3500));
+        let unchanged = ColorTempState {
+            enabled: Some(true),
+            temperature: Some(3500),
+        };
+        assert!(warmer.diff(&unchanged).is_empty());
+    }
+
+    #[test]
+    fn nothing_is_sent_until_enabled_is_set() {
+        let temperature_only = ColorTempState {
+            enabled: None,
+            temperature: Some(3500),
+        };
+        assert!(ColorTempState::default().diff(&temperature_only).is_empty());
+    }
+}
@@ -0,0 +1,123 @@
+// Watch COSMIC Cursor Changes
+
+use std::error::Error;
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+
+use cosmic_config::{Config, ConfigGet};
+use tracing::error;
+
+use crate::event::cursor::CursorEvent;
+use crate::event::{Event, Source, SourcedEvent};
+
+pub const COSMIC_TK_NAMESPACE: &str = "com.system76.CosmicTk";
+pub const VERSION: u64 = 1;
+
+const THEME_KEY: &str = "cursor_theme";
+const SIZE_KEY: &str = "cursor_size";
+
+#[derive(Debug, Default)]
+pub struct CursorState {
+    theme: Option<String>,
+    size: Option<u32>,
+}
+
+impl CursorState {
+    fn read(config: &Config) -> Self {
+        Self {
+            theme: config.get(THEME_KEY).ok(),
+            size: config.get(SIZE_KEY).ok(),
+        }
+    }
+
+    /// Events needed to go from `self` to `new`, theme first. Missing keys are left
+    /// alone rather than reset.
+    pub fn diff(&self, new: &CursorState) -> Vec<SourcedEvent> {
+        let mut events = Vec::new();
+        if let Some(theme) = new
+            .theme
+            .as_ref()
+            .filter(|theme| self.theme.as_ref() != Some(*theme))
+        {
+            events.push(SourcedEvent::new(
+                Event::Cursor(CursorEvent::Theme(theme.clone())),
+                source(THEME_KEY),
+            ));
+        }
+        if let Some(size) = new.size.filter(|size| self.size != Some(*size)) {
+            events.push(SourcedEvent::new(
+                Event::Cursor(CursorEvent::Size(size)),
+                source(SIZE_KEY),
+            ));
+        }
+        events
+    }
+}
+
+fn source(key: &'static str) -> Source {
+    Source {
+        namespace: COSMIC_TK_NAMESPACE,
+        key: Some(key),
+    }
+}
+
+pub fn start_cursor_watcher(
+    tx: &Arc<Mutex<Sender<SourcedEvent>>>,
+) -> Result<Box<dyn std::any::Any + Send>, Box<dyn Error>> {
+    let config = Config::new(COSMIC_TK_NAMESPACE, VERSION)?;
+
+    let initial = CursorState::read(&config);
+    if let Ok(sender) = tx.lock() {
+        for event in CursorState::default().diff(&initial) {
+            let _ = sender.send(event);
+        }
+    }
+    let state = Arc::new(Mutex::new(initial));
+
+    let watcher = config.watch({
+        let tx = Arc::clone(tx);
+        let state = Arc::clone(&state);
+        move |cfg: &Config, _keys| {
+            let (Ok(sender), Ok(mut state)) = (tx.lock(), state.lock()) else {
+                return;
+            };
+            let new_state = CursorState::read(cfg);
+            for event in state.diff(&new_state) {
+                if let Err(err) = sender.send(event) {
+                    error!("Failed to send cursor event: {err}");
+                }
+            }
+            *state = new_state;
+        }
+    })?;
+
+    Ok(Box::new(watcher))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(events: &[SourcedEvent]) -> Vec<&'static str> {
+        events.iter().map(|sourced| sourced.event.name()).collect()
+    }
+
+    #[test]
+    fn only_changed_keys_are_sent() {
+        let pop = CursorState {
+            theme: Some("Pop".to_string()),
+            size: Some(24),
+        };
+        assert_eq!(
+            names(&CursorState::default().diff(&pop)),
+            ["cursor_theme", "cursor_size"]
+        );
+
+        let larger = CursorState {
+            theme: Some("Pop".to_string()),
+            size: Some(32),
+        };
+        assert_eq!(names(&pop.diff(&larger)), ["cursor_size"]);
+        assert!(pop.diff(&CursorState::default()).is_empty());
+    }
+}
@@ -0,0 +1,211 @@
+// Watch for External Mice
+//
+// COSMIC's `DisabledOnExternalMouse` touchpad state has no equivalent on compositors
+// without libinput's `disabled_on_external_mouse` mode. For those, this watches
+// `/dev/input` for pointer hotplug and turns the state into `Disabled` while an
+// external mouse is plugged in and `Enabled` once it is gone.
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+
+use cosmic_comp_config::input::{DeviceState, InputConfig};
+use cosmic_config::Config;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tracing::{debug, error};
+
+use crate::event::input::{InputEvent, TouchpadEvent};
+use crate::event::{Event, Source, SourcedEvent};
+use crate::watcher::input::{INPUTNAMESPACE, InputClass, VERSION, read_class};
+
+const DEV_INPUT: &str = "/dev/input";
+const SYS_INPUT: &str = "/sys/class/input";
+
+// `BUS_USB` and `BUS_BLUETOOTH` from linux/input.h. Built-in touchpads and pointing
+// sticks sit on I2C, SMBus or the PS/2 port, virtual devices on `BUS_VIRTUAL`.
+const EXTERNAL_BUSES: &[u16] = &[0x03, 0x05];
+// `REL_X` and `REL_Y`: the device moves a pointer with relative motion.
+const REL_XY: u64 = 0b11;
+
+#[derive(Debug)]
+struct Shared {
+    // The backend cannot apply `DisabledOnExternalMouse` itself.
+    active: AtomicBool,
+    external_mouse: AtomicBool,
+}
+
+/// Keeps the `/dev/input` watch alive and resolves `DisabledOnExternalMouse` for the
+/// main loop. Inactive until `set_active`, so backends with their own support are not
+/// overridden.
+pub struct DeviceMonitor {
+    _watcher: RecommendedWatcher,
+    shared: Arc<Shared>,
+}
+
+impl DeviceMonitor {
+    /// Resolve `DisabledOnExternalMouse` from now on. Set from
+    /// `Compositor::disables_touchpad_on_external_mouse`, again after a backend switch.
+    pub fn set_active(&self, active: bool) {
+        self.shared.active.store(active, Ordering::Relaxed);
+    }
+
+    /// `sourced` with a `DisabledOnExternalMouse` touchpad state replaced by `Disabled` or
+    /// `Enabled`, depending on whether an external mouse is plugged in. Other events, and
+    /// every event while inactive, are returned unchanged.
+    pub fn resolve(&self, mut sourced: SourcedEvent) -> SourcedEvent {
+        if !self.shared.active.load(Ordering::Relaxed) {
+            return sourced;
+        }
+        if let Event::Input(InputEvent::TouchPad {
+            event: TouchpadEvent::State(state @ DeviceState::DisabledOnExternalMouse),
+            ..
+        }) = &mut sourced.event
+        {
+            *state = touchpad_state(self.shared.external_mouse.load(Ordering::Relaxed));
+        }
+        sourced
+    }
+}
+
+fn touchpad_state(external_mouse: bool) -> DeviceState {
+    if external_mouse {
+        DeviceState::Disabled
+    } else {
+        DeviceState::Enabled
+    }
+}
+
+/// Whether a device with this `id/bustype` and `capabilities/rel` is an external mouse.
+fn is_external_mouse(bustype: &str, rel: &str) -> bool {
+    let external =
+        u16::from_str_radix(bustype.trim(), 16).is_ok_and(|bus| EXTERNAL_BUSES.contains(&bus));
+    // Space-separated hex words, most significant first.
+    let rel = rel
+        .split_whitespace()
+        .last()
+        .and_then(|word| u64::from_str_radix(word, 16).ok())
+        .unwrap_or(0);
+    external && rel & REL_XY == REL_XY
+}
+
+/// Whether any `eventN` device under `sys` with a node in `dev` is an external mouse.
+/// Devices whose node is gone are skipped: sysfs can lag behind an unplug.
+fn external_mouse_present(sys: &Path, dev: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(sys) else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let name = entry.file_name();
+        if !name.to_string_lossy().starts_with("event") || !dev.join(&name).exists() {
+            return false;
+        }
+        let device = entry.path().join("device");
+        let read = |file: &str| fs::read_to_string(device.join(file)).unwrap_or_default();
+        is_external_mouse(&read("id/bustype"), &read("capabilities/rel"))
+    })
+}
+
+/// Whether COSMIC's touchpad state is currently `DisabledOnExternalMouse`.
+fn wants_auto_disable() -> bool {
+    Config::new(INPUTNAMESPACE, VERSION)
+        .ok()
+        .and_then(|config| read_class::<InputConfig>(&config, InputClass::Touchpad))
+        .is_some_and(|touchpad| matches!(touchpad.state, DeviceState::DisabledOnExternalMouse))
+}
+
+pub fn start_device_monitor(
+    tx: &Arc<Mutex<Sender<SourcedEvent>>>,
+) -> Result<DeviceMonitor, Box<dyn Error>> {
+    let (sys, dev) = (PathBuf::from(SYS_INPUT), PathBuf::from(DEV_INPUT));
+    let shared = Arc::new(Shared {
+        active: AtomicBool::new(false),
+        external_mouse: AtomicBool::new(external_mouse_present(&sys, &dev)),
+    });
+
+    let mut watcher = notify::recommended_watcher({
+        let tx = Arc::clone(tx);
+        let shared = Arc::clone(&shared);
+        let dev = dev.clone();
+        move |res: notify::Result<notify::Event>| {
+            let event = match res {
+                Ok(event) => event,
+                Err(err) => {
+                    error!("Device monitor error: {err}");
+                    return;
+                }
+            };
+            if !matches!(event.kind, EventKind::Create(_) | EventKind::Remove(_)) {
+                return;
+            }
+            let present = external_mouse_present(&sys, &dev);
+            if shared.external_mouse.swap(present, Ordering::Relaxed) == present {
+                return;
+            }
+            debug!(
+                "External mouse {}",
+                if present { "plugged in" } else { "removed" }
+            );
+            if !shared.active.load(Ordering::Relaxed) || !wants_auto_disable() {
+                return;
+            }
+            let state = TouchpadEvent::State(touchpad_state(present));
+            let event = SourcedEvent::new(
+                Event::Input(InputEvent::touchpad(state)),
+                Source {
+                    namespace: INPUTNAMESPACE,
+                    key: Some("input_touchpad"),
+                },
+            );
+            let Ok(sender) = tx.lock() else {
+                return;
+            };
+            if let Err(err) = sender.send(event) {
+                error!("Failed to send touchpad state event: {err}");
+            }
+        }
+    })?;
+    watcher.watch(&dev, RecursiveMode::NonRecursive)?;
+
+    Ok(DeviceMonitor {
+        _watcher: watcher,
+        shared,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_usb_and_bluetooth_pointers_are_external() {
+        assert!(is_external_mouse("0003\n", "3\n"));
+        assert!(is_external_mouse("0005", "1943"));
+        assert!(is_external_mouse("0003", "1 903"));
+        // I2C touchpad, PS/2 pointing stick, USB keyboard, uinput mouse.
+        assert!(!is_external_mouse("0018", "0"));
+        assert!(!is_external_mouse("0011", "3"));
+        assert!(!is_external_mouse("0003", "0"));
+        assert!(!is_external_mouse("0006", "3"));
+    }
+
+    #[test]
+    fn unplugged_devices_are_not_counted() {
+        let root = std::env::temp_dir().join(format!("cosmolith-devices-{}", std::process::id()));
+        let (sys, dev) = (root.join("sys"), root.join("dev"));
+        let device = sys.join("event7").join("device");
+        fs::create_dir_all(device.join("id")).unwrap();
+        fs::create_dir_all(device.join("capabilities")).unwrap();
+        fs::create_dir_all(&dev).unwrap();
+        fs::write(device.join("id/bustype"), "0003\n").unwrap();
+        fs::write(device.join("capabilities/rel"), "903\n").unwrap();
+
+        assert!(!external_mouse_present(&sys, &dev));
+        fs::write(dev.join("event7"), "").unwrap();
+        assert!(external_mouse_present(&sys, &dev));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
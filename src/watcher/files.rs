@@ -0,0 +1,68 @@
+// Fallback watch on the cosmic-config files themselves.
+//
+// Writes that bypass cosmic-config (provisioning scripts, `cp`, editors) do not always
+// reach `Config::watch` callbacks. This watches the namespace directory on disk and
+// reports the keys whose files changed, so the caller can re-read and diff them.
+
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tracing::error;
+
+/// Directory cosmic-config stores a namespace's keys in, one file per key.
+pub fn namespace_dir(namespace: &str, version: u64) -> Option<PathBuf> {
+    Some(
+        dirs::config_dir()?
+            .join("cosmic")
+            .join(namespace)
+            .join(format!("v{version}")),
+    )
+}
+
+/// Watch `namespace` on disk and call `on_change` with the keys whose files were
+/// created or modified. The returned watcher must be kept alive.
+pub fn watch_namespace<F>(
+    namespace: &str,
+    version: u64,
+    on_change: F,
+) -> Result<RecommendedWatcher, Box<dyn Error>>
+where
+    F: Fn(&[String]) + Send + 'static,
+{
+    let dir = namespace_dir(namespace, version).ok_or("Could not resolve the config directory")?;
+    // Watching requires the directory to exist; cosmic-config creates it lazily.
+    std::fs::create_dir_all(&dir)?;
+
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        let event = match res {
+            Ok(event) => event,
+            Err(err) => {
+                error!("File watcher error: {err}");
+                return;
+            }
+        };
+        if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+            return;
+        }
+
+        let keys: Vec<String> = event.paths.iter().filter_map(|path| key_name(path)).collect();
+        if !keys.is_empty() {
+            on_change(&keys);
+        }
+    })?;
+    watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+
+    Ok(watcher)
+}
+
+fn key_name(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    // Skip editor swap files and cosmic-config's atomic-write temporaries.
+    if name.starts_with('.') || name.ends_with('~') {
+        return None;
+    }
+    Some(name.to_string())
+}
@@ -3,14 +3,21 @@
 use std::{error::Error, sync::mpsc::Sender};
 
 use cosmic_comp_config::{XkbConfig, KeyboardConfig};
-use cosmic_comp_config::input::InputConfig;
+use cosmic_comp_config::input::{DeviceState, InputConfig};
 use cosmic_config::{Config, ConfigGet};
+use tracing::{error, info, warn};
 
+use super::files;
+use crate::compositor::reconnect::ReconnectPolicy;
+use crate::error::Error as CosmolithError;
+use crate::state::AppliedState;
 use crate::event::{
-    Event,
+    Event, Source, SourcedEvent,
     input::{KeyboardEvent, MouseEvent, TouchpadEvent},
 };
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 // #todo : Find all the keys linked to  com.system76.CosmicComp and catch those and read events
 // implemented
@@ -37,21 +44,155 @@ use std::sync::{Arc, Mutex};
 pub const INPUTNAMESPACE: &str = "com.system76.CosmicComp";
 pub const VERSION: u64 = 1;
 
+/// Which part of `InputState` a CosmicComp key feeds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputClass {
+    Touchpad,
+    Mouse,
+    Keyboard,
+    Numlock,
+}
+
+/// Known CosmicComp keys and the class they belong to. When upstream renames or
+/// adds a key (e.g. a dedicated `input_mouse`), add the alias here. The first alias
+/// of a class is the one read at startup; later ones are fallbacks.
+pub const KEY_ALIASES: &[(&str, InputClass)] = &[
+    ("input_touchpad", InputClass::Touchpad),
+    ("input_default", InputClass::Mouse),
+    ("input_mouse", InputClass::Mouse),
+    ("xkb_config", InputClass::Keyboard),
+    ("keyboard_config", InputClass::Numlock),
+];
+
+/// CosmicComp keys that are not input related and are deliberately not watched.
+const IGNORED_KEYS: &[&str] = &[
+    "workspaces",
+    "pinned_workspaces",
+    "input_touchpad_override",
+    "input_devices",
+    "autotile",
+    "autotile_behavior",
+    "active_hint",
+    "focus_follows_cursor",
+    "cursor_follows_focus",
+    "focus_follows_cursor_delay",
+    "descale_xwayland",
+    "xwayland_eavesdropping",
+    "edge_snap_threshold",
+    "accessibility_zoom",
+];
+
+/// Per-field XKB keys, for cosmic-config versions that do not store `xkb_config` as one
+/// value. Only read when `xkb_config` is absent.
+const XKB_FIELD_KEYS: &[&str] = &[
+    "xkb_rules",
+    "xkb_model",
+    "xkb_layout",
+    "xkb_variant",
+    "xkb_options",
+    "xkb_repeat_delay",
+    "xkb_repeat_rate",
+];
+
+pub fn input_class(key: &str) -> Option<InputClass> {
+    if XKB_FIELD_KEYS.contains(&key) {
+        return Some(InputClass::Keyboard);
+    }
+    KEY_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == key)
+        .map(|(_, class)| *class)
+}
+
+/// Source for events derived from `key`, using the static alias name.
+fn key_source(key: &str) -> Source {
+    let alias = KEY_ALIASES.iter().find(|(alias, _)| *alias == key).map(|(alias, _)| *alias);
+    Source {
+        namespace: INPUTNAMESPACE,
+        key: alias,
+    }
+}
+
+/// Source for events derived from `class` when the exact key is not known.
+fn class_source(class: InputClass) -> Source {
+    let alias = KEY_ALIASES.iter().find(|(_, c)| *c == class).map(|(alias, _)| *alias);
+    Source {
+        namespace: INPUTNAMESPACE,
+        key: alias,
+    }
+}
+
+fn sourced(events: Vec<Event>, source: Source) -> impl Iterator<Item = SourcedEvent> {
+    events.into_iter().map(move |event| SourcedEvent::new(event, source))
+}
+
+/// Read the first alias of `class` that is present in `config`.
+pub fn read_class<T: serde::de::DeserializeOwned>(config: &Config, class: InputClass) -> Option<T> {
+    KEY_ALIASES
+        .iter()
+        .filter(|(_, c)| *c == class)
+        .find_map(|(alias, _)| config.get::<T>(alias).ok())
+}
+
+/// The XKB config, from `xkb_config` or else assembled from the per-field keys.
+pub fn read_xkb(config: &Config) -> Option<XkbConfig> {
+    read_class::<XkbConfig>(config, InputClass::Keyboard)
+        .or_else(|| xkb_from_fields(|key| config.get(key).ok(), |key| config.get(key).ok()))
+}
+
+/// Build an `XkbConfig` from per-field values, with XKB defaults for missing fields.
+/// `None` when no field is set at all.
+fn xkb_from_fields(
+    string: impl Fn(&str) -> Option<String>,
+    number: impl Fn(&str) -> Option<u32>,
+) -> Option<XkbConfig> {
+    let mut xkb = XkbConfig::default();
+    let mut found = false;
+    let mut set = |field: &mut String, key: &str| {
+        if let Some(value) = string(key) {
+            *field = value;
+            found = true;
+        }
+    };
+    set(&mut xkb.rules, "xkb_rules");
+    set(&mut xkb.model, "xkb_model");
+    set(&mut xkb.layout, "xkb_layout");
+    set(&mut xkb.variant, "xkb_variant");
+    if let Some(options) = string("xkb_options") {
+        xkb.options = Some(options);
+        found = true;
+    }
+    for (field, key) in [
+        (&mut xkb.repeat_delay, "xkb_repeat_delay"),
+        (&mut xkb.repeat_rate, "xkb_repeat_rate"),
+    ] {
+        if let Some(value) = number(key) {
+            *field = value;
+            found = true;
+        }
+    }
+    found.then_some(xkb)
+}
+
 pub struct InputState {
     touchpad: Option<InputConfig>,
     mouse: Option<InputConfig>,
-    // #todo: Find which exact type is used to emit and monitor changes for this
-    // Add that here and then
-    // 1. pattern match / 2. add events / 3. impl from() / 4. Events -> Ipc Calls Mapping
+    // `xkb_config` (or its per-field keys), diffed into `KeyboardEvent`s.
     keyboard: Option<XkbConfig>,
+    // `keyboard_config`, which only carries the numlock state.
     numslock: Option<KeyboardConfig>,
+    // Times each unmapped key has been seen, so schema drift shows up in the logs.
+    unmapped: HashMap<String, u32>,
 }
 
 fn startup_keyboard_events(config: XkbConfig) -> Vec<Event> {
     KeyboardEvent::from(XkbConfig::default(), config)
 }
 
-fn send_events(tx: &Arc<Mutex<Sender<Event>>>, events: Vec<Event>) -> Result<(), Box<dyn Error>> {
+fn send_events(
+    tx: &Arc<Mutex<Sender<SourcedEvent>>>,
+    events: Vec<SourcedEvent>,
+) -> Result<(), Box<dyn Error>> {
     if let Ok(sender) = tx.lock() {
         for event in events {
             sender.send(event)?;
@@ -61,54 +202,318 @@ fn send_events(tx: &Arc<Mutex<Sender<Event>>>, events: Vec<Event>) -> Result<(),
     Ok(())
 }
 
-pub fn send_initial_input_events(tx: &Arc<Mutex<Sender<Event>>>) -> Result<(), Box<dyn Error>> {
-    let config = Config::new(INPUTNAMESPACE, VERSION)?;
+/// Send the events that bring the compositor in line with the current COSMIC config.
+///
+/// Each class is diffed against `applied`, the config cosmolith last applied, so only
+/// what changed while it was not running is sent. Classes without a snapshot get the
+/// full startup sync (see `current_input_events`).
+pub fn send_initial_input_events(
+    tx: &Arc<Mutex<Sender<SourcedEvent>>>,
+    applied: Option<&AppliedState>,
+) -> Result<(), Box<dyn Error>> {
+    let current = AppliedState::current().map_err(|err| err as Box<dyn Error>)?;
+    let applied = applied.cloned().unwrap_or_default();
+    send_events(tx, sync_events(&current, &applied))
+}
 
-    if let Ok(current_keyboard) = config.get::<XkbConfig>("xkb_config") {
-        send_events(tx, startup_keyboard_events(current_keyboard))?;
+/// Every input setting currently stored in COSMIC, as the events that would set it.
+///
+/// Keyboard and numlock settings are diffed against their defaults; touchpad and mouse
+/// settings against the same config with every optional setting cleared, which yields
+/// the composite events (`Acceleration`, `ScrollConfig`, ...) for whatever is set.
+pub fn current_input_events() -> Result<Vec<SourcedEvent>, Box<dyn Error + Send + Sync>> {
+    Ok(sync_events(&AppliedState::current()?, &AppliedState::default()))
+}
+
+/// The events that take a compositor from `applied` to `current`, class by class.
+/// Classes missing from `applied` are diffed as in `current_input_events`. The numlock
+/// state is sent whenever it is not the default.
+fn sync_events(current: &AppliedState, applied: &AppliedState) -> Vec<SourcedEvent> {
+    let mut events = Vec::new();
+    if let Some(keyboard) = current.keyboard.clone() {
+        let diff = match applied.keyboard.clone() {
+            Some(old) => KeyboardEvent::from(old, keyboard),
+            None => startup_keyboard_events(keyboard),
+        };
+        events.extend(sourced(diff, class_source(InputClass::Keyboard)));
+    }
+    if let Some(touchpad) = current.touchpad.clone() {
+        let old = applied.touchpad.clone().unwrap_or_else(|| unset(&touchpad));
+        let source = class_source(InputClass::Touchpad);
+        events.extend(sourced(TouchpadEvent::from(old, touchpad), source));
+    }
+    if let Some(mouse) = current.mouse.clone() {
+        let old = applied.mouse.clone().unwrap_or_else(|| unset(&mouse));
+        let source = class_source(InputClass::Mouse);
+        events.extend(sourced(MouseEvent::from(old, mouse), source));
     }
+    if let Some(numlock) = current.numlock.clone() {
+        // Diffed against the default even with a snapshot: `LastBoot` has to be resolved
+        // at every session start (see `compositor::numlock::target`).
+        let diff = KeyboardEvent::from_keyboard_config(KeyboardConfig::default(), numlock);
+        events.extend(sourced(diff, class_source(InputClass::Numlock)));
+    }
+    events
+}
 
-    Ok(())
+/// `config` with every optional setting cleared and the device enabled, as a compositor
+/// has it before anything is applied.
+fn unset(config: &InputConfig) -> InputConfig {
+    InputConfig {
+        state: DeviceState::Enabled,
+        acceleration: None,
+        calibration: None,
+        click_method: None,
+        disable_while_typing: None,
+        left_handed: None,
+        middle_button_emulation: None,
+        rotation_angle: None,
+        scroll_config: None,
+        tap_config: None,
+        map_to_output: None,
+        ..config.clone()
+    }
+}
+
+/// Backoff between attempts to resubscribe after one failed, e.g. while cosmic-config is
+/// restarting. Retries never stop; the cap keeps a long outage from spamming the log.
+const RESUBSCRIBE_BACKOFF: ReconnectPolicy = ReconnectPolicy {
+    max_attempts: u32::MAX,
+    base_delay: Duration::from_secs(1),
+    max_delay: Duration::from_secs(60),
+};
+
+/// Consecutive watchdog checks that must see missed changes before resubscribing, so a
+/// change that is still on its way through the watcher is not mistaken for a dead one.
+const STALE_CHECKS_BEFORE_RESTART: u32 = 2;
+
+/// The running input watchers and the state they diff against.
+pub struct InputWatcher {
+    tx: Arc<Mutex<Sender<SourcedEvent>>>,
+    state: Arc<Mutex<InputState>>,
+    // `None` after a resubscribe failed, until a retry succeeds.
+    subscriptions: Option<Box<dyn std::any::Any + Send>>,
+    stale_checks: u32,
+    // Failed resubscribe attempts in a row, and when to try the next one.
+    failed_attempts: u32,
+    retry_at: Option<Instant>,
+}
+
+impl InputWatcher {
+    /// Called periodically by the main loop, and whenever [`retry_due`](Self::retry_due).
+    ///
+    /// cosmic-config only calls back on change, so a quiet watcher is not a sign of a
+    /// dead one. Instead the current config is compared to the last state the watcher
+    /// delivered; if it keeps differing, the watcher stopped receiving notifications
+    /// (for example after a cosmic-config restart). The missed changes are then sent as
+    /// events and the watchers are recreated. If that fails it is retried with backoff,
+    /// and each failure is returned as `Error::WatcherSetup`.
+    pub fn watchdog(&mut self) -> Result<(), Box<dyn Error>> {
+        if self.subscriptions.is_none() {
+            return self.resubscribe();
+        }
+        let config = Config::new(INPUTNAMESPACE, VERSION)?;
+        let stale = self
+            .state
+            .lock()
+            .map(|state| state.missed_changes(&config))
+            .unwrap_or(false);
+        if !stale {
+            self.stale_checks = 0;
+            return Ok(());
+        }
+
+        self.stale_checks += 1;
+        if self.stale_checks < STALE_CHECKS_BEFORE_RESTART {
+            return Ok(());
+        }
+
+        warn!("Input watcher stopped delivering changes; resubscribing.");
+        // Drop the old subscriptions first so both never run at once.
+        self.subscriptions = None;
+        self.stale_checks = 0;
+        self.resubscribe()
+    }
+
+    /// Whether a failed resubscribe should be retried now.
+    pub fn retry_due(&self, now: Instant) -> bool {
+        self.retry_at.is_some_and(|retry_at| retry_at <= now)
+    }
+
+    fn resubscribe(&mut self) -> Result<(), Box<dyn Error>> {
+        let subscribed = Config::new(INPUTNAMESPACE, VERSION)
+            .map_err(|err| err.to_string())
+            .and_then(|config| {
+                // Release the state lock before sending: `forward_changes` takes the
+                // sender first.
+                let events = match self.state.lock() {
+                    Ok(mut state) => state.resync(&config),
+                    Err(_) => Vec::new(),
+                };
+                send_events(&self.tx, events).map_err(|err| err.to_string())?;
+                subscribe(&self.tx, &self.state).map_err(|err| err.to_string())
+            });
+        match subscribed {
+            Ok(subscriptions) => {
+                if self.failed_attempts > 0 {
+                    let attempts = self.failed_attempts + 1;
+                    info!("Input watcher resubscribed after {attempts} attempt(s).");
+                }
+                self.subscriptions = Some(subscriptions);
+                self.failed_attempts = 0;
+                self.retry_at = None;
+                Ok(())
+            }
+            Err(reason) => {
+                self.failed_attempts += 1;
+                let delay = RESUBSCRIBE_BACKOFF.delay(self.failed_attempts);
+                warn!("Retrying the input watcher in {delay:?}.");
+                self.retry_at = Some(Instant::now() + delay);
+                Err(CosmolithError::WatcherSetup {
+                    namespace: INPUTNAMESPACE,
+                    attempts: self.failed_attempts,
+                    reason,
+                }
+                .into())
+            }
+        }
+    }
 }
 
 pub fn start_input_watcher(
-    tx: &Arc<Mutex<Sender<Event>>>,
-) -> Result<Box<dyn std::any::Any + Send>, Box<dyn Error>> {
+    tx: &Arc<Mutex<Sender<SourcedEvent>>>,
+) -> Result<InputWatcher, Box<dyn Error>> {
     let config = Config::new(INPUTNAMESPACE, VERSION)?;
     let state = Arc::new(Mutex::new(InputState {
-        touchpad: config.get::<InputConfig>("input_touchpad").ok(),
-        mouse: config.get::<InputConfig>("input_default").ok(),
-        keyboard: config.get::<XkbConfig>("xkb_config").ok(),
-        numslock: config.get::<KeyboardConfig>("keyboard_config").ok(),
+        touchpad: read_class(&config, InputClass::Touchpad),
+        mouse: read_class(&config, InputClass::Mouse),
+        keyboard: read_xkb(&config),
+        numslock: read_class(&config, InputClass::Numlock),
+        unmapped: HashMap::new(),
     }));
+    let subscriptions = subscribe(tx, &state)?;
+
+    Ok(InputWatcher {
+        tx: Arc::clone(tx),
+        state,
+        subscriptions: Some(subscriptions),
+        stale_checks: 0,
+        failed_attempts: 0,
+        retry_at: None,
+    })
+}
+
+fn subscribe(
+    tx: &Arc<Mutex<Sender<SourcedEvent>>>,
+    state: &Arc<Mutex<InputState>>,
+) -> Result<Box<dyn std::any::Any + Send>, Box<dyn Error>> {
+    let config = Config::new(INPUTNAMESPACE, VERSION)?;
 
-    // Keep the watcher alive for the lifetime of the program.
     let watcher = config.watch({
-        let tx = Arc::clone(&tx);
-        let state = Arc::clone(&state);
-        move |cfg: &Config, keys| {
-            if let Ok(sender) = tx.lock() {
-                if let Ok(mut state) = state.lock() {
-                    let events = state.from(cfg, keys);
-                    for event in events {
-                        if let Err(err) = sender.send(event) {
-                            eprintln!("Failed to send input event: {err}");
-                        }
-                    }
+        let tx = Arc::clone(tx);
+        let state = Arc::clone(state);
+        move |cfg: &Config, keys| forward_changes(&tx, &state, cfg, keys)
+    })?;
+
+    // Fallback for files edited directly on disk. Both watchers diff against the same
+    // state, so a change seen by both only produces events once.
+    let file_watcher = files::watch_namespace(INPUTNAMESPACE, VERSION, {
+        let tx = Arc::clone(tx);
+        let state = Arc::clone(state);
+        move |keys| match Config::new(INPUTNAMESPACE, VERSION) {
+            Ok(cfg) => forward_changes(&tx, &state, &cfg, keys),
+            Err(err) => error!("Failed to re-read {INPUTNAMESPACE}: {err}"),
+        }
+    });
+    let file_watcher = match file_watcher {
+        Ok(file_watcher) => Some(file_watcher),
+        Err(err) => {
+            warn!("On-disk config watch unavailable: {err}");
+            None
+        }
+    };
+
+    Ok(Box::new((watcher, file_watcher)))
+}
+
+fn forward_changes(
+    tx: &Arc<Mutex<Sender<SourcedEvent>>>,
+    state: &Arc<Mutex<InputState>>,
+    cfg: &Config,
+    keys: &[String],
+) {
+    if let Ok(sender) = tx.lock() {
+        if let Ok(mut state) = state.lock() {
+            let events = state.from(cfg, keys);
+            for event in events {
+                if let Err(err) = sender.send(event) {
+                    error!("Failed to send input event: {err}");
                 }
             }
         }
-    })?;
-
-    Ok(Box::new(watcher))
+    }
 }
 
 impl InputState {
-    pub fn from(&mut self, cfg: &Config, keys: &[String]) -> Vec<Event> {
+    /// Whether `cfg` holds a value that differs from the last one seen for any class.
+    fn missed_changes(&self, cfg: &Config) -> bool {
+        fn differs<T: PartialEq>(current: Option<T>, seen: &Option<T>) -> bool {
+            current.is_some_and(|current| seen.as_ref() != Some(&current))
+        }
+
+        differs(read_class::<InputConfig>(cfg, InputClass::Touchpad), &self.touchpad)
+            || differs(read_class::<InputConfig>(cfg, InputClass::Mouse), &self.mouse)
+            || differs(read_xkb(cfg), &self.keyboard)
+            || differs(read_class::<KeyboardConfig>(cfg, InputClass::Numlock), &self.numslock)
+    }
+
+    /// Re-read every class from `cfg` and return the events for whatever changed.
+    fn resync(&mut self, cfg: &Config) -> Vec<SourcedEvent> {
         let mut events = Vec::new();
+        if let Some(new) = read_class::<InputConfig>(cfg, InputClass::Touchpad) {
+            if let Some(old) = self.touchpad.replace(new.clone()) {
+                let source = class_source(InputClass::Touchpad);
+                events.extend(sourced(TouchpadEvent::from(old, new), source));
+            }
+        }
+        if let Some(new) = read_class::<InputConfig>(cfg, InputClass::Mouse) {
+            if let Some(old) = self.mouse.replace(new.clone()) {
+                let source = class_source(InputClass::Mouse);
+                events.extend(sourced(MouseEvent::from(old, new), source));
+            }
+        }
+        if let Some(new) = read_xkb(cfg) {
+            if let Some(old) = self.keyboard.replace(new.clone()) {
+                let source = class_source(InputClass::Keyboard);
+                events.extend(sourced(KeyboardEvent::from(old, new), source));
+            }
+        }
+        if let Some(new) = read_class::<KeyboardConfig>(cfg, InputClass::Numlock) {
+            if let Some(old) = self.numslock.replace(new.clone()) {
+                let source = class_source(InputClass::Numlock);
+                events.extend(sourced(KeyboardEvent::from_keyboard_config(old, new), source));
+            }
+        }
+        events
+    }
+
+    pub fn from(&mut self, cfg: &Config, keys: &[String]) -> Vec<SourcedEvent> {
+        let mut sourced_events = Vec::new();
         for key in keys {
-            match key.as_str() {
-                "input_touchpad" => match cfg.get::<InputConfig>(key) {
+            let mut events = Vec::new();
+            let Some(class) = input_class(key) else {
+                if !IGNORED_KEYS.contains(&key.as_str()) {
+                    let count = self.unmapped.entry(key.clone()).or_insert(0);
+                    *count += 1;
+                    warn!(
+                        "Unmapped key in Input ({INPUTNAMESPACE}): {key} (seen {count} times). \
+                         Add it to KEY_ALIASES if it is a renamed input key."
+                    );
+                }
+                continue;
+            };
+            match class {
+                InputClass::Touchpad => match cfg.get::<InputConfig>(key) {
                     Ok(new_config) => {
                         if let Some(old) = self.touchpad.clone() {
                             events.extend(TouchpadEvent::from(old, new_config.clone()));
@@ -116,10 +521,10 @@ impl InputState {
                         self.touchpad = Some(new_config);
                     }
                     Err(e) => {
-                        eprintln!("Failed to get changed config due to the error: {:?}", e);
+                        error!("Failed to get changed config due to the error: {:?}", e);
                     }
                 },
-                "input_default" => match cfg.get::<InputConfig>(key) {
+                InputClass::Mouse => match cfg.get::<InputConfig>(key) {
                     Ok(new_config) => {
                         if let Some(old) = self.mouse.clone() {
                             events.extend(MouseEvent::from(old, new_config.clone()));
@@ -127,21 +532,22 @@ impl InputState {
                         self.mouse = Some(new_config);
                     }
                     Err(e) => {
-                        eprintln!("Failed to get changed config due to the error: {:?}", e);
+                        error!("Failed to get changed config due to the error: {:?}", e);
                     }
                 },
-                "xkb_config" => match cfg.get::<XkbConfig>(key) {
-                    Ok(new_config) => {
+                // Read through `read_xkb` so a per-field key change sees the whole config.
+                InputClass::Keyboard => match read_xkb(cfg) {
+                    Some(new_config) => {
                         if let Some(old) = self.keyboard.clone() {
                             events.extend(KeyboardEvent::from(old, new_config.clone()));
                         }
                         self.keyboard = Some(new_config);
                     }
-                    Err(e) => {
-                        eprintln!("Failed to get changed config due to the error: {:?}", e);
+                    None => {
+                        error!("Failed to read the changed XKB config from {key}");
                     }
                 },
-                "keyboard_config" => match cfg.get::<KeyboardConfig>(key) {
+                InputClass::Numlock => match cfg.get::<KeyboardConfig>(key) {
                     Ok(new_config) => {
                         if let Some(old) = self.numslock.clone() {
                             events.extend(KeyboardEvent::from_keyboard_config(old, new_config.clone()));
@@ -149,17 +555,167 @@ impl InputState {
                         self.numslock = Some(new_config);
                     }
                     Err(e) => {
-                        eprintln!("Failed to get changed config due to the error: {:?}", e);
+                        error!("Failed to get changed config due to the error: {:?}", e);
                     }
-                }
-                x => {
-                    eprintln!(
-                        "Unknown key found in Input (com.system76.CosmicComp): {}",
-                        x
-                    );
-                }
+                },
             }
+            sourced_events.extend(sourced(events, key_source(key)));
         }
-        events
+        sourced_events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmic_comp_config::NumlockState;
+    use cosmic_comp_config::input::ScrollConfig;
+
+    use super::*;
+    use crate::event::InputEvent;
+
+    fn pointer(natural_scroll: bool) -> InputConfig {
+        InputConfig {
+            state: DeviceState::Enabled,
+            acceleration: None,
+            calibration: None,
+            click_method: None,
+            disable_while_typing: None,
+            left_handed: Some(true),
+            middle_button_emulation: None,
+            rotation_angle: None,
+            scroll_config: Some(ScrollConfig {
+                method: None,
+                natural_scroll: Some(natural_scroll),
+                scroll_button: None,
+                scroll_factor: None,
+            }),
+            tap_config: None,
+            map_to_output: None,
+        }
+    }
+
+    fn names(events: &[SourcedEvent]) -> Vec<&'static str> {
+        events.iter().map(|sourced| sourced.event.name()).collect()
+    }
+
+    #[test]
+    fn a_fresh_start_sends_every_class() {
+        let current = AppliedState {
+            keyboard: Some(XkbConfig {
+                layout: "de".to_string(),
+                ..Default::default()
+            }),
+            touchpad: Some(pointer(true)),
+            mouse: Some(pointer(false)),
+            ..Default::default()
+        };
+        let names = names(&sync_events(&current, &AppliedState::default()));
+        for name in [
+            "keyboard_layout",
+            "touchpad_left_handed",
+            "touchpad_natural_scroll",
+            "mouse_left_handed",
+            "mouse_natural_scroll",
+        ] {
+            assert!(names.contains(&name), "{name} missing from {names:?}");
+        }
+    }
+
+    #[test]
+    fn a_snapshot_limits_the_sync_to_what_drifted() {
+        let applied = AppliedState {
+            touchpad: Some(pointer(false)),
+            mouse: Some(pointer(false)),
+            ..Default::default()
+        };
+        let current = AppliedState {
+            touchpad: Some(pointer(true)),
+            ..applied.clone()
+        };
+        assert_eq!(names(&sync_events(&current, &applied)), ["touchpad_natural_scroll"]);
+    }
+
+    #[test]
+    fn a_disabled_touchpad_is_synced() {
+        let current = AppliedState {
+            touchpad: Some(InputConfig {
+                state: DeviceState::Disabled,
+                ..pointer(false)
+            }),
+            ..Default::default()
+        };
+        let events = sync_events(&current, &AppliedState::default());
+        assert!(events.iter().any(|sourced| matches!(
+            sourced.event,
+            Event::Input(InputEvent::TouchPad {
+                event: TouchpadEvent::State(DeviceState::Disabled),
+                ..
+            })
+        )));
+    }
+
+    #[test]
+    fn numlock_is_restored_at_every_start() {
+        let current = AppliedState {
+            numlock: Some(KeyboardConfig {
+                numlock_state: NumlockState::LastBoot,
+            }),
+            ..Default::default()
+        };
+        // Even when the snapshot already has it.
+        let events = sync_events(&current, &current);
+        assert!(matches!(
+            &events[..],
+            [SourcedEvent {
+                event: Event::Input(InputEvent::Keyboard(KeyboardEvent::NumLock(
+                    NumlockState::LastBoot
+                ))),
+                ..
+            }]
+        ));
+        assert_eq!(events[0].source.key, Some("keyboard_config"));
+    }
+
+    #[test]
+    fn only_a_non_default_numlock_state_is_synced() {
+        let with = |numlock_state| AppliedState {
+            numlock: Some(KeyboardConfig { numlock_state }),
+            ..Default::default()
+        };
+        let fresh = AppliedState::default();
+        let synced = |state| names(&sync_events(&with(state), &fresh));
+        use NumlockState::{BootOff, BootOn, LastBoot};
+        for state in [BootOn, BootOff, LastBoot] {
+            let expected: &[&str] = if state == NumlockState::default() {
+                &[]
+            } else {
+                &["numslock_state"]
+            };
+            assert_eq!(synced(state), expected);
+        }
+    }
+
+    #[test]
+    fn xkb_fields_fill_in_over_defaults() {
+        let strings = HashMap::from([("xkb_layout", "de,us"), ("xkb_options", "caps:escape")]);
+        let numbers = HashMap::from([("xkb_repeat_rate", 40u32)]);
+        let xkb = xkb_from_fields(
+            |key| strings.get(key).map(|value| value.to_string()),
+            |key| numbers.get(key).copied(),
+        )
+        .unwrap();
+
+        let default = XkbConfig::default();
+        assert_eq!(xkb.layout, "de,us");
+        assert_eq!(xkb.options.as_deref(), Some("caps:escape"));
+        assert_eq!(xkb.repeat_rate, 40);
+        assert_eq!(xkb.rules, default.rules);
+        assert_eq!(xkb.repeat_delay, default.repeat_delay);
+    }
+
+    #[test]
+    fn no_xkb_fields_means_no_config() {
+        assert!(xkb_from_fields(|_| None, |_| None).is_none());
+        assert_eq!(input_class("xkb_layout"), Some(InputClass::Keyboard));
     }
 }
@@ -1,2 +1,8 @@
+pub mod color_temp;
+pub mod cursor;
+pub mod device_monitor;
+pub mod files;
 pub mod input;
-pub mod shortcuts;
\ No newline at end of file
+pub mod shortcuts;
+pub mod theme;
+pub mod workspaces;
\ No newline at end of file
@@ -5,17 +5,27 @@ use std::collections::HashMap;
 use cosmic_config::Config;
 use cosmic_settings_config::shortcuts::{self, Action, Binding};
 
-use crate::event::{Event, ShortcutEvent};
+use crate::event::{Event, ShortcutEvent, Source, SourcedEvent};
 
 pub const SHORTCUTS_NAMESPACE: &str = shortcuts::ID;
 pub const VERSION: u64 = 1;
 
+// Shortcuts are read from the namespace as a whole (defaults merged with custom).
+const SOURCE: Source = Source {
+    namespace: SHORTCUTS_NAMESPACE,
+    key: None,
+};
+
 pub struct ShortcutsState {
     pub shortcuts: HashMap<Binding, Action>,
 }
 
+fn shortcut_event(event: ShortcutEvent) -> SourcedEvent {
+    SourcedEvent::new(Event::Shortcut(event), SOURCE)
+}
+
 pub fn start_shortcuts_watcher(
-    tx: &Arc<Mutex<Sender<Event>>>,
+    tx: &Arc<Mutex<Sender<SourcedEvent>>>,
 ) -> Result<Box<dyn std::any::Any + Send>, Box<dyn Error>> {
     let config = Config::new(SHORTCUTS_NAMESPACE, VERSION)?;
     
@@ -27,7 +37,7 @@ pub fn start_shortcuts_watcher(
     
     if let Ok(sender) = tx.lock() {
         for (binding, action) in initial_shortcuts.iter() {
-            let _ = sender.send(Event::Shortcut(ShortcutEvent::Add {
+            let _ = sender.send(shortcut_event(ShortcutEvent::Add {
                 binding: binding.clone(),
                 shortcut: action.clone().into(),
             }));
@@ -45,7 +55,7 @@ pub fn start_shortcuts_watcher(
                     
                     for (binding, action) in old_shortcuts.iter() {
                         if !new_shortcuts.contains_key(binding) || new_shortcuts.get(binding) != Some(action) {
-                            let _ = sender.send(Event::Shortcut(ShortcutEvent::Remove {
+                            let _ = sender.send(shortcut_event(ShortcutEvent::Remove {
                                 shortcut: action.clone().into(),
                                 binding: binding.clone(),
                             }));
@@ -54,7 +64,7 @@ pub fn start_shortcuts_watcher(
                     
                     for (binding, action) in new_shortcuts.iter() {
                         if !old_shortcuts.contains_key(binding) || old_shortcuts.get(binding) != Some(action) {
-                            let _ = sender.send(Event::Shortcut(ShortcutEvent::Add {
+                            let _ = sender.send(shortcut_event(ShortcutEvent::Add {
                                 shortcut: action.clone().into(),
                                 binding: binding.clone(),
                             }));
@@ -0,0 +1,99 @@
+// Watch COSMIC Theme Mode Changes
+
+use std::error::Error;
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+
+use cosmic_config::{Config, ConfigGet};
+use tracing::error;
+
+use crate::event::theme::{ColorScheme, ThemeEvent};
+use crate::event::{Event, Source, SourcedEvent};
+
+pub const THEME_MODE_NAMESPACE: &str = "com.system76.CosmicTheme.Mode";
+pub const VERSION: u64 = 1;
+
+const IS_DARK_KEY: &str = "is_dark";
+
+const SOURCE: Source = Source {
+    namespace: THEME_MODE_NAMESPACE,
+    key: Some(IS_DARK_KEY),
+};
+
+#[derive(Debug, Default)]
+pub struct ThemeState {
+    is_dark: Option<bool>,
+}
+
+impl ThemeState {
+    fn read(config: &Config) -> Self {
+        Self {
+            is_dark: config.get(IS_DARK_KEY).ok(),
+        }
+    }
+
+    /// Events needed to go from `self` to `new`. A missing key is left alone rather
+    /// than treated as light.
+    pub fn diff(&self, new: &ThemeState) -> Vec<SourcedEvent> {
+        match new.is_dark {
+            Some(is_dark) if self.is_dark != Some(is_dark) => {
+                let event = Event::Theme(ThemeEvent::Mode(ColorScheme::from_dark(is_dark)));
+                vec![SourcedEvent::new(event, SOURCE)]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+pub fn start_theme_watcher(
+    tx: &Arc<Mutex<Sender<SourcedEvent>>>,
+) -> Result<Box<dyn std::any::Any + Send>, Box<dyn Error>> {
+    let config = Config::new(THEME_MODE_NAMESPACE, VERSION)?;
+
+    let initial = ThemeState::read(&config);
+    if let Ok(sender) = tx.lock() {
+        for event in ThemeState::default().diff(&initial) {
+            let _ = sender.send(event);
+        }
+    }
+    let state = Arc::new(Mutex::new(initial));
+
+    let watcher = config.watch({
+        let tx = Arc::clone(tx);
+        let state = Arc::clone(&state);
+        move |cfg: &Config, _keys| {
+            let (Ok(sender), Ok(mut state)) = (tx.lock(), state.lock()) else {
+                return;
+            };
+            let new_state = ThemeState::read(cfg);
+            for event in state.diff(&new_state) {
+                if let Err(err) = sender.send(event) {
+                    error!("Failed to send theme event: {err}");
+                }
+            }
+            *state = new_state;
+        }
+    })?;
+
+    Ok(Box::new(watcher))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_a_changed_mode_is_sent() {
+        let dark = ThemeState { is_dark: Some(true) };
+        let events = ThemeState::default().diff(&dark);
+        assert!(matches!(
+            events.as_slice(),
+            [SourcedEvent {
+                event: Event::Theme(ThemeEvent::Mode(ColorScheme::Dark)),
+                ..
+            }]
+        ));
+        assert!(dark.diff(&ThemeState { is_dark: Some(true) }).is_empty());
+        assert!(dark.diff(&ThemeState::default()).is_empty());
+    }
+}
@@ -0,0 +1,92 @@
+// Watch COSMIC Workspace Config Changes
+
+use std::error::Error;
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+
+use cosmic_config::{Config, ConfigGet};
+use tracing::error;
+
+use crate::event::{Event, Source, SourcedEvent};
+use crate::event::workspaces::WorkspaceEvent;
+
+pub const WORKSPACES_NAMESPACE: &str = "com.system76.CosmicWorkspaces";
+pub const VERSION: u64 = 1;
+
+const COUNT_KEY: &str = "workspace_count";
+const NAMES_KEY: &str = "workspace_names";
+
+fn source(key: &'static str) -> Source {
+    Source {
+        namespace: WORKSPACES_NAMESPACE,
+        key: Some(key),
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct WorkspacesState {
+    count: Option<u32>,
+    names: Option<Vec<String>>,
+}
+
+impl WorkspacesState {
+    fn read(config: &Config) -> Self {
+        Self {
+            count: config.get(COUNT_KEY).ok(),
+            names: config.get(NAMES_KEY).ok(),
+        }
+    }
+
+    /// Events needed to go from `self` to `new`. Keys that are missing from the new
+    /// config are left alone rather than reset.
+    pub fn diff(&self, new: &WorkspacesState) -> Vec<SourcedEvent> {
+        let mut events = Vec::new();
+        if let Some(count) = new.count {
+            if self.count != Some(count) {
+                let event = Event::Workspace(WorkspaceEvent::Count(count));
+                events.push(SourcedEvent::new(event, source(COUNT_KEY)));
+            }
+        }
+        if let Some(names) = &new.names {
+            if self.names.as_ref() != Some(names) {
+                let event = Event::Workspace(WorkspaceEvent::Names(names.clone()));
+                events.push(SourcedEvent::new(event, source(NAMES_KEY)));
+            }
+        }
+        events
+    }
+}
+
+pub fn start_workspaces_watcher(
+    tx: &Arc<Mutex<Sender<SourcedEvent>>>,
+) -> Result<Box<dyn std::any::Any + Send>, Box<dyn Error>> {
+    let config = Config::new(WORKSPACES_NAMESPACE, VERSION)?;
+
+    let initial = WorkspacesState::read(&config);
+    if let Ok(sender) = tx.lock() {
+        for event in WorkspacesState::default().diff(&initial) {
+            let _ = sender.send(event);
+        }
+    }
+    let state = Arc::new(Mutex::new(initial));
+
+    let watcher = config.watch({
+        let tx = Arc::clone(&tx);
+        let state = Arc::clone(&state);
+        move |cfg: &Config, _keys| {
+            if let Ok(sender) = tx.lock() {
+                if let Ok(mut state) = state.lock() {
+                    let new_state = WorkspacesState::read(cfg);
+                    for event in state.diff(&new_state) {
+                        if let Err(err) = sender.send(event) {
+                            error!("Failed to send workspace event: {err}");
+                        }
+                    }
+                    *state = new_state;
+                }
+            }
+        }
+    })?;
+
+    Ok(Box::new(watcher))
+}